@@ -1,4 +1,5 @@
 use y_project;
+use y_project::binary_file::FileDetails;
 pub mod memory_view;
 use memory_view::{MemoryView, MViewOutput, MViewMsg};
 
@@ -18,6 +19,8 @@ struct App {
     open_button: Controller<OpenButton>,
     // binary
     bindata: Vec<u8>,
+    // exports of the opened PE image, one function per line
+    exports: String,
     //bin_view: Component<gtk::TextView>,
     
     memory_view_component: Controller<MemoryView>,
@@ -55,7 +58,18 @@ impl SimpleComponent for App {
                 gtk::Box {
                     set_orientation: gtk::Orientation::Vertical,
                     set_spacing: 5,
-
+                    gtk::Label::new(Some("Exports")),
+                    gtk::ScrolledWindow {
+                        set_vexpand: true,
+                        set_min_content_width: 300,
+                        gtk::Label {
+                            set_selectable: true,
+                            set_xalign: 0.0,
+                            set_yalign: 0.0,
+                            #[watch]
+                            set_label: &model.exports,
+                        },
+                    },
                 },
                 // collumn 2
                 gtk::Box {
@@ -99,6 +113,7 @@ impl SimpleComponent for App {
         let model = App {
             open_button: open_button,
             bindata: bindata,
+            exports: String::new(),
             memory_view_component: memview,
         };
 
@@ -113,15 +128,44 @@ impl SimpleComponent for App {
             Msg::Open(path) => {
                 println!("* Opened file {path:?} *");
                 // save file binaru to structure
-                self.bindata = y_project::read_file(&path.into_os_string().into_string().unwrap());
-                self.memory_view_component
-                    .emit(MViewMsg::Draw(self.bindata.clone()));
+                match y_project::read_file(&path.to_string_lossy().into_owned()) {
+                    Ok(bindata) => {
+                        self.exports = export_list(&bindata);
+                        self.bindata = bindata;
+                        self.memory_view_component
+                            .emit(MViewMsg::Draw(self.bindata.clone()));
+                    }
+                    Err(error) => eprintln!("* Cannot open file {path:?}: {error} *"),
+                }
             }
             Msg::None => {}
         }
     }
 }
 
+/// Export list of a PE image: ordinal, name and address or forwarder
+fn export_list(bindata: &[u8]) -> String {
+    let file = match y_project::parse(bindata) {
+        Ok(file) => file,
+        Err(error) => return format!("{error}"),
+    };
+    let export_table = match &file.details {
+        FileDetails::Pe(pe) => match &pe.export_table {
+            Some(export_table) => export_table,
+            None => return String::from("No exports"),
+        },
+        _ => return String::from("Not a PE image"),
+    };
+    let mut lines = vec![export_table.dll_name.clone()];
+    for function in export_table.functions.iter() {
+        let name = function.name.as_deref().unwrap_or("-");
+        lines.push(match &function.forwarder {
+            Some(forwarder) => format!("{:>5} {} -> {}", function.ordinal, name, forwarder),
+            None => format!("{:>5} {} {:#x}", function.ordinal, name, function.rva),
+        });
+    }
+    lines.join("\n")
+}
 
 fn main() {
     let app = RelmApp::new("relm4.example.simple");
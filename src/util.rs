@@ -1,183 +0,0 @@
-pub mod elf_structure;
-pub mod jvm_structure;
-pub mod macho_structure;
-pub mod pe_structure;
-pub mod signature;
-use std::convert::TryInto;
-
-pub fn match_codepage<'a>(u16_codepage: u16) -> &'a str {
-    let codepage: &str;
-    match u16_codepage {
-        37 => codepage = "IBM037",
-        437 => codepage = "IBM437",
-        500 => codepage = "IBM500",
-        708 => codepage = "ASMO-708",
-        709 => codepage = "",
-        710 => codepage = "",
-        720 => codepage = "DOS-720",
-        737 => codepage = "ibm737",
-        775 => codepage = "ibm775",
-        850 => codepage = "ibm850",
-        852 => codepage = "ibm852",
-        855 => codepage = "IBM855",
-        857 => codepage = "ibm857",
-        858 => codepage = "IBM00858",
-        860 => codepage = "IBM860",
-        861 => codepage = "ibm861",
-        862 => codepage = "DOS-862",
-        863 => codepage = "IBM863",
-        864 => codepage = "IBM864",
-        865 => codepage = "IBM865",
-        866 => codepage = "cp866",
-        869 => codepage = "ibm869",
-        870 => codepage = "IBM870",
-        874 => codepage = "windows-874",
-        875 => codepage = "cp875",
-        932 => codepage = "shift_jis",
-        936 => codepage = "gb2312",
-        949 => codepage = "ks_c_5601-1987",
-        950 => codepage = "big5",
-        1026 => codepage = "IBM1026",
-        1047 => codepage = "IBM01047",
-        1140 => codepage = "IBM01140",
-        1141 => codepage = "IBM01141",
-        1142 => codepage = "IBM01142",
-        1143 => codepage = "IBM01143",
-        1144 => codepage = "IBM01144",
-        1145 => codepage = "IBM01145",
-        1146 => codepage = "IBM01146",
-        1147 => codepage = "IBM01147",
-        1148 => codepage = "IBM01148",
-        1149 => codepage = "IBM01149",
-        1200 => codepage = "utf-16",
-        1201 => codepage = "unicodeFFFE",
-        1250 => codepage = "windows-1250",
-        1251 => codepage = "windows-1251",
-        1252 => codepage = "windows-1252",
-        1253 => codepage = "windows-1253",
-        1254 => codepage = "windows-1254",
-        1255 => codepage = "windows-1255",
-        1256 => codepage = "windows-1256",
-        1257 => codepage = "windows-1257",
-        1258 => codepage = "windows-1258",
-        1361 => codepage = "Johab",
-        10000 => codepage = "macintosh",
-        10001 => codepage = "x-mac-japanese",
-        10002 => codepage = "x-mac-chinesetrad",
-        10003 => codepage = "x-mac-korean",
-        10004 => codepage = "x-mac-arabic",
-        10005 => codepage = "x-mac-hebrew",
-        10006 => codepage = "x-mac-greek",
-        10007 => codepage = "x-mac-cyrillic",
-        10008 => codepage = "x-mac-chinesesimp",
-        10010 => codepage = "x-mac-romanian",
-        10017 => codepage = "x-mac-ukrainian",
-        10021 => codepage = "x-mac-thai",
-        10029 => codepage = "x-mac-ce",
-        10079 => codepage = "x-mac-icelandic",
-        10081 => codepage = "x-mac-turkish",
-        10082 => codepage = "x-mac-croatian",
-        12000 => codepage = "utf-32",
-        12001 => codepage = "utf-32BE",
-        20000 => codepage = "x-Chinese_CNS",
-        20001 => codepage = "x-cp20001",
-        20002 => codepage = "x_Chinese-Eten",
-        20003 => codepage = "x-cp20003",
-        20004 => codepage = "x-cp20004",
-        20005 => codepage = "x-cp20005",
-        20105 => codepage = "x-IA5",
-        20106 => codepage = "x-IA5-German",
-        20107 => codepage = "x-IA5-Swedish",
-        20108 => codepage = "x-IA5-Norwegian",
-        20127 => codepage = "us-ascii",
-        20261 => codepage = "x-cp20261",
-        20269 => codepage = "x-cp20269",
-        20273 => codepage = "IBM273",
-        20277 => codepage = "IBM277",
-        20278 => codepage = "IBM278",
-        20280 => codepage = "IBM280",
-        20284 => codepage = "IBM284",
-        20285 => codepage = "IBM285",
-        20290 => codepage = "IBM290",
-        20297 => codepage = "IBM297",
-        20420 => codepage = "IBM420",
-        20423 => codepage = "IBM423",
-        20424 => codepage = "IBM424",
-        20833 => codepage = "x-EBCDIC-KoreanExtended",
-        20838 => codepage = "IBM-Thai",
-        20866 => codepage = "koi8-r",
-        20871 => codepage = "IBM871",
-        20880 => codepage = "IBM880",
-        20905 => codepage = "IBM905",
-        20924 => codepage = "IBM00924",
-        20932 => codepage = "EUC-JP",
-        20936 => codepage = "x-cp20936",
-        20949 => codepage = "x-cp20949",
-        21025 => codepage = "cp1025",
-        21866 => codepage = "koi8-u",
-        28591 => codepage = "iso-8859-1",
-        28592 => codepage = "iso-8859-2",
-        28593 => codepage = "iso-8859-3",
-        28594 => codepage = "iso-8859-4",
-        28595 => codepage = "iso-8859-5",
-        28596 => codepage = "iso-8859-6",
-        28597 => codepage = "iso-8859-7",
-        28598 => codepage = "iso-8859-8",
-        28599 => codepage = "iso-8859-9",
-        28603 => codepage = "iso-8859-13",
-        28605 => codepage = "iso-8859-15",
-        29001 => codepage = "x-Europa",
-        38598 => codepage = "iso-8859-8-i",
-        50220 => codepage = "iso-2022-jp",
-        50221 => codepage = "csISO2022JP",
-        50222 => codepage = "iso-2022-jp",
-        50225 => codepage = "iso-2022-kr",
-        50227 => codepage = "x-cp50227",
-        50229 => codepage = "",
-        50930 => codepage = "",
-        50931 => codepage = "",
-        50933 => codepage = "",
-        50935 => codepage = "",
-        50936 => codepage = "",
-        50937 => codepage = "",
-        50939 => codepage = "",
-        51932 => codepage = "euc-jp",
-        51936 => codepage = "EUC-CN",
-        51949 => codepage = "euc-kr",
-        51950 => codepage = "",
-        52936 => codepage = "hz-gb-2312",
-        54936 => codepage = "GB18030",
-        57002 => codepage = "x-iscii-de",
-        57003 => codepage = "x-iscii-be",
-        57004 => codepage = "x-iscii-ta",
-        57005 => codepage = "x-iscii-te",
-        57006 => codepage = "x-iscii-as",
-        57007 => codepage = "x-iscii-or",
-        57008 => codepage = "x-iscii-ka",
-        57009 => codepage = "x-iscii-ma",
-        57010 => codepage = "x-iscii-gu",
-        57011 => codepage = "x-iscii-pa",
-        65000 => codepage = "utf-7",
-        65001 => codepage = "utf-8",
-        _ => codepage = "Unknown",
-    }
-    codepage
-}
-
-pub fn le_to_u32(bytes: &[u8]) -> u32 {
-    let array: [u8; 4] = bytes[0..4].try_into().expect("wrong size length");
-    u32::from_le_bytes(array)
-}
-
-pub fn le_to_u16(bytes: &[u8]) -> u16 {
-    let array: [u8; 2] = bytes[0..2].try_into().expect("wrong size length");
-    u16::from_le_bytes(array)
-}
-
-pub fn le_to_usize(bytes: &[u8]) -> usize {
-    let mut array = [0u8; std::mem::size_of::<usize>()];
-    for (i, &byte) in bytes.iter().enumerate() {
-        array[i] = byte;
-    }
-    usize::from_le_bytes(array)
-}
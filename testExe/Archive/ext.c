@@ -0,0 +1,2 @@
+int ext(int x){return x+1;}
+int a_really_long_function_name_here(void){return 4;}
@@ -0,0 +1,4 @@
+int counter;
+static int helper(int x){return x*3;}
+int exported_function(int a){return helper(a)+counter;}
+const char message[]="hello";
@@ -0,0 +1,3 @@
+extern int ext(int);
+int counter;
+int use_ext(int a){return ext(a)+counter;}
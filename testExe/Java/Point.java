@@ -0,0 +1,37 @@
+package demo;
+
+import java.util.function.IntSupplier;
+
+public record Point(int x, long y) {
+    static final double RATIO = 0.25;
+
+    int sum(int[] values) {
+        IntSupplier extra = () -> x + values.length;
+        int result = 100000;
+        for (int value : values) {
+            result += value;
+        }
+        return result + extra.getAsInt();
+    }
+
+    float scaled() {
+        return x * 1.5e10f;
+    }
+
+    long shifted() {
+        return y + 1234567890123L;
+    }
+
+    static int[] squares(int count) {
+        int[] squares = new int[count];
+        for (int i = 0; i < count; i++) {
+            squares[i] = i * i;
+        }
+        return squares;
+    }
+
+    @Override
+    public String toString() {
+        return "Point " + x;
+    }
+}
@@ -0,0 +1,11 @@
+public class Try {
+    static int f(String s) {
+        try {
+            return Integer.parseInt(s);
+        } catch (NumberFormatException e) {
+            return -1;
+        } finally {
+            System.out.println("done");
+        }
+    }
+}
@@ -0,0 +1,654 @@
+use std::collections::HashSet;
+
+use crate::ar_structure::{
+    ArMember, ArObject, ArSymbol, FileInfoAr, AR_MAGIC, AR_MEMBER_HEADER_END,
+    AR_MEMBER_HEADER_SIZE, AR_THIN_MAGIC, BSD_LONG_NAME_PREFIX, BSD_SYMBOL_TABLE,
+    BSD_SYMBOL_TABLE_64, BSD_SYMBOL_TABLE_64_SORTED, BSD_SYMBOL_TABLE_SORTED, GNU_LONG_NAMES,
+    GNU_SYMBOL_TABLE, GNU_SYMBOL_TABLE_64,
+};
+use crate::binary_file::{
+    Architecture, BinaryExport, BinaryFile, Bitness, Endianness, FileDetails, Format,
+};
+use crate::error::ParseError;
+use crate::signature::SignatureDatabase;
+use crate::util::{
+    be_to_u32, be_to_u64, get_bytes, get_header, le_to_u16, le_to_u32, le_to_u64, read_c_string,
+};
+
+/// Header fields are ASCII numbers padded with spaces, an empty field is 0
+fn ascii_number(field: &[u8], radix: u32, offset: usize) -> Result<u64, ParseError> {
+    let text = std::str::from_utf8(field)
+        .map_err(|_| ParseError::InvalidData {
+            structure: "ar member header",
+            offset,
+        })?
+        .trim();
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, radix).map_err(|_| ParseError::InvalidData {
+        structure: "ar member header",
+        offset,
+    })
+}
+
+/// Reads the member header at `offset`, the name is left as stored (`/123`, `#1/20`...).
+/// Members of thin archives have no data in the archive, only their size.
+pub fn extract_ar_member(
+    bytes: &[u8],
+    offset: usize,
+    with_data: bool,
+) -> Result<ArMember<'_>, ParseError> {
+    let header = get_header(bytes, offset, AR_MEMBER_HEADER_SIZE, "ar member header")?;
+    if &header[58..60] != AR_MEMBER_HEADER_END {
+        return Err(ParseError::BadMagic {
+            format: "ar member",
+            found: header[58..60].to_vec(),
+        });
+    }
+    let size = ascii_number(&header[48..58], 10, offset)? as usize;
+    let data = if with_data {
+        get_bytes(bytes, offset + AR_MEMBER_HEADER_SIZE, size)?
+    } else {
+        &bytes[0..0]
+    };
+    Ok(ArMember {
+        name: String::from_utf8_lossy(&header[0..16])
+            .trim_end()
+            .to_string(),
+        header_offset: offset,
+        modification_time: ascii_number(&header[16..28], 10, offset)?,
+        owner_id: ascii_number(&header[28..34], 10, offset)? as u32,
+        group_id: ascii_number(&header[34..40], 10, offset)? as u32,
+        mode: ascii_number(&header[40..48], 8, offset)? as u32,
+        size,
+        data,
+    })
+}
+
+/// Resolves GNU (`/123` into the long names table, `name/`) and BSD (`#1/20`, name before
+/// the data) member names, the BSD name is removed from the member data
+pub fn resolve_member_name(member: &mut ArMember, long_names: &[u8]) -> Result<(), ParseError> {
+    if let Some(length) = member.name.strip_prefix(BSD_LONG_NAME_PREFIX) {
+        let length = ascii_number(length.as_bytes(), 10, member.header_offset)? as usize;
+        let name = get_bytes(member.data, 0, length)?;
+        member.name = String::from_utf8_lossy(name)
+            .trim_end_matches('\0')
+            .to_string();
+        member.data = &member.data[length..];
+        member.size = member.size.saturating_sub(length);
+    } else if let Some(name_offset) = member.name.strip_prefix('/') {
+        let name_offset = ascii_number(name_offset.as_bytes(), 10, member.header_offset)?;
+        let names =
+            long_names
+                .get(name_offset as usize..)
+                .ok_or(ParseError::InvalidStringIndex {
+                    table: "ar long names",
+                    index: name_offset as usize,
+                })?;
+        // GNU ends the names with "/\n", Windows with a NUL
+        let end = names
+            .iter()
+            .position(|&byte| byte == b'\n' || byte == 0)
+            .unwrap_or(names.len());
+        member.name = String::from_utf8_lossy(&names[..end])
+            .trim_end_matches('/')
+            .to_string();
+    } else {
+        member.name = member.name.trim_end_matches('/').to_string();
+    }
+    Ok(())
+}
+
+/// NUL terminated names following a symbol index, one per offset
+fn index_names(strings: &[u8], offsets: impl Iterator<Item = usize>) -> Vec<ArSymbol> {
+    strings
+        .split(|&byte| byte == 0)
+        .zip(offsets)
+        .map(|(name, member_offset)| ArSymbol {
+            name: String::from_utf8_lossy(name).to_string(),
+            member_offset,
+        })
+        .collect()
+}
+
+/// GNU `/` and `/SYM64/` members: big endian count, member offsets then names
+pub fn extract_gnu_symbol_index(
+    data: &[u8],
+    word_size: usize,
+) -> Result<Vec<ArSymbol>, ParseError> {
+    let read_word = |offset: usize| -> Result<usize, ParseError> {
+        let word = get_bytes(data, offset, word_size)?;
+        Ok(if word_size == 8 {
+            be_to_u64(word) as usize
+        } else {
+            be_to_u32(word) as usize
+        })
+    };
+    let count = read_word(0)?;
+    let offsets = get_bytes(
+        data,
+        word_size,
+        count
+            .checked_mul(word_size)
+            .ok_or(ParseError::InvalidSize {
+                structure: "ar symbol index",
+                offset: 0,
+                size: count,
+            })?,
+    )?;
+    let offsets = offsets.chunks_exact(word_size).map(|word| {
+        if word_size == 8 {
+            be_to_u64(word) as usize
+        } else {
+            be_to_u32(word) as usize
+        }
+    });
+    Ok(index_names(&data[word_size * (count + 1)..], offsets))
+}
+
+/// Windows second linker member: little endian member offsets, then symbols as 1-based
+/// indexes into these offsets, sorted by name
+pub fn extract_windows_symbol_index(data: &[u8]) -> Result<Vec<ArSymbol>, ParseError> {
+    let member_count = le_to_u32(get_bytes(data, 0, 4)?) as usize;
+    let member_offsets = get_bytes(data, 4, member_count.saturating_mul(4))?;
+    let symbol_count_offset = 4 + member_offsets.len();
+    let symbol_count = le_to_u32(get_bytes(data, symbol_count_offset, 4)?) as usize;
+    let indexes = get_bytes(
+        data,
+        symbol_count_offset + 4,
+        symbol_count.saturating_mul(2),
+    )?;
+
+    let mut offsets = Vec::new();
+    for index in indexes.chunks_exact(2) {
+        let index = le_to_u16(index) as usize;
+        let member_offset = index
+            .checked_sub(1)
+            .and_then(|index| member_offsets.get(index * 4..index * 4 + 4))
+            .ok_or(ParseError::InvalidStringIndex {
+                table: "ar linker member",
+                index,
+            })?;
+        offsets.push(le_to_u32(member_offset) as usize);
+    }
+    Ok(index_names(
+        &data[symbol_count_offset + 4 + indexes.len()..],
+        offsets.into_iter(),
+    ))
+}
+
+/// BSD `__.SYMDEF` members: ranlib entries (name offset, member offset) then the names.
+/// BSD archives are written in the byte order of the host, every current one is little endian.
+pub fn extract_bsd_symbol_index(
+    data: &[u8],
+    word_size: usize,
+) -> Result<Vec<ArSymbol>, ParseError> {
+    let read_word = |offset: usize| -> Result<usize, ParseError> {
+        let word = get_bytes(data, offset, word_size)?;
+        Ok(if word_size == 8 {
+            le_to_u64(word) as usize
+        } else {
+            le_to_u32(word) as usize
+        })
+    };
+    let ranlib_size = read_word(0)?;
+    let ranlibs = get_bytes(data, word_size, ranlib_size)?;
+    let strings_size = read_word(word_size + ranlib_size)?;
+    let strings = get_bytes(data, 2 * word_size + ranlib_size, strings_size)?;
+
+    let mut symbols = Vec::new();
+    for offset in (0..ranlibs.len() / (2 * word_size)).map(|index| index * 2 * word_size) {
+        let name_offset = read_word(word_size + offset)?;
+        symbols.push(ArSymbol {
+            name: read_c_string(strings, name_offset)?,
+            member_offset: read_word(2 * word_size + offset)?,
+        });
+    }
+    Ok(symbols)
+}
+
+impl FileInfoAr<'_> {
+    /// Member defining `symbol`, from the symbol index or else from the parsed objects
+    pub fn member_defining(&self, symbol: &str) -> Option<&str> {
+        let indexed = self
+            .symbol_index
+            .iter()
+            .filter(|entry| entry.name == symbol)
+            .find_map(|entry| {
+                self.members
+                    .iter()
+                    .find(|member| member.header_offset == entry.member_offset)
+            });
+        match indexed {
+            Some(member) => Some(&member.name),
+            None => self
+                .objects
+                .iter()
+                .find(|object| {
+                    object
+                        .file
+                        .symbols
+                        .iter()
+                        .any(|defined| defined.name == symbol)
+                        || object
+                            .file
+                            .exports
+                            .iter()
+                            .any(|export| export.name == symbol)
+                })
+                .map(|object| object.member_name.as_str()),
+        }
+    }
+}
+
+pub fn parse_ar(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let thin = bytes.starts_with(AR_THIN_MAGIC);
+    if !thin && !bytes.starts_with(AR_MAGIC) {
+        return Err(ParseError::BadMagic {
+            format: "ar",
+            found: bytes.iter().take(AR_MAGIC.len()).copied().collect(),
+        });
+    }
+
+    let mut members = Vec::new();
+    let mut symbol_index = Vec::new();
+    let mut long_names: &[u8] = &[];
+    let mut symbol_table_seen = false;
+    let mut offset = AR_MAGIC.len();
+    while offset < bytes.len() {
+        // lone padding newline at the end of some archives
+        if bytes.len() - offset < AR_MEMBER_HEADER_SIZE && bytes[offset..].trim_ascii().is_empty() {
+            break;
+        }
+        let raw_name = get_header(bytes, offset, 16, "ar member header")?;
+        let raw_name = String::from_utf8_lossy(raw_name).trim_end().to_string();
+        // the symbol index and the long names are stored even in thin archives
+        let special = raw_name == GNU_SYMBOL_TABLE
+            || raw_name == GNU_SYMBOL_TABLE_64
+            || raw_name == GNU_LONG_NAMES;
+        let mut member = extract_ar_member(bytes, offset, !thin || special)?;
+        // members are aligned on 2 bytes
+        offset += AR_MEMBER_HEADER_SIZE + member.data.len() + member.data.len() % 2;
+
+        match raw_name.as_str() {
+            // a second `/` member is the Windows linker member, the same index sorted
+            GNU_SYMBOL_TABLE if symbol_table_seen => {
+                symbol_index = extract_windows_symbol_index(member.data)?;
+            }
+            GNU_SYMBOL_TABLE => {
+                symbol_index = extract_gnu_symbol_index(member.data, 4)?;
+                symbol_table_seen = true;
+            }
+            GNU_SYMBOL_TABLE_64 => symbol_index = extract_gnu_symbol_index(member.data, 8)?,
+            GNU_LONG_NAMES => long_names = member.data,
+            _ => {
+                resolve_member_name(&mut member, long_names)?;
+                match member.name.as_str() {
+                    BSD_SYMBOL_TABLE | BSD_SYMBOL_TABLE_SORTED => {
+                        symbol_index = extract_bsd_symbol_index(member.data, 4)?
+                    }
+                    BSD_SYMBOL_TABLE_64 | BSD_SYMBOL_TABLE_64_SORTED => {
+                        symbol_index = extract_bsd_symbol_index(member.data, 8)?
+                    }
+                    _ => members.push(member),
+                }
+            }
+        }
+    }
+
+    // members that are not objects (text files, nested data) are only listed, so are
+    // nested archives, which could nest deep enough to exhaust the stack
+    let database = SignatureDatabase::builtin();
+    let mut objects = Vec::new();
+    for member in members.iter() {
+        if member.data.starts_with(AR_MAGIC) || member.data.starts_with(AR_THIN_MAGIC) {
+            continue;
+        }
+        let signature = match database.find_all(member.data).first() {
+            Some(candidate) => candidate.signature.name.clone(),
+            None => continue,
+        };
+        if let Ok(file) = crate::parse_with_signature(&signature, member.data) {
+            objects.push(ArObject {
+                member_name: member.name.clone(),
+                file,
+            });
+        }
+    }
+
+    let symbols = objects
+        .iter()
+        .flat_map(|object| object.file.symbols.iter().cloned())
+        .collect::<Vec<_>>();
+    // the symbol index lists what the library provides to the linker
+    let exports = if symbol_index.is_empty() {
+        objects
+            .iter()
+            .flat_map(|object| object.file.exports.iter().cloned())
+            .collect()
+    } else {
+        symbol_index
+            .iter()
+            .map(|symbol| BinaryExport {
+                name: symbol.name.clone(),
+                address: 0,
+            })
+            .collect::<Vec<_>>()
+    };
+    // undefined symbols resolved by another member are not imports of the library, the
+    // symbol tables of the objects list undefined symbols too so only exports count
+    let defined: HashSet<&str> = objects
+        .iter()
+        .flat_map(|object| object.file.exports.iter())
+        .chain(exports.iter())
+        .map(|export| export.name.as_str())
+        .collect();
+    let mut seen = HashSet::new();
+    let imports = objects
+        .iter()
+        .flat_map(|object| object.file.imports.iter())
+        .filter(|import| !import.library.is_empty() || !defined.contains(import.name.as_str()))
+        .filter(|import| seen.insert((import.library.clone(), import.name.clone())))
+        .cloned()
+        .collect();
+    let mut libraries: Vec<String> = Vec::new();
+    for object in objects.iter() {
+        for library in object.file.imports.iter().map(|import| &import.library) {
+            if !library.is_empty() && !libraries.contains(library) {
+                libraries.push(library.clone());
+            }
+        }
+    }
+
+    let first = objects.first().map(|object| &object.file);
+    Ok(BinaryFile {
+        format: Format::Ar,
+        architecture: first.map_or(Architecture::Unknown(0), |file| file.architecture),
+        endianness: first.map_or(Endianness::Little, |file| file.endianness),
+        bitness: first.map_or(Bitness::Bits32, |file| file.bitness),
+        entry_point: 0,
+        sections: Vec::new(),
+        segments: Vec::new(),
+        symbols,
+        libraries,
+        imports,
+        exports,
+        details: FileDetails::Ar(FileInfoAr {
+            thin,
+            members,
+            symbol_index,
+            objects,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member_header(name: &str, size: usize) -> Vec<u8> {
+        let header = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}",
+            name, 0, 0, 0, 644, size
+        );
+        [header.as_bytes(), AR_MEMBER_HEADER_END].concat()
+    }
+
+    fn archive(members: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut bytes = AR_MAGIC.to_vec();
+        for (name, data) in members {
+            bytes.extend_from_slice(&member_header(name, data.len()));
+            bytes.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                bytes.push(b'\n');
+            }
+        }
+        bytes
+    }
+
+    fn test_file(name: &str) -> Vec<u8> {
+        std::fs::read(format!(
+            "{}/../testExe/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    fn member_names<'a>(ar: &'a FileInfoAr) -> Vec<&'a str> {
+        ar.members
+            .iter()
+            .map(|member| member.name.as_str())
+            .collect()
+    }
+
+    fn index<'a>(ar: &'a FileInfoAr) -> Vec<(&'a str, usize)> {
+        ar.symbol_index
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.member_offset))
+            .collect()
+    }
+
+    fn ar_details<'a>(file: &'a BinaryFile<'a>) -> &'a FileInfoAr<'a> {
+        match &file.details {
+            FileDetails::Ar(ar) => ar,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn nested_archives_are_listed_but_not_parsed() {
+        let inner = archive(&[("a.txt/", b"text")]);
+        let bytes = archive(&[("inner.a/", &inner), ("b.txt/", b"more text")]);
+        let file = parse_ar(&bytes).unwrap();
+        let ar = ar_details(&file);
+        let names: Vec<&str> = ar
+            .members
+            .iter()
+            .map(|member| member.name.as_str())
+            .collect();
+        assert_eq!(names, ["inner.a", "b.txt"]);
+        assert!(ar.objects.is_empty());
+    }
+
+    #[test]
+    fn gnu_archives_resolve_long_names_and_parse_elf_objects() {
+        let bytes = test_file("Archive/libgnu.a");
+        let file = parse_ar(&bytes).unwrap();
+        let ar = ar_details(&file);
+        assert!(!ar.thin);
+        assert_eq!(member_names(ar), ["u.o", "a_very_long_object_file_name.o"]);
+        let offsets: Vec<usize> = ar
+            .members
+            .iter()
+            .map(|member| member.header_offset)
+            .collect();
+        assert_eq!(offsets, [0xEA, 0x626]);
+        assert_eq!(
+            index(ar),
+            [
+                ("use_ext", 0xEA),
+                ("counter", 0xEA),
+                ("ext", 0x626),
+                ("a_really_long_function_name_here", 0x626),
+            ]
+        );
+        assert_eq!(
+            ar.member_defining("ext"),
+            Some("a_very_long_object_file_name.o")
+        );
+        assert_eq!(ar.member_defining("counter"), Some("u.o"));
+        assert_eq!(ar.member_defining("printf"), None);
+
+        assert_eq!(ar.objects.len(), 2);
+        assert_eq!(ar.objects[1].file.format, Format::Elf);
+        assert_eq!(file.architecture, Architecture::X86_64);
+        assert!(file.symbols.iter().any(|symbol| symbol.name == "use_ext"));
+        let exports: Vec<&str> = file
+            .exports
+            .iter()
+            .map(|export| export.name.as_str())
+            .collect();
+        assert_eq!(
+            exports,
+            [
+                "use_ext",
+                "counter",
+                "ext",
+                "a_really_long_function_name_here"
+            ]
+        );
+        // u.o calls ext, which the other member defines
+        assert!(file.imports.is_empty());
+    }
+
+    #[test]
+    fn bsd_archives_read_names_before_the_data_and_parse_macho_objects() {
+        let bytes = test_file("Archive/libmacho.a");
+        let file = parse_ar(&bytes).unwrap();
+        let ar = ar_details(&file);
+        assert_eq!(member_names(ar), ["hello_world_object_file.o"]);
+        // the name is no longer part of the data
+        assert_eq!(ar.members[0].data, test_file("MacOS/hello.o"));
+        assert_eq!(index(ar), [("_main", 0x68)]);
+        assert_eq!(ar.members[0].header_offset, 0x68);
+        assert_eq!(
+            ar.member_defining("_main"),
+            Some("hello_world_object_file.o")
+        );
+
+        assert_eq!(ar.objects[0].file.format, Format::MachO);
+        assert_eq!(file.architecture, Architecture::AArch64);
+        assert!(file.symbols.iter().any(|symbol| symbol.name == "_main"));
+        assert_eq!(file.exports.len(), 1);
+        assert_eq!(file.exports[0].name, "_main");
+    }
+
+    #[test]
+    fn windows_libraries_use_the_second_linker_member() {
+        let bytes = test_file("Archive/libwin.lib");
+        let file = parse_ar(&bytes).unwrap();
+        let ar = ar_details(&file);
+        assert_eq!(
+            member_names(ar),
+            ["u.obj", "a_very_long_object_file_name.obj"]
+        );
+        // sorted by name, unlike the first linker member
+        assert_eq!(
+            index(ar),
+            [
+                ("counter", 0x15E),
+                ("exported_function", 0x3A0),
+                ("message", 0x3A0),
+                ("use_ext", 0x15E),
+            ]
+        );
+        assert_eq!(
+            ar.member_defining("message"),
+            Some("a_very_long_object_file_name.obj")
+        );
+
+        assert_eq!(ar.objects[0].file.format, Format::Coff);
+        assert_eq!(file.architecture, Architecture::X86_64);
+        assert!(file
+            .symbols
+            .iter()
+            .any(|symbol| symbol.name == "exported_function"));
+        // ext is defined in no member
+        let imports: Vec<&str> = file
+            .imports
+            .iter()
+            .map(|import| import.name.as_str())
+            .collect();
+        assert_eq!(imports, ["ext"]);
+    }
+
+    #[test]
+    fn member_defining_falls_back_to_the_objects() {
+        let mut bytes = test_file("Archive/libgnu.a");
+        // drop the symbol index by renaming it to an ordinary member
+        bytes[AR_MAGIC.len()..AR_MAGIC.len() + 2].copy_from_slice(b"x/");
+        let file = parse_ar(&bytes).unwrap();
+        let ar = ar_details(&file);
+        assert!(ar.symbol_index.is_empty());
+        assert_eq!(
+            ar.member_defining("ext"),
+            Some("a_very_long_object_file_name.o")
+        );
+        assert_eq!(ar.member_defining("use_ext"), Some("u.o"));
+    }
+
+    #[test]
+    fn deeply_nested_archives_do_not_overflow_the_stack() {
+        // each archive is the only member of the next one, all sizes are even
+        let depth = 20_000;
+        let mut headers = Vec::new();
+        let mut size = AR_MAGIC.len();
+        for _ in 0..depth {
+            headers.push([AR_MAGIC, &member_header("nested.a/", size)].concat());
+            size += AR_MAGIC.len() + AR_MEMBER_HEADER_SIZE;
+        }
+        let mut bytes: Vec<u8> = headers.into_iter().rev().flatten().collect();
+        bytes.extend_from_slice(AR_MAGIC);
+        let file = parse_ar(&bytes).unwrap();
+        assert_eq!(ar_details(&file).members.len(), 1);
+    }
+
+    #[test]
+    fn truncated_archives_are_rejected() {
+        let bytes = archive(&[("a.txt/", b"text"), ("b.txt/", b"more text")]);
+        assert!(parse_ar(&bytes[..AR_MAGIC.len() - 1]).is_err());
+        // cut anywhere but between the members
+        let first_member_end = AR_MAGIC.len() + AR_MEMBER_HEADER_SIZE + 4;
+        for length in
+            (AR_MAGIC.len() + 1..bytes.len() - 1).filter(|&length| length != first_member_end)
+        {
+            assert!(parse_ar(&bytes[..length]).is_err(), "length {}", length);
+        }
+    }
+
+    #[test]
+    fn hostile_member_headers_are_rejected() {
+        let mut bytes = archive(&[("a.txt/", b"text")]);
+        bytes[AR_MAGIC.len() + 48] = b'x';
+        assert!(matches!(
+            parse_ar(&bytes),
+            Err(ParseError::InvalidData {
+                structure: "ar member header",
+                ..
+            })
+        ));
+
+        let bytes = archive(&[("/9999", b"text")]);
+        assert!(matches!(
+            parse_ar(&bytes),
+            Err(ParseError::InvalidStringIndex {
+                table: "ar long names",
+                index: 9999
+            })
+        ));
+
+        // BSD name longer than the member
+        let bytes = archive(&[("#1/64", b"short")]);
+        assert!(matches!(
+            parse_ar(&bytes),
+            Err(ParseError::OutOfRange { .. })
+        ));
+
+        let bytes = archive(&[("a.txt/", &[0; 99_999])]);
+        assert!(matches!(
+            parse_ar(&bytes[..AR_MAGIC.len() + AR_MEMBER_HEADER_SIZE + 10]),
+            Err(ParseError::OutOfRange { size: 99_999, .. })
+        ));
+    }
+
+    #[test]
+    fn symbol_index_counts_are_bounded() {
+        // a GNU index announcing more offsets than it holds
+        let mut index = 0x4000_0000u32.to_be_bytes().to_vec();
+        index.extend_from_slice(&[0; 8]);
+        let bytes = archive(&[("/", &index)]);
+        assert!(parse_ar(&bytes).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::binary_file::BinaryFile;
+
+pub const AR_MAGIC: &[u8] = b"!<arch>\n";
+/// Thin archives only reference their members, which stay in their own files
+pub const AR_THIN_MAGIC: &[u8] = b"!<thin>\n";
+pub const AR_MEMBER_HEADER_SIZE: usize = 60;
+pub const AR_MEMBER_HEADER_END: &[u8] = b"`\n";
+
+/// GNU and Windows symbol index (the first linker member of a .lib), big endian
+pub const GNU_SYMBOL_TABLE: &str = "/";
+pub const GNU_SYMBOL_TABLE_64: &str = "/SYM64/";
+pub const GNU_LONG_NAMES: &str = "//";
+pub const BSD_SYMBOL_TABLE: &str = "__.SYMDEF";
+pub const BSD_SYMBOL_TABLE_SORTED: &str = "__.SYMDEF SORTED";
+pub const BSD_SYMBOL_TABLE_64: &str = "__.SYMDEF_64";
+pub const BSD_SYMBOL_TABLE_64_SORTED: &str = "__.SYMDEF_64 SORTED";
+/// BSD long names are stored at the start of the member data, the length follows the prefix
+pub const BSD_LONG_NAME_PREFIX: &str = "#1/";
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ArMember<'a> {
+    pub name: String,
+    pub header_offset: usize,
+    pub modification_time: u64,
+    pub owner_id: u32,
+    pub group_id: u32,
+    pub mode: u32,
+    pub size: usize,
+    /// Empty for the members of thin archives
+    pub data: &'a [u8],
+}
+
+/// Symbol index entry, the member defining the symbol starts at `member_offset`
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ArSymbol {
+    pub name: String,
+    pub member_offset: usize,
+}
+
+/// Member recognised as an object file and parsed on its own
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ArObject<'a> {
+    pub member_name: String,
+    pub file: BinaryFile<'a>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FileInfoAr<'a> {
+    pub thin: bool,
+    /// Every member but the symbol index and the long names table
+    pub members: Vec<ArMember<'a>>,
+    pub symbol_index: Vec<ArSymbol>,
+    pub objects: Vec<ArObject<'a>>,
+}
@@ -0,0 +1,149 @@
+use crate::ar_structure::FileInfoAr;
+use crate::elf_structure::FileInfoELF;
+use crate::jvm_structure::ClassFile;
+use crate::macho_structure::FileInfoMachO;
+use crate::pe_structure::{COFFObject, ImportObjectHeader, PEFile, TEFile};
+use crate::zip_structure::FileInfoZip;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Pe,
+    /// COFF object files, the input of the Microsoft linker
+    Coff,
+    /// UEFI Terse Executables, PE images with most headers stripped
+    Te,
+    Elf,
+    MachO,
+    JavaClass,
+    /// ZIP archives and the JARs built on them
+    Zip,
+    /// Static libraries, `.a` and Windows `.lib`
+    Ar,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Architecture {
+    X86,
+    X86_64,
+    Arm,
+    AArch64,
+    Mips,
+    PowerPC,
+    PowerPC64,
+    RiscV,
+    /// Java bytecode
+    Jvm,
+    Unknown(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bitness {
+    Bits32,
+    Bits64,
+}
+
+/// Section as seen by every front end, whatever the file format is.
+/// Addresses are virtual addresses (image base already applied for PE).
+#[derive(Debug)]
+pub struct BinarySection<'a> {
+    pub name: String,
+    pub virtual_address: u64,
+    pub virtual_size: u64,
+    pub file_offset: u64,
+    pub file_size: u64,
+    pub raw_data: &'a [u8],
+}
+
+#[derive(Debug)]
+pub struct BinarySegment<'a> {
+    pub name: String,
+    pub virtual_address: u64,
+    pub virtual_size: u64,
+    pub file_offset: u64,
+    pub file_size: u64,
+    pub raw_data: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Object,
+    Section,
+    File,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct BinarySymbol {
+    pub name: String,
+    /// Filled by `demangle_symbols`, the parsers never demangle on their own
+    pub demangled_name: Option<String>,
+    pub kind: SymbolKind,
+    pub address: u64,
+    pub size: u64,
+}
+
+/// Demangling hook, returns `None` for names the demangler does not understand
+pub type Demangler = fn(&str) -> Option<String>;
+
+/// Demangles every symbol name with an external demangler (C++, Rust, Swift...)
+pub fn demangle_symbols(file: &mut BinaryFile, demangler: Demangler) {
+    for symbol in file.symbols.iter_mut() {
+        symbol.demangled_name = demangler(&symbol.name);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BinaryImport {
+    /// Empty when the format does not bind imports to a library (ELF)
+    pub library: String,
+    pub name: String,
+    /// Address the code calls through (PLT stub, IAT slot...), 0 when unknown
+    pub address: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct BinaryExport {
+    pub name: String,
+    pub address: u64,
+}
+
+/// Format specific structures the unified model was built from
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum FileDetails<'a> {
+    Pe(PEFile<'a>),
+    Coff(COFFObject<'a>),
+    /// Short import object of a Windows import library
+    ImportObject(ImportObjectHeader),
+    Te(TEFile<'a>),
+    Elf(FileInfoELF<'a>),
+    MachO(FileInfoMachO<'a>),
+    Jvm(ClassFile<'a>),
+    Zip(FileInfoZip<'a>),
+    Ar(FileInfoAr<'a>),
+}
+
+#[derive(Debug)]
+pub struct BinaryFile<'a> {
+    pub format: Format,
+    pub architecture: Architecture,
+    pub endianness: Endianness,
+    pub bitness: Bitness,
+    pub entry_point: u64,
+    pub sections: Vec<BinarySection<'a>>,
+    pub segments: Vec<BinarySegment<'a>>,
+    pub symbols: Vec<BinarySymbol>,
+    /// Shared libraries the file depends on (DLLs, DT_NEEDED, dylibs)
+    pub libraries: Vec<String>,
+    pub imports: Vec<BinaryImport>,
+    pub exports: Vec<BinaryExport>,
+    pub details: FileDetails<'a>,
+}
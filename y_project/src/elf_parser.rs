@@ -0,0 +1,1785 @@
+use crate::binary_file::{
+    Architecture, BinaryExport, BinaryFile, BinaryImport, BinarySection, BinarySegment,
+    BinarySymbol, Bitness, Endianness, FileDetails, Format, SymbolKind,
+};
+use crate::elf_structure::ELFDynamicEntry;
+use crate::elf_structure::ELFCoreMappedFile;
+use crate::elf_structure::ELFDynamicSection;
+use crate::elf_structure::ELFGnuProperty;
+use crate::elf_structure::ELFHeader;
+use crate::elf_structure::ELFIdentification;
+use crate::elf_structure::ELFNote;
+use crate::elf_structure::ELFNoteContent;
+use crate::elf_structure::ELFPltEntry;
+use crate::elf_structure::ELFProgramHeader;
+use crate::elf_structure::ELFProgramHeaderTable;
+use crate::elf_structure::ELFRelocation;
+use crate::elf_structure::ELFRelocationTable;
+use crate::elf_structure::ELFSection;
+use crate::elf_structure::ELFSectionTable;
+use crate::elf_structure::ELFSymbol;
+use crate::elf_structure::ELFSymbolTable;
+use crate::elf_structure::FileInfoELF;
+use crate::elf_structure::{
+    PT_DYNAMIC, PT_GNU_EH_FRAME, PT_GNU_PROPERTY, PT_GNU_RELRO, PT_GNU_STACK, PT_HIOS, PT_HIPROC,
+    PT_INTERP, PT_LOAD, PT_LOOS, PT_LOPROC, PT_NOTE, PT_NULL, PT_PHDR, PT_SHLIB, PT_TLS,
+};
+use crate::elf_structure::{
+    DT_FINI, DT_FINI_ARRAY, DT_FINI_ARRAYSZ, DT_FLAGS, DT_FLAGS_1, DT_GNU_HASH, DT_HASH, DT_INIT,
+    DT_INIT_ARRAY, DT_INIT_ARRAYSZ, DT_NEEDED, DT_NULL, DT_PREINIT_ARRAY, DT_PREINIT_ARRAYSZ,
+    DT_RPATH, DT_RUNPATH, DT_SONAME, DT_STRSZ, DT_STRTAB,
+};
+use crate::elf_structure::{
+    EM_386, EM_AARCH64, EM_X86_64, ET_REL, R_386_GLOB_DAT, R_386_JMP_SLOT, R_AARCH64_GLOB_DAT,
+    R_AARCH64_JUMP_SLOT, R_X86_64_GLOB_DAT, R_X86_64_JUMP_SLOT,
+};
+use crate::elf_structure::{
+    SHN_UNDEF, SHN_XINDEX, SHT_DYNAMIC, SHT_DYNSYM, SHT_NOBITS, SHT_NOTE, SHT_NULL, SHT_REL,
+    SHT_RELA, SHT_STRTAB, SHT_SYMTAB,
+};
+use crate::elf_structure::{
+    STB_GLOBAL, STB_WEAK, STT_FILE, STT_FUNC, STT_GNU_IFUNC, STT_OBJECT, STT_SECTION, STT_TLS,
+};
+use crate::elf_structure::{
+    GNU_PROPERTY_AARCH64_FEATURE_1_AND, GNU_PROPERTY_AARCH64_FEATURE_1_BTI,
+    GNU_PROPERTY_AARCH64_FEATURE_1_PAC, GNU_PROPERTY_NO_COPY_ON_PROTECTED, GNU_PROPERTY_STACK_SIZE,
+    GNU_PROPERTY_X86_FEATURE_1_AND, GNU_PROPERTY_X86_FEATURE_1_IBT,
+    GNU_PROPERTY_X86_FEATURE_1_SHSTK,
+};
+use crate::elf_structure::{
+    NT_ARM_PAC_MASK, NT_AUXV, NT_FILE, NT_GNU_ABI_TAG, NT_GNU_BUILD_ID, NT_GNU_GOLD_VERSION,
+    NT_GNU_PROPERTY_TYPE_0, NT_PRFPREG, NT_PRPSINFO, NT_PRSTATUS, NT_SIGINFO, NT_TASKSTRUCT,
+    NT_X86_XSTATE,
+};
+use crate::error::ParseError;
+use crate::util::{
+    get_bytes, get_header, le_to_u32, read_c_string, read_u16, read_u32, read_u64, read_uint,
+};
+
+const ELF32_SECTION_HEADER_SIZE: usize = 40;
+const ELF64_SECTION_HEADER_SIZE: usize = 64;
+const ELF32_PROGRAM_HEADER_SIZE: usize = 32;
+const ELF64_PROGRAM_HEADER_SIZE: usize = 56;
+const ELF32_SYMBOL_SIZE: usize = 16;
+const ELF64_SYMBOL_SIZE: usize = 24;
+
+pub fn extract_elf_identification(bytes: &[u8]) -> Result<ELFIdentification<'_>, ParseError> {
+    let bytes = get_header(bytes, 0, 16, "ELF identification")?;
+    if &bytes[0..4] != b"\x7FELF" {
+        return Err(ParseError::BadMagic {
+            format: "ELF",
+            found: bytes[0..4].to_vec(),
+        });
+    }
+    Ok(ELFIdentification {
+        magic: &bytes[0..4],
+        class: &bytes[4..5],
+        data: &bytes[5..6],
+        version: &bytes[6..7],
+        os_abi: &bytes[7..8],
+        abi_version: &bytes[8..9],
+        padding: &bytes[9..16],
+    })
+}
+
+pub fn extract_elf_header<'a>(
+    bytes: &'a [u8],
+    identification: &ELFIdentification,
+) -> Result<ELFHeader<'a>, ParseError> {
+    if identification.class == b"\x01" {
+        let bytes = get_header(bytes, 0, 52, "ELF header")?;
+        Ok(ELFHeader {
+            file_type: &bytes[16..18],
+            machine: &bytes[18..20],
+            version: &bytes[20..24],
+            entry_point: &bytes[24..28],
+            program_header_offset: &bytes[28..32],
+            section_header_offset: &bytes[32..36],
+            flags: &bytes[36..40],
+            header_size: &bytes[40..42],
+            program_header_entry_size: &bytes[42..44],
+            program_header_entry_count: &bytes[44..46],
+            section_header_entry_size: &bytes[46..48],
+            section_header_entry_count: &bytes[48..50],
+            section_name_string_table_index: &bytes[50..52],
+        })
+    } else {
+        let bytes = get_header(bytes, 0, 64, "ELF header")?;
+        Ok(ELFHeader {
+            file_type: &bytes[16..18],
+            machine: &bytes[18..20],
+            version: &bytes[20..24],
+            entry_point: &bytes[24..32],
+            program_header_offset: &bytes[32..40],
+            section_header_offset: &bytes[40..48],
+            flags: &bytes[48..52],
+            header_size: &bytes[52..54],
+            program_header_entry_size: &bytes[54..56],
+            program_header_entry_count: &bytes[56..58],
+            section_header_entry_size: &bytes[58..60],
+            section_header_entry_count: &bytes[60..62],
+            section_name_string_table_index: &bytes[62..64],
+        })
+    }
+}
+
+fn is_elf64(identification: &ELFIdentification) -> bool {
+    identification.class == b"\x02"
+}
+
+/// EI_DATA 2 is ELFDATA2MSB, every multi-byte field after `e_ident` follows it
+pub fn elf_endianness(identification: &ELFIdentification) -> Endianness {
+    if identification.data == b"\x02" {
+        Endianness::Big
+    } else {
+        Endianness::Little
+    }
+}
+
+/// Strings of `.shstrtab`, `.strtab` and `.dynstr` are NUL terminated and indexed by byte offset
+pub fn elf_string(
+    string_table: &[u8],
+    index: usize,
+    table: &'static str,
+) -> Result<String, ParseError> {
+    if index == 0 && string_table.is_empty() {
+        return Ok(String::new());
+    }
+    if index >= string_table.len() {
+        return Err(ParseError::InvalidStringIndex { table, index });
+    }
+    read_c_string(string_table, index)
+}
+
+fn extract_elf_section<'a>(
+    bytes: &'a [u8],
+    is_64: bool,
+    endianness: Endianness,
+    offset: usize,
+) -> Result<ELFSection<'a>, ParseError> {
+    let mut section = if is_64 {
+        let header = get_header(bytes, offset, ELF64_SECTION_HEADER_SIZE, "ELF section header")?;
+        ELFSection {
+            name: String::new(),
+            name_offset: read_u32(&header[0..4], endianness),
+            section_type: read_u32(&header[4..8], endianness),
+            flags: read_u64(&header[8..16], endianness),
+            address: read_u64(&header[16..24], endianness),
+            offset: read_u64(&header[24..32], endianness),
+            size: read_u64(&header[32..40], endianness),
+            link: read_u32(&header[40..44], endianness),
+            info: read_u32(&header[44..48], endianness),
+            address_align: read_u64(&header[48..56], endianness),
+            entry_size: read_u64(&header[56..64], endianness),
+            raw_data: &[],
+        }
+    } else {
+        let header = get_header(bytes, offset, ELF32_SECTION_HEADER_SIZE, "ELF section header")?;
+        ELFSection {
+            name: String::new(),
+            name_offset: read_u32(&header[0..4], endianness),
+            section_type: read_u32(&header[4..8], endianness),
+            flags: read_u32(&header[8..12], endianness) as u64,
+            address: read_u32(&header[12..16], endianness) as u64,
+            offset: read_u32(&header[16..20], endianness) as u64,
+            size: read_u32(&header[20..24], endianness) as u64,
+            link: read_u32(&header[24..28], endianness),
+            info: read_u32(&header[28..32], endianness),
+            address_align: read_u32(&header[32..36], endianness) as u64,
+            entry_size: read_u32(&header[36..40], endianness) as u64,
+            raw_data: &[],
+        }
+    };
+    // SHT_NOBITS sections (.bss) take no room in the file
+    if section.section_type != SHT_NOBITS && section.section_type != SHT_NULL {
+        section.raw_data = get_bytes(bytes, section.offset as usize, section.size as usize)?;
+    }
+    Ok(section)
+}
+
+pub fn extract_elf_section_table<'a>(
+    bytes: &'a [u8],
+    identification: &ELFIdentification,
+    header: &ELFHeader,
+) -> Result<ELFSectionTable<'a>, ParseError> {
+    let mut section_table = ELFSectionTable {
+        sections: Vec::new(),
+    };
+    let endianness = elf_endianness(identification);
+    let table_offset = read_uint(header.section_header_offset, endianness) as usize;
+    if table_offset == 0 {
+        return Ok(section_table);
+    }
+
+    let is_64 = is_elf64(identification);
+    let header_size = if is_64 {
+        ELF64_SECTION_HEADER_SIZE
+    } else {
+        ELF32_SECTION_HEADER_SIZE
+    };
+    let entry_size =
+        (read_uint(header.section_header_entry_size, endianness) as usize).max(header_size);
+
+    // with more than SHN_LORESERVE sections the real count and string table index
+    // are stored in the first section header
+    let first_section = extract_elf_section(bytes, is_64, endianness, table_offset)?;
+    let mut section_count = read_uint(header.section_header_entry_count, endianness) as usize;
+    if section_count == 0 {
+        section_count = first_section.size as usize;
+    }
+    let mut names_index = read_u16(header.section_name_string_table_index, endianness) as usize;
+    if names_index == SHN_XINDEX as usize {
+        names_index = first_section.link as usize;
+    }
+    get_header(
+        bytes,
+        table_offset,
+        section_count.saturating_mul(entry_size),
+        "ELF section header table",
+    )?;
+
+    for i in 0..section_count {
+        section_table
+            .sections
+            .push(extract_elf_section(
+                bytes,
+                is_64,
+                endianness,
+                table_offset + i * entry_size,
+            )?);
+    }
+
+    if names_index != SHN_UNDEF as usize {
+        let names = section_table
+            .sections
+            .get(names_index)
+            .ok_or(ParseError::InvalidStringIndex {
+                table: "ELF section header table",
+                index: names_index,
+            })?
+            .raw_data;
+        for section in section_table.sections.iter_mut() {
+            section.name = elf_string(names, section.name_offset as usize, ".shstrtab")?;
+        }
+    }
+    Ok(section_table)
+}
+
+/// Name of a segment type, OS and processor specific types without a name are given as an
+/// offset from PT_LOOS or PT_LOPROC
+pub fn segment_type_name(segment_type: u32) -> String {
+    let name = match segment_type {
+        PT_NULL => "PT_NULL",
+        PT_LOAD => "PT_LOAD",
+        PT_DYNAMIC => "PT_DYNAMIC",
+        PT_INTERP => "PT_INTERP",
+        PT_NOTE => "PT_NOTE",
+        PT_SHLIB => "PT_SHLIB",
+        PT_PHDR => "PT_PHDR",
+        PT_TLS => "PT_TLS",
+        PT_GNU_EH_FRAME => "PT_GNU_EH_FRAME",
+        PT_GNU_STACK => "PT_GNU_STACK",
+        PT_GNU_RELRO => "PT_GNU_RELRO",
+        PT_GNU_PROPERTY => "PT_GNU_PROPERTY",
+        PT_LOOS..=PT_HIOS => return format!("PT_LOOS+{:#x}", segment_type - PT_LOOS),
+        PT_LOPROC..=PT_HIPROC => return format!("PT_LOPROC+{:#x}", segment_type - PT_LOPROC),
+        _ => return format!("{:#x}", segment_type),
+    };
+    name.to_string()
+}
+
+fn extract_elf_program_header<'a>(
+    bytes: &'a [u8],
+    is_64: bool,
+    endianness: Endianness,
+    offset: usize,
+) -> Result<ELFProgramHeader<'a>, ParseError> {
+    let mut program_header = if is_64 {
+        let header = get_header(bytes, offset, ELF64_PROGRAM_HEADER_SIZE, "ELF program header")?;
+        ELFProgramHeader {
+            segment_type: read_u32(&header[0..4], endianness),
+            flags: read_u32(&header[4..8], endianness),
+            offset: read_u64(&header[8..16], endianness),
+            virtual_address: read_u64(&header[16..24], endianness),
+            physical_address: read_u64(&header[24..32], endianness),
+            file_size: read_u64(&header[32..40], endianness),
+            memory_size: read_u64(&header[40..48], endianness),
+            align: read_u64(&header[48..56], endianness),
+            raw_data: &[],
+        }
+    } else {
+        let header = get_header(bytes, offset, ELF32_PROGRAM_HEADER_SIZE, "ELF program header")?;
+        ELFProgramHeader {
+            segment_type: read_u32(&header[0..4], endianness),
+            offset: read_u32(&header[4..8], endianness) as u64,
+            virtual_address: read_u32(&header[8..12], endianness) as u64,
+            physical_address: read_u32(&header[12..16], endianness) as u64,
+            file_size: read_u32(&header[16..20], endianness) as u64,
+            memory_size: read_u32(&header[20..24], endianness) as u64,
+            flags: read_u32(&header[24..28], endianness),
+            align: read_u32(&header[28..32], endianness) as u64,
+            raw_data: &[],
+        }
+    };
+    program_header.raw_data = get_bytes(
+        bytes,
+        program_header.offset as usize,
+        program_header.file_size as usize,
+    )?;
+    Ok(program_header)
+}
+
+pub fn extract_elf_program_header_table<'a>(
+    bytes: &'a [u8],
+    identification: &ELFIdentification,
+    header: &ELFHeader,
+) -> Result<ELFProgramHeaderTable<'a>, ParseError> {
+    let mut program_header_table = ELFProgramHeaderTable {
+        program_headers: Vec::new(),
+    };
+    let endianness = elf_endianness(identification);
+    let table_offset = read_uint(header.program_header_offset, endianness) as usize;
+    if table_offset == 0 {
+        return Ok(program_header_table);
+    }
+
+    let is_64 = is_elf64(identification);
+    let header_size = if is_64 {
+        ELF64_PROGRAM_HEADER_SIZE
+    } else {
+        ELF32_PROGRAM_HEADER_SIZE
+    };
+    let entry_size =
+        (read_uint(header.program_header_entry_size, endianness) as usize).max(header_size);
+    let entry_count = read_uint(header.program_header_entry_count, endianness) as usize;
+    get_header(
+        bytes,
+        table_offset,
+        entry_count * entry_size,
+        "ELF program header table",
+    )?;
+
+    for i in 0..entry_count {
+        program_header_table
+            .program_headers
+            .push(extract_elf_program_header(
+                bytes,
+                is_64,
+                endianness,
+                table_offset + i * entry_size,
+            )?);
+    }
+    Ok(program_header_table)
+}
+
+/// Maps a virtual address to a file offset through the PT_LOAD segments, addresses
+/// only present in memory (.bss) have no file offset
+pub fn virtual_address_to_offset(
+    program_header_table: &ELFProgramHeaderTable,
+    address: u64,
+) -> Option<u64> {
+    program_header_table
+        .program_headers
+        .iter()
+        .filter(|program_header| program_header.segment_type == PT_LOAD)
+        .find(|program_header| {
+            address >= program_header.virtual_address
+                && address - program_header.virtual_address < program_header.file_size
+        })
+        .map(|program_header| program_header.offset + (address - program_header.virtual_address))
+}
+
+fn extract_elf_symbol(symbol: &[u8], is_64: bool, endianness: Endianness) -> ELFSymbol {
+    let (info, other, value, size, section_index) = if is_64 {
+        (
+            symbol[4],
+            symbol[5],
+            read_u64(&symbol[8..16], endianness),
+            read_u64(&symbol[16..24], endianness),
+            read_u16(&symbol[6..8], endianness),
+        )
+    } else {
+        (
+            symbol[12],
+            symbol[13],
+            read_u32(&symbol[4..8], endianness) as u64,
+            read_u32(&symbol[8..12], endianness) as u64,
+            read_u16(&symbol[14..16], endianness),
+        )
+    };
+    ELFSymbol {
+        name: String::new(),
+        name_offset: read_u32(&symbol[0..4], endianness),
+        value,
+        size,
+        binding: info >> 4,
+        symbol_type: info & 0xF,
+        visibility: other & 0x3,
+        section_index,
+    }
+}
+
+/// Reads every symbol of the first section of type `symbol_table_type` (SHT_SYMTAB or
+/// SHT_DYNSYM), names come from the string table in the section `link` field
+pub fn extract_elf_symbol_table(
+    identification: &ELFIdentification,
+    section_table: &ELFSectionTable,
+    symbol_table_type: u32,
+) -> Result<ELFSymbolTable, ParseError> {
+    let mut symbol_table = ELFSymbolTable {
+        symbols: Vec::new(),
+    };
+    let symbol_section = match section_table
+        .sections
+        .iter()
+        .find(|section| section.section_type == symbol_table_type)
+    {
+        Some(section) => section,
+        None => return Ok(symbol_table),
+    };
+    let names = section_table
+        .sections
+        .get(symbol_section.link as usize)
+        .ok_or(ParseError::InvalidStringIndex {
+            table: "ELF section header table",
+            index: symbol_section.link as usize,
+        })?
+        .raw_data;
+
+    let is_64 = is_elf64(identification);
+    let endianness = elf_endianness(identification);
+    let symbol_size = if is_64 {
+        ELF64_SYMBOL_SIZE
+    } else {
+        ELF32_SYMBOL_SIZE
+    };
+    let table_name = if symbol_table_type == SHT_DYNSYM {
+        ".dynstr"
+    } else {
+        ".strtab"
+    };
+    for raw_symbol in symbol_section.raw_data.chunks_exact(symbol_size) {
+        let mut symbol = extract_elf_symbol(raw_symbol, is_64, endianness);
+        symbol.name = elf_string(names, symbol.name_offset as usize, table_name)?;
+        symbol_table.symbols.push(symbol);
+    }
+    Ok(symbol_table)
+}
+
+fn dynamic_value(entries: &[ELFDynamicEntry], tag: u64) -> Option<u64> {
+    entries
+        .iter()
+        .find(|entry| entry.tag == tag)
+        .map(|entry| entry.value)
+}
+
+/// DT_STRTAB is a virtual address, the section table is only a fallback for files
+/// without program headers
+fn dynamic_string_table<'a>(
+    bytes: &'a [u8],
+    section_table: &ELFSectionTable<'a>,
+    program_header_table: &ELFProgramHeaderTable,
+    entries: &[ELFDynamicEntry],
+) -> Result<&'a [u8], ParseError> {
+    let address = match dynamic_value(entries, DT_STRTAB) {
+        Some(address) => address,
+        None => return Ok(&[]),
+    };
+    if let Some(offset) = virtual_address_to_offset(program_header_table, address) {
+        let size = dynamic_value(entries, DT_STRSZ).unwrap_or(0);
+        return get_bytes(bytes, offset as usize, size as usize);
+    }
+    Ok(section_table
+        .sections
+        .iter()
+        .find(|section| section.section_type == SHT_STRTAB && section.address == address)
+        .map_or(&[], |section| section.raw_data))
+}
+
+/// Reads DT_INIT_ARRAY like arrays of pointers
+fn dynamic_address_array(
+    bytes: &[u8],
+    is_64: bool,
+    endianness: Endianness,
+    program_header_table: &ELFProgramHeaderTable,
+    entries: &[ELFDynamicEntry],
+    array_tag: u64,
+    size_tag: u64,
+) -> Result<Vec<u64>, ParseError> {
+    let (address, size) = match (
+        dynamic_value(entries, array_tag),
+        dynamic_value(entries, size_tag),
+    ) {
+        (Some(address), Some(size)) => (address, size),
+        _ => return Ok(Vec::new()),
+    };
+    let offset = match virtual_address_to_offset(program_header_table, address) {
+        Some(offset) => offset,
+        None => return Ok(Vec::new()),
+    };
+    let data = get_bytes(bytes, offset as usize, size as usize)?;
+    Ok(if is_64 {
+        data.chunks_exact(8).map(|pointer| read_u64(pointer, endianness)).collect()
+    } else {
+        data
+            .chunks_exact(4)
+            .map(|pointer| read_u32(pointer, endianness) as u64)
+            .collect()
+    })
+}
+
+pub fn extract_elf_dynamic_section(
+    bytes: &[u8],
+    identification: &ELFIdentification,
+    section_table: &ELFSectionTable,
+    program_header_table: &ELFProgramHeaderTable,
+) -> Result<Option<ELFDynamicSection>, ParseError> {
+    let dynamic_data = program_header_table
+        .program_headers
+        .iter()
+        .find(|program_header| program_header.segment_type == PT_DYNAMIC)
+        .map(|program_header| program_header.raw_data)
+        .or_else(|| {
+            section_table
+                .sections
+                .iter()
+                .find(|section| section.section_type == SHT_DYNAMIC)
+                .map(|section| section.raw_data)
+        });
+    let dynamic_data = match dynamic_data {
+        Some(dynamic_data) => dynamic_data,
+        None => return Ok(None),
+    };
+
+    let is_64 = is_elf64(identification);
+    let endianness = elf_endianness(identification);
+    let mut dynamic_section = ELFDynamicSection::default();
+    let entry_size = if is_64 { 16 } else { 8 };
+    for raw_entry in dynamic_data.chunks_exact(entry_size) {
+        let entry = if is_64 {
+            ELFDynamicEntry {
+                tag: read_u64(&raw_entry[0..8], endianness),
+                value: read_u64(&raw_entry[8..16], endianness),
+            }
+        } else {
+            ELFDynamicEntry {
+                tag: read_u32(&raw_entry[0..4], endianness) as u64,
+                value: read_u32(&raw_entry[4..8], endianness) as u64,
+            }
+        };
+        if entry.tag == DT_NULL {
+            break;
+        }
+        dynamic_section.entries.push(entry);
+    }
+
+    let strings = dynamic_string_table(
+        bytes,
+        section_table,
+        program_header_table,
+        &dynamic_section.entries,
+    )?;
+    for entry in dynamic_section.entries.iter() {
+        match entry.tag {
+            DT_NEEDED => dynamic_section
+                .needed
+                .push(elf_string(strings, entry.value as usize, ".dynstr")?),
+            DT_SONAME => {
+                dynamic_section.soname = Some(elf_string(strings, entry.value as usize, ".dynstr")?)
+            }
+            DT_RPATH => {
+                dynamic_section.rpath = Some(elf_string(strings, entry.value as usize, ".dynstr")?)
+            }
+            DT_RUNPATH => {
+                dynamic_section.runpath = Some(elf_string(strings, entry.value as usize, ".dynstr")?)
+            }
+            DT_INIT => dynamic_section.init = Some(entry.value),
+            DT_FINI => dynamic_section.fini = Some(entry.value),
+            DT_FLAGS => dynamic_section.flags = entry.value,
+            DT_FLAGS_1 => dynamic_section.flags_1 = entry.value,
+            DT_HASH => dynamic_section.hash = Some(entry.value),
+            DT_GNU_HASH => dynamic_section.gnu_hash = Some(entry.value),
+            _ => {}
+        }
+    }
+
+    let entries = &dynamic_section.entries;
+    dynamic_section.init_array = dynamic_address_array(
+        bytes,
+        is_64,
+        endianness,
+        program_header_table,
+        entries,
+        DT_INIT_ARRAY,
+        DT_INIT_ARRAYSZ,
+    )?;
+    dynamic_section.fini_array = dynamic_address_array(
+        bytes,
+        is_64,
+        endianness,
+        program_header_table,
+        entries,
+        DT_FINI_ARRAY,
+        DT_FINI_ARRAYSZ,
+    )?;
+    dynamic_section.preinit_array = dynamic_address_array(
+        bytes,
+        is_64,
+        endianness,
+        program_header_table,
+        entries,
+        DT_PREINIT_ARRAY,
+        DT_PREINIT_ARRAYSZ,
+    )?;
+    Ok(Some(dynamic_section))
+}
+
+pub fn relocation_type_name(machine: u16, relocation_type: u32) -> &'static str {
+    match (machine, relocation_type) {
+        (EM_X86_64, 0) => "R_X86_64_NONE",
+        (EM_X86_64, 1) => "R_X86_64_64",
+        (EM_X86_64, 2) => "R_X86_64_PC32",
+        (EM_X86_64, 3) => "R_X86_64_GOT32",
+        (EM_X86_64, 4) => "R_X86_64_PLT32",
+        (EM_X86_64, 5) => "R_X86_64_COPY",
+        (EM_X86_64, 6) => "R_X86_64_GLOB_DAT",
+        (EM_X86_64, 7) => "R_X86_64_JUMP_SLOT",
+        (EM_X86_64, 8) => "R_X86_64_RELATIVE",
+        (EM_X86_64, 9) => "R_X86_64_GOTPCREL",
+        (EM_X86_64, 10) => "R_X86_64_32",
+        (EM_X86_64, 11) => "R_X86_64_32S",
+        (EM_X86_64, 16) => "R_X86_64_DTPMOD64",
+        (EM_X86_64, 17) => "R_X86_64_DTPOFF64",
+        (EM_X86_64, 18) => "R_X86_64_TPOFF64",
+        (EM_X86_64, 37) => "R_X86_64_IRELATIVE",
+        (EM_X86_64, 41) => "R_X86_64_GOTPCRELX",
+        (EM_X86_64, 42) => "R_X86_64_REX_GOTPCRELX",
+        (EM_386, 0) => "R_386_NONE",
+        (EM_386, 1) => "R_386_32",
+        (EM_386, 2) => "R_386_PC32",
+        (EM_386, 3) => "R_386_GOT32",
+        (EM_386, 4) => "R_386_PLT32",
+        (EM_386, 5) => "R_386_COPY",
+        (EM_386, 6) => "R_386_GLOB_DAT",
+        (EM_386, 7) => "R_386_JMP_SLOT",
+        (EM_386, 8) => "R_386_RELATIVE",
+        (EM_386, 9) => "R_386_GOTOFF",
+        (EM_386, 10) => "R_386_GOTPC",
+        (EM_386, 14) => "R_386_TLS_TPOFF",
+        (EM_386, 35) => "R_386_TLS_DTPMOD32",
+        (EM_386, 36) => "R_386_TLS_DTPOFF32",
+        (EM_386, 42) => "R_386_IRELATIVE",
+        (EM_386, 43) => "R_386_GOT32X",
+        (EM_AARCH64, 0) => "R_AARCH64_NONE",
+        (EM_AARCH64, 257) => "R_AARCH64_ABS64",
+        (EM_AARCH64, 258) => "R_AARCH64_ABS32",
+        (EM_AARCH64, 261) => "R_AARCH64_PREL32",
+        (EM_AARCH64, 275) => "R_AARCH64_ADR_PREL_PG_HI21",
+        (EM_AARCH64, 277) => "R_AARCH64_ADD_ABS_LO12_NC",
+        (EM_AARCH64, 282) => "R_AARCH64_JUMP26",
+        (EM_AARCH64, 283) => "R_AARCH64_CALL26",
+        (EM_AARCH64, 286) => "R_AARCH64_LDST64_ABS_LO12_NC",
+        (EM_AARCH64, 311) => "R_AARCH64_ADR_GOT_PAGE",
+        (EM_AARCH64, 312) => "R_AARCH64_LD64_GOT_LO12_NC",
+        (EM_AARCH64, 1024) => "R_AARCH64_COPY",
+        (EM_AARCH64, 1025) => "R_AARCH64_GLOB_DAT",
+        (EM_AARCH64, 1026) => "R_AARCH64_JUMP_SLOT",
+        (EM_AARCH64, 1027) => "R_AARCH64_RELATIVE",
+        (EM_AARCH64, 1028) => "R_AARCH64_TLS_DTPMOD",
+        (EM_AARCH64, 1029) => "R_AARCH64_TLS_DTPREL",
+        (EM_AARCH64, 1030) => "R_AARCH64_TLS_TPREL",
+        (EM_AARCH64, 1031) => "R_AARCH64_TLSDESC",
+        (EM_AARCH64, 1032) => "R_AARCH64_IRELATIVE",
+        _ => "Unknown",
+    }
+}
+
+/// Reads every SHT_REL and SHT_RELA section, symbol names come from the symbol table
+/// in the section `link` field
+pub fn extract_elf_relocation_tables(
+    identification: &ELFIdentification,
+    section_table: &ELFSectionTable,
+    symbol_table: &ELFSymbolTable,
+    dynamic_symbol_table: &ELFSymbolTable,
+) -> Vec<ELFRelocationTable> {
+    let is_64 = is_elf64(identification);
+    let endianness = elf_endianness(identification);
+    let mut relocation_tables = Vec::new();
+
+    for section in section_table
+        .sections
+        .iter()
+        .filter(|section| section.section_type == SHT_REL || section.section_type == SHT_RELA)
+    {
+        let has_addend = section.section_type == SHT_RELA;
+        let entry_size = match (is_64, has_addend) {
+            (true, true) => 24,
+            (true, false) => 16,
+            (false, true) => 12,
+            (false, false) => 8,
+        };
+        let symbols = match section_table
+            .sections
+            .get(section.link as usize)
+            .map(|symbol_section| symbol_section.section_type)
+        {
+            Some(SHT_DYNSYM) => dynamic_symbol_table,
+            _ => symbol_table,
+        };
+
+        let mut relocation_table = ELFRelocationTable {
+            section_name: section.name.clone(),
+            relocations: Vec::new(),
+        };
+        for entry in section.raw_data.chunks_exact(entry_size) {
+            let (offset, symbol_index, relocation_type, addend) = if is_64 {
+                let info = read_u64(&entry[8..16], endianness);
+                (
+                    read_u64(&entry[0..8], endianness),
+                    (info >> 32) as u32,
+                    info as u32,
+                    has_addend.then(|| read_u64(&entry[16..24], endianness) as i64),
+                )
+            } else {
+                let info = read_u32(&entry[4..8], endianness);
+                (
+                    read_u32(&entry[0..4], endianness) as u64,
+                    info >> 8,
+                    info & 0xFF,
+                    has_addend.then(|| read_u32(&entry[8..12], endianness) as i32 as i64),
+                )
+            };
+            let symbol_name = if symbol_index == 0 {
+                None
+            } else {
+                symbols
+                    .symbols
+                    .get(symbol_index as usize)
+                    .map(|symbol| symbol.name.clone())
+            };
+            relocation_table.relocations.push(ELFRelocation {
+                offset,
+                symbol_index,
+                relocation_type,
+                addend,
+                symbol_name,
+            });
+        }
+        relocation_tables.push(relocation_table);
+    }
+    relocation_tables
+}
+
+fn is_import_relocation(machine: u16, relocation_type: u32) -> bool {
+    matches!(
+        (machine, relocation_type),
+        (EM_X86_64, R_X86_64_JUMP_SLOT)
+            | (EM_X86_64, R_X86_64_GLOB_DAT)
+            | (EM_386, R_386_JMP_SLOT)
+            | (EM_386, R_386_GLOB_DAT)
+            | (EM_AARCH64, R_AARCH64_JUMP_SLOT)
+            | (EM_AARCH64, R_AARCH64_GLOB_DAT)
+    )
+}
+
+/// GOT slot read by the indirect jump of a x86 PLT stub, `jmp *disp(%rip)` for x86-64,
+/// `jmp *addr` or `jmp *disp(%ebx)` for i386
+fn x86_plt_got_address(stub: &[u8], address: u64, is_64: bool, got_plt_address: u64) -> Option<u64> {
+    (0..stub.len().saturating_sub(5)).find_map(|i| {
+        let displacement = le_to_u32(&stub[i + 2..i + 6]);
+        match (stub[i], stub[i + 1], is_64) {
+            (0xFF, 0x25, true) => Some(
+                address
+                    .wrapping_add(i as u64 + 6)
+                    .wrapping_add(displacement as i32 as i64 as u64),
+            ),
+            (0xFF, 0x25, false) => Some(displacement as u64),
+            (0xFF, 0xA3, false) => {
+                Some((got_plt_address as u32).wrapping_add(displacement) as u64)
+            }
+            _ => None,
+        }
+    })
+}
+
+/// GOT slot loaded by an AArch64 PLT stub: `adrp x16, page` then `ldr x17, [x16, #offset]`
+fn aarch64_plt_got_address(stub: &[u8], address: u64) -> Option<u64> {
+    if stub.len() < 8 {
+        return None;
+    }
+    let adrp = le_to_u32(&stub[0..4]);
+    let ldr = le_to_u32(&stub[4..8]);
+    if adrp & 0x9F00_0000 != 0x9000_0000 || ldr & 0xFFC0_0000 != 0xF940_0000 {
+        return None;
+    }
+    let immediate = (((adrp >> 5) & 0x7FFFF) << 2 | ((adrp >> 29) & 0x3)) as u64;
+    // sign extend the 21 bits page count
+    let pages = ((immediate << 43) as i64 >> 43) << 12;
+    let page = (address & !0xFFF).wrapping_add(pages as u64);
+    Some(page.wrapping_add((((ldr >> 10) & 0xFFF) as u64) * 8))
+}
+
+/// Resolves the stubs of .plt, .plt.sec and .plt.got to the imported symbols through
+/// the JUMP_SLOT and GLOB_DAT relocations of their GOT slot
+pub fn extract_elf_plt_entries(
+    machine: u16,
+    identification: &ELFIdentification,
+    section_table: &ELFSectionTable,
+    relocation_tables: &[ELFRelocationTable],
+) -> Vec<ELFPltEntry> {
+    let is_64 = is_elf64(identification);
+    let got_plt_address = section_table
+        .sections
+        .iter()
+        .find(|section| section.name == ".got.plt")
+        .map_or(0, |section| section.address);
+    let mut plt_entries = Vec::new();
+
+    for section in section_table
+        .sections
+        .iter()
+        .filter(|section| matches!(section.name.as_str(), ".plt" | ".plt.sec" | ".plt.got"))
+    {
+        let stub_size = if section.name == ".plt.got" && machine != EM_AARCH64 {
+            8
+        } else {
+            16
+        };
+        for (index, stub) in section.raw_data.chunks(stub_size).enumerate() {
+            let address = section.address.wrapping_add((index * stub_size) as u64);
+            let got_address = match machine {
+                EM_X86_64 | EM_386 => x86_plt_got_address(stub, address, is_64, got_plt_address),
+                EM_AARCH64 => aarch64_plt_got_address(stub, address),
+                _ => None,
+            };
+            let got_address = match got_address {
+                Some(got_address) => got_address,
+                None => continue,
+            };
+            let name = relocation_tables
+                .iter()
+                .flat_map(|relocation_table| relocation_table.relocations.iter())
+                .filter(|relocation| is_import_relocation(machine, relocation.relocation_type))
+                .find(|relocation| relocation.offset == got_address)
+                .and_then(|relocation| relocation.symbol_name.clone());
+            if let Some(name) = name {
+                plt_entries.push(ELFPltEntry {
+                    address,
+                    got_address,
+                    name,
+                });
+            }
+        }
+    }
+    plt_entries
+}
+
+/// OS field of NT_GNU_ABI_TAG
+fn gnu_abi_tag_os(os: u32) -> &'static str {
+    match os {
+        0 => "Linux",
+        1 => "GNU/Hurd",
+        2 => "Solaris",
+        3 => "FreeBSD",
+        _ => "Unknown",
+    }
+}
+
+pub fn core_note_type_name(note_type: u32) -> &'static str {
+    match note_type {
+        NT_PRSTATUS => "NT_PRSTATUS",
+        NT_PRFPREG => "NT_PRFPREG",
+        NT_PRPSINFO => "NT_PRPSINFO",
+        NT_TASKSTRUCT => "NT_TASKSTRUCT",
+        NT_AUXV => "NT_AUXV",
+        NT_X86_XSTATE => "NT_X86_XSTATE",
+        NT_ARM_PAC_MASK => "NT_ARM_PAC_MASK",
+        NT_SIGINFO => "NT_SIGINFO",
+        NT_FILE => "NT_FILE",
+        _ => "Unknown",
+    }
+}
+
+/// Properties are padded to 8 bytes in ELFCLASS64 files and to 4 bytes in ELFCLASS32 ones
+fn extract_elf_gnu_properties<'a>(
+    descriptor: &'a [u8],
+    machine: u16,
+    is_64: bool,
+    endianness: Endianness,
+) -> Result<Vec<ELFGnuProperty<'a>>, ParseError> {
+    let align = if is_64 { 8 } else { 4 };
+    let mut properties = Vec::new();
+    let mut offset = 0;
+    while offset + 8 <= descriptor.len() {
+        let property_type = read_u32(&descriptor[offset..offset + 4], endianness);
+        let data_size = read_u32(&descriptor[offset + 4..offset + 8], endianness) as usize;
+        let data = get_bytes(descriptor, offset + 8, data_size)?;
+        let is_x86 = machine == EM_386 || machine == EM_X86_64;
+        let property = match property_type {
+            // the stack size is a pointer sized integer, as wide as the alignment
+            GNU_PROPERTY_STACK_SIZE if data.len() >= align => {
+                ELFGnuProperty::StackSize(read_uint(&data[..align], endianness))
+            }
+            GNU_PROPERTY_NO_COPY_ON_PROTECTED => ELFGnuProperty::NoCopyOnProtected,
+            GNU_PROPERTY_X86_FEATURE_1_AND if is_x86 && data.len() >= 4 => {
+                let features = read_u32(&data[0..4], endianness);
+                ELFGnuProperty::X86Feature1 {
+                    ibt: features & GNU_PROPERTY_X86_FEATURE_1_IBT != 0,
+                    shstk: features & GNU_PROPERTY_X86_FEATURE_1_SHSTK != 0,
+                }
+            }
+            GNU_PROPERTY_AARCH64_FEATURE_1_AND if machine == EM_AARCH64 && data.len() >= 4 => {
+                let features = read_u32(&data[0..4], endianness);
+                ELFGnuProperty::AArch64Feature1 {
+                    bti: features & GNU_PROPERTY_AARCH64_FEATURE_1_BTI != 0,
+                    pac: features & GNU_PROPERTY_AARCH64_FEATURE_1_PAC != 0,
+                }
+            }
+            _ => ELFGnuProperty::Other {
+                property_type,
+                data,
+            },
+        };
+        properties.push(property);
+        offset = (offset + 8 + data_size).next_multiple_of(align);
+    }
+    Ok(properties)
+}
+
+/// NT_FILE: count and page size, `count` (start, end, page offset) triples then `count` names
+fn extract_elf_core_mapped_files(
+    descriptor: &[u8],
+    is_64: bool,
+    endianness: Endianness,
+) -> Result<Vec<ELFCoreMappedFile>, ParseError> {
+    let word_size = if is_64 { 8 } else { 4 };
+    let word = |index: usize| -> Result<u64, ParseError> {
+        Ok(read_uint(get_bytes(descriptor, index * word_size, word_size)?, endianness))
+    };
+    let count = word(0)? as usize;
+    let page_size = word(1)?;
+    let mut names_offset = count
+        .checked_mul(3)
+        .and_then(|words| words.checked_add(2))
+        .and_then(|words| words.checked_mul(word_size))
+        .ok_or(ParseError::OutOfRange {
+            offset: 2 * word_size,
+            size: count,
+            file_size: descriptor.len(),
+        })?;
+    let mut mapped_files = Vec::new();
+    for i in 0..count {
+        // advance over the raw bytes, a lossy decoded name can be longer than them
+        let names = descriptor.get(names_offset..).ok_or(ParseError::OutOfRange {
+            offset: names_offset,
+            size: 1,
+            file_size: descriptor.len(),
+        })?;
+        let name_length = names.iter().position(|&byte| byte == 0).unwrap_or(names.len());
+        let name = String::from_utf8_lossy(&names[..name_length]).to_string();
+        names_offset += name_length + 1;
+        mapped_files.push(ELFCoreMappedFile {
+            start: word(2 + i * 3)?,
+            end: word(3 + i * 3)?,
+            file_offset: word(4 + i * 3)?.wrapping_mul(page_size),
+            name,
+        });
+    }
+    Ok(mapped_files)
+}
+
+/// Walks the Elf_Nhdr records of one PT_NOTE segment or SHT_NOTE section
+fn extract_elf_note_entries<'a>(
+    data: &'a [u8],
+    align: usize,
+    machine: u16,
+    is_64: bool,
+    endianness: Endianness,
+) -> Result<Vec<ELFNote<'a>>, ParseError> {
+    let mut notes = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let note_header = get_header(data, offset, 12, "ELF note header")?;
+        let name_size = read_u32(&note_header[0..4], endianness) as usize;
+        let descriptor_size = read_u32(&note_header[4..8], endianness) as usize;
+        let note_type = read_u32(&note_header[8..12], endianness);
+        let name_bytes = get_bytes(data, offset + 12, name_size)?;
+        let name = String::from_utf8_lossy(name_bytes)
+            .trim_end_matches('\0')
+            .to_string();
+        let descriptor_offset = (offset + 12 + name_size).next_multiple_of(align);
+        let descriptor = get_bytes(data, descriptor_offset, descriptor_size)?;
+
+        let content = match (name.as_str(), note_type) {
+            ("GNU", NT_GNU_BUILD_ID) => ELFNoteContent::GnuBuildId(
+                descriptor.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            ),
+            ("GNU", NT_GNU_ABI_TAG) if descriptor.len() >= 16 => ELFNoteContent::GnuAbiTag {
+                os: gnu_abi_tag_os(read_u32(&descriptor[0..4], endianness)),
+                major: read_u32(&descriptor[4..8], endianness),
+                minor: read_u32(&descriptor[8..12], endianness),
+                patch: read_u32(&descriptor[12..16], endianness),
+            },
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => ELFNoteContent::GnuProperties(
+                extract_elf_gnu_properties(descriptor, machine, is_64, endianness)?,
+            ),
+            ("GNU", NT_GNU_GOLD_VERSION) => {
+                ELFNoteContent::GnuGoldVersion(read_c_string(descriptor, 0).unwrap_or_default())
+            }
+            ("CORE", NT_FILE) => ELFNoteContent::CoreMappedFiles(extract_elf_core_mapped_files(
+                descriptor, is_64, endianness,
+            )?),
+            ("CORE" | "LINUX", _) => ELFNoteContent::Core(core_note_type_name(note_type)),
+            _ => ELFNoteContent::Unknown,
+        };
+        notes.push(ELFNote {
+            name,
+            note_type,
+            descriptor,
+            content,
+        });
+        offset = (descriptor_offset + descriptor_size).next_multiple_of(align);
+    }
+    Ok(notes)
+}
+
+/// Notes are read from the PT_NOTE segments, relocatable files have no segments so
+/// their SHT_NOTE sections are used instead
+pub fn extract_elf_notes<'a>(
+    machine: u16,
+    identification: &ELFIdentification,
+    section_table: &ELFSectionTable<'a>,
+    program_header_table: &ELFProgramHeaderTable<'a>,
+) -> Result<Vec<ELFNote<'a>>, ParseError> {
+    let is_64 = is_elf64(identification);
+    let endianness = elf_endianness(identification);
+    let mut note_blocks: Vec<(&[u8], u64)> = program_header_table
+        .program_headers
+        .iter()
+        .filter(|program_header| program_header.segment_type == PT_NOTE)
+        .map(|program_header| (program_header.raw_data, program_header.align))
+        .collect();
+    if note_blocks.is_empty() {
+        note_blocks = section_table
+            .sections
+            .iter()
+            .filter(|section| section.section_type == SHT_NOTE)
+            .map(|section| (section.raw_data, section.address_align))
+            .collect();
+    }
+
+    let mut notes = Vec::new();
+    for (data, align) in note_blocks {
+        // only the GNU property notes of ELFCLASS64 files use an 8 bytes alignment
+        let align = if align == 8 { 8 } else { 4 };
+        notes.extend(extract_elf_note_entries(data, align, machine, is_64, endianness)?);
+    }
+    Ok(notes)
+}
+
+fn elf_symbol_kind(symbol: &ELFSymbol) -> SymbolKind {
+    match symbol.symbol_type {
+        STT_FUNC | STT_GNU_IFUNC => SymbolKind::Function,
+        STT_OBJECT | STT_TLS => SymbolKind::Object,
+        STT_SECTION => SymbolKind::Section,
+        STT_FILE => SymbolKind::File,
+        _ => SymbolKind::Other,
+    }
+}
+
+fn machine_architecture(machine: u16) -> Architecture {
+    match machine {
+        0x03 => Architecture::X86,
+        0x3E => Architecture::X86_64,
+        0x28 => Architecture::Arm,
+        0xB7 => Architecture::AArch64,
+        0x08 | 0x0A => Architecture::Mips,
+        0x14 => Architecture::PowerPC,
+        0x15 => Architecture::PowerPC64,
+        0xF3 => Architecture::RiscV,
+        _ => Architecture::Unknown(machine as u32),
+    }
+}
+
+pub fn parse_elf(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let identification = extract_elf_identification(bytes)?;
+    let header = extract_elf_header(bytes, &identification)?;
+    let section_table = extract_elf_section_table(bytes, &identification, &header)?;
+    let program_header_table = extract_elf_program_header_table(bytes, &identification, &header)?;
+    let interpreter = program_header_table
+        .program_headers
+        .iter()
+        .find(|program_header| program_header.segment_type == PT_INTERP)
+        .map(|program_header| read_c_string(program_header.raw_data, 0))
+        .transpose()?;
+    let symbol_table = extract_elf_symbol_table(&identification, &section_table, SHT_SYMTAB)?;
+    let dynamic_symbol_table =
+        extract_elf_symbol_table(&identification, &section_table, SHT_DYNSYM)?;
+    let dynamic_section = extract_elf_dynamic_section(
+        bytes,
+        &identification,
+        &section_table,
+        &program_header_table,
+    )?;
+    let libraries = dynamic_section
+        .as_ref()
+        .map_or(Vec::new(), |dynamic_section| dynamic_section.needed.clone());
+    let endianness = elf_endianness(&identification);
+    let machine = read_u16(header.machine, endianness);
+    let relocation_tables = extract_elf_relocation_tables(
+        &identification,
+        &section_table,
+        &symbol_table,
+        &dynamic_symbol_table,
+    );
+    let plt_entries =
+        extract_elf_plt_entries(machine, &identification, &section_table, &relocation_tables);
+    let notes = extract_elf_notes(
+        machine,
+        &identification,
+        &section_table,
+        &program_header_table,
+    )?;
+
+    let bitness = if identification.class == b"\x01" {
+        Bitness::Bits32
+    } else {
+        Bitness::Bits64
+    };
+
+    let sections = section_table
+        .sections
+        .iter()
+        .filter(|section| section.section_type != SHT_NULL)
+        .map(|section| BinarySection {
+            name: section.name.clone(),
+            virtual_address: section.address,
+            virtual_size: section.size,
+            file_offset: section.offset,
+            file_size: section.raw_data.len() as u64,
+            raw_data: section.raw_data,
+        })
+        .collect();
+
+    let segments = program_header_table
+        .program_headers
+        .iter()
+        .map(|program_header| BinarySegment {
+            name: segment_type_name(program_header.segment_type),
+            virtual_address: program_header.virtual_address,
+            virtual_size: program_header.memory_size,
+            file_offset: program_header.offset,
+            file_size: program_header.file_size,
+            raw_data: program_header.raw_data,
+        })
+        .collect();
+
+    // .symtab is a superset of .dynsym when the binary is not stripped
+    let named_symbols = if symbol_table.symbols.is_empty() {
+        &dynamic_symbol_table
+    } else {
+        &symbol_table
+    };
+    let mut symbols: Vec<BinarySymbol> = named_symbols
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.section_index != SHN_UNDEF && !symbol.name.is_empty())
+        .map(|symbol| BinarySymbol {
+            name: symbol.name.clone(),
+            demangled_name: None,
+            kind: elf_symbol_kind(symbol),
+            address: symbol.value,
+            size: symbol.size,
+        })
+        .collect();
+    // labels the stubs so calls show up as `call printf@plt`
+    symbols.extend(plt_entries.iter().map(|plt_entry| BinarySymbol {
+        name: format!("{}@plt", plt_entry.name),
+        demangled_name: None,
+        kind: SymbolKind::Function,
+        address: plt_entry.address,
+        size: 0,
+    }));
+
+    // every imported symbol goes through a GOT slot, calls go through its PLT stub if any
+    let mut imports: Vec<BinaryImport> = Vec::new();
+    for relocation in relocation_tables
+        .iter()
+        .flat_map(|relocation_table| relocation_table.relocations.iter())
+        .filter(|relocation| is_import_relocation(machine, relocation.relocation_type))
+    {
+        let name = match &relocation.symbol_name {
+            Some(name) if !name.is_empty() => name,
+            _ => continue,
+        };
+        let address = plt_entries
+            .iter()
+            .find(|plt_entry| plt_entry.got_address == relocation.offset)
+            .map_or(relocation.offset, |plt_entry| plt_entry.address);
+        if !imports.iter().any(|import| &import.name == name) {
+            imports.push(BinaryImport {
+                library: String::new(),
+                name: name.clone(),
+                address,
+            });
+        }
+    }
+    // relocatable objects (.o) have no dynamic linking information, their global symbols
+    // are what the static linker resolves
+    let relocatable = read_u16(header.file_type, endianness) == ET_REL;
+    if relocatable {
+        for symbol in symbol_table.symbols.iter().filter(|symbol| {
+            symbol.section_index == SHN_UNDEF
+                && (symbol.binding == STB_GLOBAL || symbol.binding == STB_WEAK)
+                && !symbol.name.is_empty()
+        }) {
+            if !imports.iter().any(|import| import.name == symbol.name) {
+                imports.push(BinaryImport {
+                    library: String::new(),
+                    name: symbol.name.clone(),
+                    address: 0,
+                });
+            }
+        }
+    }
+    let exported_symbols = if relocatable {
+        &symbol_table
+    } else {
+        &dynamic_symbol_table
+    };
+    let exports = exported_symbols
+        .symbols
+        .iter()
+        .filter(|symbol| {
+            symbol.section_index != SHN_UNDEF
+                && (symbol.binding == STB_GLOBAL || symbol.binding == STB_WEAK)
+                && !symbol.name.is_empty()
+        })
+        .map(|symbol| BinaryExport {
+            name: symbol.name.clone(),
+            address: symbol.value,
+        })
+        .collect();
+
+    Ok(BinaryFile {
+        format: Format::Elf,
+        architecture: machine_architecture(machine),
+        endianness,
+        bitness,
+        entry_point: read_uint(header.entry_point, endianness),
+        sections,
+        segments,
+        symbols,
+        libraries,
+        imports,
+        exports,
+        details: FileDetails::Elf(FileInfoELF {
+            identification,
+            header,
+            section_table,
+            program_header_table,
+            interpreter,
+            symbol_table,
+            dynamic_symbol_table,
+            dynamic_section,
+            relocation_tables,
+            plt_entries,
+            notes,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_file::demangle_symbols;
+    use crate::elf_structure::{DT_PLTGOT, STB_LOCAL};
+
+    fn test_file(name: &str) -> Vec<u8> {
+        std::fs::read(format!(
+            "{}/../testExe/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    fn elf_details<'a>(file: &'a BinaryFile<'a>) -> &'a FileInfoELF<'a> {
+        match &file.details {
+            FileDetails::Elf(elf) => elf,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn sections_are_named_and_hold_their_bytes() {
+        let bytes = test_file("Linux/elf-Linux-lib-x64.so");
+        let file = parse_elf(&bytes).unwrap();
+        let sections = &elf_details(&file).section_table.sections;
+        assert_eq!(sections.len(), 26);
+        assert_eq!(sections[0].section_type, SHT_NULL);
+        assert_eq!(sections[25].name, ".shstrtab");
+
+        let text = sections
+            .iter()
+            .find(|section| section.name == ".text")
+            .unwrap();
+        assert_eq!(text.address, 0x15860);
+        assert_eq!(text.offset, 0x15860);
+        assert_eq!(text.size, 0xc6abc);
+        assert_eq!(text.raw_data, &bytes[0x15860..0x15860 + 0xc6abc]);
+
+        // .bss takes no room in the file
+        let bss = sections
+            .iter()
+            .find(|section| section.name == ".bss")
+            .unwrap();
+        assert_eq!(bss.section_type, SHT_NOBITS);
+        assert!(bss.raw_data.is_empty());
+        assert!(file
+            .sections
+            .iter()
+            .any(|section| section.name == ".text" && section.raw_data == text.raw_data));
+    }
+
+    #[test]
+    fn dynamic_symbols_are_used_without_a_symbol_table() {
+        let bytes = test_file("Linux/elf-Linux-lib-x64.so");
+        let mut file = parse_elf(&bytes).unwrap();
+        let elf = elf_details(&file);
+        assert!(elf.symbol_table.symbols.is_empty());
+        let dynamic_symbols = &elf.dynamic_symbol_table.symbols;
+        assert_eq!(dynamic_symbols.len(), 815);
+
+        let create = &dynamic_symbols[642];
+        assert_eq!(create.name, "cairo_create");
+        assert_eq!(create.value, 0x22780);
+        assert_eq!(create.size, 77);
+        assert_eq!(create.binding, STB_GLOBAL);
+        assert_eq!(create.symbol_type, STT_FUNC);
+        assert_eq!(create.section_index, 11);
+        assert_eq!(dynamic_symbols[11].name, "FcPatternDestroy");
+        assert_eq!(dynamic_symbols[11].section_index, SHN_UNDEF);
+
+        let symbol = file
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == "cairo_create")
+            .unwrap();
+        assert!(matches!(symbol.kind, SymbolKind::Function));
+        assert_eq!(symbol.address, 0x22780);
+        assert_eq!(symbol.size, 77);
+        assert!(symbol.demangled_name.is_none());
+        // imports are not symbols of the library
+        assert!(!file
+            .symbols
+            .iter()
+            .any(|symbol| symbol.name == "FcPatternDestroy"));
+
+        demangle_symbols(&mut file, |name| {
+            name.strip_prefix("cairo_").map(str::to_uppercase)
+        });
+        let symbol = file
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == "cairo_create")
+            .unwrap();
+        assert_eq!(symbol.demangled_name.as_deref(), Some("CREATE"));
+        assert!(file
+            .symbols
+            .iter()
+            .filter(|symbol| !symbol.name.starts_with("cairo_"))
+            .all(|symbol| symbol.demangled_name.is_none()));
+    }
+
+    #[test]
+    fn dynamic_section_lists_the_needed_libraries() {
+        let bytes = test_file("Linux/elf-Linux-lib-x64.so");
+        let file = parse_elf(&bytes).unwrap();
+        let dynamic = elf_details(&file).dynamic_section.as_ref().unwrap();
+        // readelf counts 43 with the terminating DT_NULL, which is not kept
+        assert_eq!(dynamic.entries.len(), 42);
+        assert_eq!(
+            dynamic.needed,
+            [
+                "libpthread.so.0",
+                "libpixman-1.so.0",
+                "libfontconfig.so.1",
+                "libfreetype.so.6",
+                "libEGL.so.1",
+                "libdl.so.2",
+                "libpng16.so.16",
+                "libxcb-shm.so.0",
+                "libxcb-render.so.0",
+                "libxcb.so.1",
+                "libXrender.so.1",
+                "libX11.so.6",
+                "libXext.so.6",
+                "libz.so.1",
+                "libGL.so.1",
+                "librt.so.1",
+                "libm.so.6",
+                "libc.so.6",
+            ]
+        );
+        assert_eq!(dynamic.soname.as_deref(), Some("libcairo.so.2"));
+        assert_eq!(dynamic.rpath, None);
+        assert_eq!(dynamic.runpath, None);
+        assert_eq!(dynamic.init, Some(0x13fe0));
+        assert_eq!(dynamic.fini, Some(0xdc31c));
+        assert_eq!(dynamic.init_array, [0x161a0]);
+        assert_eq!(dynamic.fini_array, [0x16160]);
+        assert!(dynamic.preinit_array.is_empty());
+        assert_eq!(dynamic.hash, Some(0x1c8));
+        assert_eq!(dynamic.gnu_hash, Some(0x1e80));
+        assert!(dynamic.entries.iter().all(|entry| entry.tag != DT_NULL));
+        assert!(dynamic
+            .entries
+            .iter()
+            .any(|entry| entry.tag == DT_PLTGOT && entry.value == 0x316000));
+    }
+
+    #[test]
+    fn os_specific_segment_types_keep_their_value() {
+        assert_eq!(segment_type_name(PT_GNU_STACK), "PT_GNU_STACK");
+        assert_eq!(segment_type_name(0x6504_1580), "PT_LOOS+0x5041580");
+        assert_eq!(segment_type_name(0x7000_0001), "PT_LOPROC+0x1");
+        assert_eq!(segment_type_name(0x1234), "0x1234");
+    }
+
+    #[test]
+    fn plt_stubs_resolve_to_their_imports() {
+        let bytes = test_file("Linux/elf-Linux-lib-x64.so");
+        let file = parse_elf(&bytes).unwrap();
+        let elf = elf_details(&file);
+        let tables: Vec<(&str, usize)> = elf
+            .relocation_tables
+            .iter()
+            .map(|table| (table.section_name.as_str(), table.relocations.len()))
+            .collect();
+        assert_eq!(tables, [(".rela.dyn", 814), (".rela.plt", 389)]);
+        let jump_slot = &elf.relocation_tables[1].relocations[0];
+        assert_eq!(jump_slot.offset, 0x316018);
+        assert_eq!(
+            relocation_type_name(EM_X86_64, jump_slot.relocation_type),
+            "R_X86_64_JUMP_SLOT"
+        );
+        assert_eq!(jump_slot.addend, Some(0));
+        assert_eq!(
+            jump_slot.symbol_name.as_deref(),
+            Some("xcb_render_create_picture")
+        );
+
+        // PLT0 at 0x14000 is the resolver trampoline, one 16 bytes stub per import follows
+        let plt_entries = &elf.plt_entries;
+        assert_eq!(plt_entries.len(), 389);
+        assert_eq!(plt_entries[0].address, 0x14010);
+        assert_eq!(plt_entries[0].got_address, 0x316018);
+        assert_eq!(plt_entries[0].name, "xcb_render_create_picture");
+        assert_eq!(plt_entries[388].address, 0x15850);
+        assert_eq!(plt_entries[388].name, "png_set_write_user_transform_fn");
+
+        let stub = file
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == "xcb_render_create_picture@plt")
+            .unwrap();
+        assert_eq!(stub.address, 0x14010);
+        let import = file
+            .imports
+            .iter()
+            .find(|import| import.name == "xcb_render_create_picture")
+            .unwrap();
+        assert_eq!(import.address, 0x14010);
+    }
+
+    /// 32-bit PowerPC executable: .text, .symtab, .strtab and .shstrtab, all big endian
+    fn big_endian_file() -> Vec<u8> {
+        let mut bytes = vec![0; 0x34];
+        bytes[0..7].copy_from_slice(b"\x7fELF\x01\x02\x01");
+        bytes[16..18].copy_from_slice(&2u16.to_be_bytes());
+        bytes[18..20].copy_from_slice(&0x14u16.to_be_bytes());
+        bytes[20..24].copy_from_slice(&1u32.to_be_bytes());
+        bytes[24..28].copy_from_slice(&0x1000_0000u32.to_be_bytes());
+        bytes[32..36].copy_from_slice(&0x9Cu32.to_be_bytes());
+        bytes[40..42].copy_from_slice(&0x34u16.to_be_bytes());
+        bytes[46..48].copy_from_slice(&40u16.to_be_bytes());
+        bytes[48..50].copy_from_slice(&5u16.to_be_bytes());
+        bytes[50..52].copy_from_slice(&4u16.to_be_bytes());
+        // li r3, 0; blr
+        bytes.extend_from_slice(&[0x38, 0x60, 0x00, 0x00, 0x4E, 0x80, 0x00, 0x20]);
+        bytes.extend_from_slice(b"\0main\0counter\0");
+        bytes.extend_from_slice(b"\0.text\0.symtab\0.strtab\0.shstrtab\0\0");
+
+        let mut symbol = |name: u32, value: u32, size: u32, info: u8, section_index: u16| {
+            bytes.extend_from_slice(&name.to_be_bytes());
+            bytes.extend_from_slice(&value.to_be_bytes());
+            bytes.extend_from_slice(&size.to_be_bytes());
+            bytes.extend_from_slice(&[info, 0]);
+            bytes.extend_from_slice(&section_index.to_be_bytes());
+        };
+        symbol(0, 0, 0, 0, SHN_UNDEF);
+        symbol(6, 0x1000_0004, 4, STB_LOCAL << 4 | STT_OBJECT, 1);
+        symbol(1, 0x1000_0000, 8, STB_GLOBAL << 4 | STT_FUNC, 1);
+
+        let mut section = |fields: [u32; 10]| {
+            for field in fields {
+                bytes.extend_from_slice(&field.to_be_bytes());
+            }
+        };
+        section([0; 10]);
+        section([1, 1, 6, 0x1000_0000, 0x34, 8, 0, 0, 4, 0]);
+        section([7, SHT_SYMTAB, 0, 0, 0x6C, 48, 3, 2, 4, 16]);
+        section([15, SHT_STRTAB, 0, 0, 0x3C, 14, 0, 0, 1, 0]);
+        section([23, SHT_STRTAB, 0, 0, 0x4A, 33, 0, 0, 1, 0]);
+        bytes
+    }
+
+    #[test]
+    fn big_endian_fields_are_decoded() {
+        let bytes = big_endian_file();
+        let file = parse_elf(&bytes).unwrap();
+        assert_eq!(file.endianness, Endianness::Big);
+        assert!(matches!(file.architecture, Architecture::PowerPC));
+        assert!(matches!(file.bitness, Bitness::Bits32));
+        assert_eq!(file.entry_point, 0x1000_0000);
+
+        let elf = elf_details(&file);
+        let names: Vec<&str> = elf
+            .section_table
+            .sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect();
+        assert_eq!(names, ["", ".text", ".symtab", ".strtab", ".shstrtab"]);
+        let text = &elf.section_table.sections[1];
+        assert_eq!(text.address, 0x1000_0000);
+        assert_eq!(
+            text.raw_data,
+            [0x38, 0x60, 0x00, 0x00, 0x4E, 0x80, 0x00, 0x20]
+        );
+
+        let symbols = &elf.symbol_table.symbols;
+        assert_eq!(symbols.len(), 3);
+        assert_eq!(symbols[1].name, "counter");
+        assert_eq!(symbols[1].binding, STB_LOCAL);
+        assert_eq!(symbols[1].symbol_type, STT_OBJECT);
+        assert_eq!(symbols[2].name, "main");
+        assert_eq!(symbols[2].value, 0x1000_0000);
+        assert_eq!(symbols[2].size, 8);
+        assert_eq!(symbols[2].binding, STB_GLOBAL);
+        assert_eq!(symbols[2].section_index, 1);
+        let main = file
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == "main")
+            .unwrap();
+        assert!(matches!(main.kind, SymbolKind::Function));
+        assert_eq!(main.address, 0x1000_0000);
+    }
+
+    fn note(name: &str, note_type: u32, descriptor: &[u8], align: usize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(name.len() as u32 + 1).to_le_bytes());
+        bytes.extend_from_slice(&(descriptor.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&note_type.to_le_bytes());
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.push(0);
+        bytes.resize(bytes.len().next_multiple_of(align), 0);
+        bytes.extend_from_slice(descriptor);
+        bytes.resize(bytes.len().next_multiple_of(align), 0);
+        bytes
+    }
+
+    #[test]
+    fn gnu_notes_are_decoded() {
+        let mut abi_tag = Vec::new();
+        for word in [0u32, 3, 2, 0] {
+            abi_tag.extend_from_slice(&word.to_le_bytes());
+        }
+        let mut data = note("GNU", NT_GNU_BUILD_ID, &[0xDE, 0xAD, 0x0B, 0xEF], 4);
+        data.extend(note("GNU", NT_GNU_ABI_TAG, &abi_tag, 4));
+        data.extend(note("GNU", 0x99, &[1, 2], 4));
+        let notes =
+            extract_elf_note_entries(&data, 4, EM_X86_64, true, Endianness::Little).unwrap();
+        assert_eq!(notes.len(), 3);
+        assert!(matches!(
+            &notes[0].content,
+            ELFNoteContent::GnuBuildId(build_id) if build_id == "dead0bef"
+        ));
+        assert!(matches!(
+            notes[1].content,
+            ELFNoteContent::GnuAbiTag {
+                os: "Linux",
+                major: 3,
+                minor: 2,
+                patch: 0
+            }
+        ));
+        assert_eq!(notes[2].name, "GNU");
+        assert_eq!(notes[2].descriptor, [1, 2]);
+        assert!(matches!(notes[2].content, ELFNoteContent::Unknown));
+
+        // ELFCLASS64 properties are padded to 8 bytes
+        let mut properties = Vec::new();
+        properties.extend_from_slice(&GNU_PROPERTY_X86_FEATURE_1_AND.to_le_bytes());
+        properties.extend_from_slice(&4u32.to_le_bytes());
+        properties.extend_from_slice(&GNU_PROPERTY_X86_FEATURE_1_SHSTK.to_le_bytes());
+        properties.extend_from_slice(&[0; 4]);
+        properties.extend_from_slice(&GNU_PROPERTY_STACK_SIZE.to_le_bytes());
+        properties.extend_from_slice(&8u32.to_le_bytes());
+        properties.extend_from_slice(&0x80_0000u64.to_le_bytes());
+        let data = note("GNU", NT_GNU_PROPERTY_TYPE_0, &properties, 8);
+        let notes =
+            extract_elf_note_entries(&data, 8, EM_X86_64, true, Endianness::Little).unwrap();
+        match &notes[0].content {
+            ELFNoteContent::GnuProperties(properties) => {
+                assert_eq!(properties.len(), 2);
+                assert!(matches!(
+                    properties[0],
+                    ELFGnuProperty::X86Feature1 {
+                        ibt: false,
+                        shstk: true
+                    }
+                ));
+                assert!(matches!(
+                    properties[1],
+                    ELFGnuProperty::StackSize(0x80_0000)
+                ));
+            }
+            content => panic!("{:?}", content),
+        }
+        // the same feature is not an x86 one on AArch64
+        let notes =
+            extract_elf_note_entries(&data, 8, EM_AARCH64, true, Endianness::Little).unwrap();
+        assert!(matches!(
+            &notes[0].content,
+            ELFNoteContent::GnuProperties(properties)
+                if matches!(properties[0], ELFGnuProperty::Other { .. })
+        ));
+    }
+
+    #[test]
+    fn core_mapped_file_names_are_read_as_bytes() {
+        let mut descriptor = Vec::new();
+        for word in [
+            2u64, 0x1000, 0x40_0000, 0x40_1000, 0, 0x60_0000, 0x60_3000, 2,
+        ] {
+            descriptor.extend_from_slice(&word.to_le_bytes());
+        }
+        // an invalid UTF-8 name decodes to more bytes than it holds
+        descriptor.extend_from_slice(b"/tmp/\xFF\xFE\0/usr/lib/libc.so.6\0");
+        let data = note("CORE", NT_FILE, &descriptor, 4);
+        let notes =
+            extract_elf_note_entries(&data, 4, EM_X86_64, true, Endianness::Little).unwrap();
+        let ELFNoteContent::CoreMappedFiles(files) = &notes[0].content else {
+            panic!("{:?}", notes[0].content);
+        };
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].name, "/tmp/\u{FFFD}\u{FFFD}");
+        assert_eq!((files[0].start, files[0].end), (0x40_0000, 0x40_1000));
+        assert_eq!(files[1].name, "/usr/lib/libc.so.6");
+        assert_eq!((files[1].start, files[1].end), (0x60_0000, 0x60_3000));
+        assert_eq!(files[1].file_offset, 0x2000);
+
+        // a count past the descriptor
+        descriptor[0..8].copy_from_slice(&3u64.to_le_bytes());
+        let data = note("CORE", NT_FILE, &descriptor, 4);
+        assert!(extract_elf_note_entries(&data, 4, EM_X86_64, true, Endianness::Little).is_err());
+    }
+
+    /// Little endian ELF64 relocatable file with `.text` and `.shstrtab`, the section
+    /// header table at 0x58 after their data
+    fn relocatable_file() -> Vec<u8> {
+        let mut bytes = vec![0; 0x58];
+        bytes[0..7].copy_from_slice(b"\x7fELF\x02\x01\x01");
+        bytes[16..18].copy_from_slice(&1u16.to_le_bytes());
+        bytes[18..20].copy_from_slice(&EM_X86_64.to_le_bytes());
+        bytes[20..24].copy_from_slice(&1u32.to_le_bytes());
+        bytes[40..48].copy_from_slice(&0x58u64.to_le_bytes());
+        bytes[52..54].copy_from_slice(&64u16.to_le_bytes());
+        bytes[58..60].copy_from_slice(&64u16.to_le_bytes());
+        bytes[60..62].copy_from_slice(&3u16.to_le_bytes());
+        bytes[62..64].copy_from_slice(&2u16.to_le_bytes());
+        bytes[0x40..0x44].copy_from_slice(&[0x90, 0x90, 0x90, 0xC3]);
+        bytes[0x44..0x55].copy_from_slice(b"\0.text\0.shstrtab\0");
+
+        let mut section = |name: u32, section_type: u32, offset: u64, size: u64| {
+            let mut header = [0; 64];
+            header[0..4].copy_from_slice(&name.to_le_bytes());
+            header[4..8].copy_from_slice(&section_type.to_le_bytes());
+            header[24..32].copy_from_slice(&offset.to_le_bytes());
+            header[32..40].copy_from_slice(&size.to_le_bytes());
+            bytes.extend_from_slice(&header);
+        };
+        section(0, SHT_NULL, 0, 0);
+        section(1, 1, 0x40, 4);
+        section(7, 3, 0x44, 17);
+        bytes
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        let bytes = relocatable_file();
+        let file = parse_elf(&bytes).unwrap();
+        let names: Vec<&str> = file
+            .sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect();
+        assert_eq!(names, [".text", ".shstrtab"]);
+        for length in 0..bytes.len() {
+            assert!(parse_elf(&bytes[..length]).is_err(), "length {}", length);
+        }
+    }
+
+    #[test]
+    fn hostile_section_tables_are_rejected() {
+        let patched = |offset: usize, value: &[u8]| {
+            let mut bytes = relocatable_file();
+            bytes[offset..offset + value.len()].copy_from_slice(value);
+            parse_elf(&bytes).map(|_| ())
+        };
+        // e_shnum past the end of the file, then 0 with a huge count in the first header
+        assert!(matches!(
+            patched(60, &0xFFFFu16.to_le_bytes()),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+        let mut bytes = relocatable_file();
+        bytes[60..62].copy_from_slice(&0u16.to_le_bytes());
+        bytes[0x58 + 32..0x58 + 40].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            parse_elf(&bytes),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+        // e_shstrndx past the table, a name past .shstrtab
+        assert!(matches!(
+            patched(62, &7u16.to_le_bytes()),
+            Err(ParseError::InvalidStringIndex {
+                table: "ELF section header table",
+                index: 7
+            })
+        ));
+        assert!(matches!(
+            patched(0x58 + 64, &0x100u32.to_le_bytes()),
+            Err(ParseError::InvalidStringIndex {
+                table: ".shstrtab",
+                index: 0x100
+            })
+        ));
+        // .text data past the end of the file
+        assert!(matches!(
+            patched(0x58 + 64 + 32, &u64::MAX.to_le_bytes()),
+            Err(ParseError::OutOfRange { .. })
+        ));
+        // a program header table past the end of the file
+        let mut bytes = relocatable_file();
+        bytes[32..40].copy_from_slice(&0x1000u64.to_le_bytes());
+        bytes[54..56].copy_from_slice(&56u16.to_le_bytes());
+        bytes[56..58].copy_from_slice(&1u16.to_le_bytes());
+        assert!(matches!(
+            parse_elf(&bytes),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+    }
+}
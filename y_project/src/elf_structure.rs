@@ -28,9 +28,320 @@ pub struct ELFHeader<'a> {
     pub section_name_string_table_index: &'a [u8],
 }
 
+pub const SHT_NULL: u32 = 0;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_RELA: u32 = 4;
+pub const SHT_DYNAMIC: u32 = 6;
+pub const SHT_NOTE: u32 = 7;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_REL: u32 = 9;
+pub const SHT_DYNSYM: u32 = 11;
+
+pub const ET_REL: u16 = 1;
+pub const ET_CORE: u16 = 4;
+
+pub const EM_386: u16 = 3;
+pub const EM_X86_64: u16 = 62;
+pub const EM_AARCH64: u16 = 183;
+
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+pub const NT_PRSTATUS: u32 = 1;
+pub const NT_PRFPREG: u32 = 2;
+pub const NT_PRPSINFO: u32 = 3;
+pub const NT_TASKSTRUCT: u32 = 4;
+pub const NT_AUXV: u32 = 6;
+pub const NT_X86_XSTATE: u32 = 0x202;
+pub const NT_ARM_PAC_MASK: u32 = 0x406;
+pub const NT_SIGINFO: u32 = 0x5349_4749;
+pub const NT_FILE: u32 = 0x4649_4C45;
+
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xC000_0000;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xC000_8002;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xC000_0002;
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 1;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 1;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 2;
+
+pub const R_386_GLOB_DAT: u32 = 6;
+pub const R_386_JMP_SLOT: u32 = 7;
+pub const R_X86_64_GLOB_DAT: u32 = 6;
+pub const R_X86_64_JUMP_SLOT: u32 = 7;
+pub const R_AARCH64_GLOB_DAT: u32 = 1025;
+pub const R_AARCH64_JUMP_SLOT: u32 = 1026;
+
+pub const PT_NULL: u32 = 0;
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
+pub const PT_NOTE: u32 = 4;
+pub const PT_SHLIB: u32 = 5;
+pub const PT_PHDR: u32 = 6;
+pub const PT_TLS: u32 = 7;
+pub const PT_GNU_EH_FRAME: u32 = 0x6474_E550;
+pub const PT_GNU_STACK: u32 = 0x6474_E551;
+pub const PT_GNU_RELRO: u32 = 0x6474_E552;
+pub const PT_GNU_PROPERTY: u32 = 0x6474_E553;
+pub const PT_LOOS: u32 = 0x6000_0000;
+pub const PT_HIOS: u32 = 0x6FFF_FFFF;
+pub const PT_LOPROC: u32 = 0x7000_0000;
+pub const PT_HIPROC: u32 = 0x7FFF_FFFF;
+
+pub const DT_NULL: u64 = 0;
+pub const DT_NEEDED: u64 = 1;
+pub const DT_PLTRELSZ: u64 = 2;
+pub const DT_PLTGOT: u64 = 3;
+pub const DT_HASH: u64 = 4;
+pub const DT_STRTAB: u64 = 5;
+pub const DT_SYMTAB: u64 = 6;
+pub const DT_RELA: u64 = 7;
+pub const DT_RELASZ: u64 = 8;
+pub const DT_RELAENT: u64 = 9;
+pub const DT_STRSZ: u64 = 10;
+pub const DT_SYMENT: u64 = 11;
+pub const DT_INIT: u64 = 12;
+pub const DT_FINI: u64 = 13;
+pub const DT_SONAME: u64 = 14;
+pub const DT_RPATH: u64 = 15;
+pub const DT_SYMBOLIC: u64 = 16;
+pub const DT_REL: u64 = 17;
+pub const DT_RELSZ: u64 = 18;
+pub const DT_RELENT: u64 = 19;
+pub const DT_PLTREL: u64 = 20;
+pub const DT_DEBUG: u64 = 21;
+pub const DT_TEXTREL: u64 = 22;
+pub const DT_JMPREL: u64 = 23;
+pub const DT_BIND_NOW: u64 = 24;
+pub const DT_INIT_ARRAY: u64 = 25;
+pub const DT_FINI_ARRAY: u64 = 26;
+pub const DT_INIT_ARRAYSZ: u64 = 27;
+pub const DT_FINI_ARRAYSZ: u64 = 28;
+pub const DT_RUNPATH: u64 = 29;
+pub const DT_FLAGS: u64 = 30;
+pub const DT_PREINIT_ARRAY: u64 = 32;
+pub const DT_PREINIT_ARRAYSZ: u64 = 33;
+pub const DT_GNU_HASH: u64 = 0x6FFF_FEF5;
+pub const DT_VERSYM: u64 = 0x6FFF_FFF0;
+pub const DT_FLAGS_1: u64 = 0x6FFF_FFFB;
+pub const DT_VERNEED: u64 = 0x6FFF_FFFE;
+pub const DT_VERNEEDNUM: u64 = 0x6FFF_FFFF;
+
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_ABS: u16 = 0xFFF1;
+pub const SHN_COMMON: u16 = 0xFFF2;
+pub const SHN_XINDEX: u16 = 0xFFFF;
+
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STB_WEAK: u8 = 2;
+
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+pub const STT_FILE: u8 = 4;
+pub const STT_COMMON: u8 = 5;
+pub const STT_TLS: u8 = 6;
+pub const STT_GNU_IFUNC: u8 = 10;
+
+pub const STV_DEFAULT: u8 = 0;
+pub const STV_INTERNAL: u8 = 1;
+pub const STV_HIDDEN: u8 = 2;
+pub const STV_PROTECTED: u8 = 3;
+
+/// Elf32_Shdr and Elf64_Shdr, 32-bit fields are widened
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFSection<'a> {
+    pub name: String,
+    pub name_offset: u32,
+    pub section_type: u32,
+    pub flags: u64,
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub info: u32,
+    pub address_align: u64,
+    pub entry_size: u64,
+    pub raw_data: &'a [u8],
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFSectionTable<'a> {
+    pub sections: Vec<ELFSection<'a>>,
+}
+
+/// Elf32_Phdr and Elf64_Phdr, 32-bit fields are widened
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFProgramHeader<'a> {
+    pub segment_type: u32,
+    pub flags: u32,
+    pub offset: u64,
+    pub virtual_address: u64,
+    pub physical_address: u64,
+    pub file_size: u64,
+    pub memory_size: u64,
+    pub align: u64,
+    pub raw_data: &'a [u8],
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFProgramHeaderTable<'a> {
+    pub program_headers: Vec<ELFProgramHeader<'a>>,
+}
+
+/// Elf32_Sym and Elf64_Sym, `info` and `other` are split into their fields
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFSymbol {
+    pub name: String,
+    pub name_offset: u32,
+    pub value: u64,
+    pub size: u64,
+    pub binding: u8,
+    pub symbol_type: u8,
+    pub visibility: u8,
+    pub section_index: u16,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFSymbolTable {
+    pub symbols: Vec<ELFSymbol>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFDynamicEntry {
+    pub tag: u64,
+    pub value: u64,
+}
+
+/// Decoded PT_DYNAMIC, addresses are virtual addresses
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct ELFDynamicSection {
+    pub entries: Vec<ELFDynamicEntry>,
+    pub needed: Vec<String>,
+    pub soname: Option<String>,
+    pub rpath: Option<String>,
+    pub runpath: Option<String>,
+    pub init: Option<u64>,
+    pub fini: Option<u64>,
+    pub init_array: Vec<u64>,
+    pub fini_array: Vec<u64>,
+    pub preinit_array: Vec<u64>,
+    pub flags: u64,
+    pub flags_1: u64,
+    pub hash: Option<u64>,
+    pub gnu_hash: Option<u64>,
+}
+
+/// Elf_Rel and Elf_Rela entries, `addend` is only set for SHT_RELA
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFRelocation {
+    pub offset: u64,
+    pub symbol_index: u32,
+    pub relocation_type: u32,
+    pub addend: Option<i64>,
+    pub symbol_name: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFRelocationTable {
+    pub section_name: String,
+    pub relocations: Vec<ELFRelocation>,
+}
+
+/// PLT stub resolved to the imported function it jumps to
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFPltEntry {
+    pub address: u64,
+    pub got_address: u64,
+    pub name: String,
+}
+
+/// Entry of a NT_GNU_PROPERTY_TYPE_0 note
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum ELFGnuProperty<'a> {
+    StackSize(u64),
+    NoCopyOnProtected,
+    /// Control-flow Enforcement Technology
+    X86Feature1 { ibt: bool, shstk: bool },
+    /// Branch Target Identification and Pointer Authentication
+    AArch64Feature1 { bti: bool, pac: bool },
+    Other { property_type: u32, data: &'a [u8] },
+}
+
+/// File mapped in the crashed process, from the NT_FILE core note
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFCoreMappedFile {
+    pub start: u64,
+    pub end: u64,
+    pub file_offset: u64,
+    pub name: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum ELFNoteContent<'a> {
+    /// Lowercase hex, the way debuginfod and `.build-id/` directories name it
+    GnuBuildId(String),
+    GnuAbiTag {
+        os: &'static str,
+        major: u32,
+        minor: u32,
+        patch: u32,
+    },
+    GnuProperties(Vec<ELFGnuProperty<'a>>),
+    GnuGoldVersion(String),
+    CoreMappedFiles(Vec<ELFCoreMappedFile>),
+    /// Register sets, process infos... kept raw, their layout depends on the architecture
+    Core(&'static str),
+    Unknown,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ELFNote<'a> {
+    pub name: String,
+    pub note_type: u32,
+    pub descriptor: &'a [u8],
+    pub content: ELFNoteContent<'a>,
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct FileInfoELF<'a> {
     pub identification: ELFIdentification<'a>,
     pub header: ELFHeader<'a>,
+    pub section_table: ELFSectionTable<'a>,
+    pub program_header_table: ELFProgramHeaderTable<'a>,
+    pub interpreter: Option<String>,
+    /// .symtab, usually stripped from release binaries
+    pub symbol_table: ELFSymbolTable,
+    /// .dynsym
+    pub dynamic_symbol_table: ELFSymbolTable,
+    pub dynamic_section: Option<ELFDynamicSection>,
+    pub relocation_tables: Vec<ELFRelocationTable>,
+    pub plt_entries: Vec<ELFPltEntry>,
+    /// From PT_NOTE segments, or SHT_NOTE sections for relocatable files
+    pub notes: Vec<ELFNote<'a>>,
 }
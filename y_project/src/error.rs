@@ -0,0 +1,134 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    TruncatedHeader {
+        header: &'static str,
+        offset: usize,
+        size: usize,
+        file_size: usize,
+    },
+    BadMagic {
+        format: &'static str,
+        found: Vec<u8>,
+    },
+    OutOfRange {
+        offset: usize,
+        size: usize,
+        file_size: usize,
+    },
+    InvalidStringIndex {
+        table: &'static str,
+        index: usize,
+    },
+    InvalidSize {
+        structure: &'static str,
+        offset: usize,
+        size: usize,
+    },
+    InvalidOpcode {
+        table: &'static str,
+        offset: usize,
+        opcode: u8,
+    },
+    InvalidTag {
+        structure: &'static str,
+        offset: usize,
+        tag: u8,
+    },
+    InvalidData {
+        structure: &'static str,
+        offset: usize,
+    },
+    InvalidSignature {
+        line: usize,
+        reason: &'static str,
+    },
+    UnsupportedFormat(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(error) => write!(f, "cannot read file: {}", error),
+            ParseError::TruncatedHeader {
+                header,
+                offset,
+                size,
+                file_size,
+            } => write!(
+                f,
+                "truncated {}: {} bytes needed at offset {:#x} but the file is {} bytes long",
+                header, size, offset, file_size
+            ),
+            ParseError::BadMagic { format, found } => {
+                write!(f, "bad {} magic: {:02x?}", format, found)
+            }
+            ParseError::OutOfRange {
+                offset,
+                size,
+                file_size,
+            } => write!(
+                f,
+                "{} bytes at offset {:#x} are out of the file ({} bytes)",
+                size, offset, file_size
+            ),
+            ParseError::InvalidStringIndex { table, index } => {
+                write!(f, "invalid index {:#x} in {}", index, table)
+            }
+            ParseError::InvalidSize {
+                structure,
+                offset,
+                size,
+            } => write!(
+                f,
+                "invalid {} size {:#x} at offset {:#x}",
+                structure, size, offset
+            ),
+            ParseError::InvalidOpcode {
+                table,
+                offset,
+                opcode,
+            } => write!(
+                f,
+                "invalid {} opcode {:#04x} at offset {:#x}",
+                table, opcode, offset
+            ),
+            ParseError::InvalidTag {
+                structure,
+                offset,
+                tag,
+            } => write!(
+                f,
+                "invalid {} tag {:#04x} at offset {:#x}",
+                structure, tag, offset
+            ),
+            ParseError::InvalidData { structure, offset } => {
+                write!(f, "invalid {} data at offset {:#x}", structure, offset)
+            }
+            ParseError::InvalidSignature { line, reason } => {
+                write!(f, "invalid signature on line {}: {}", line, reason)
+            }
+            ParseError::UnsupportedFormat(format) => {
+                write!(f, "unsupported file format: {}", format)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(error: io::Error) -> Self {
+        ParseError::Io(error)
+    }
+}
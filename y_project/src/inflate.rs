@@ -0,0 +1,376 @@
+use crate::error::ParseError;
+
+const MAX_BITS: usize = 15;
+
+/// Base lengths and extra bits of the length symbols 257..285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances and extra bits of the distance symbols 0..29
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order of the code length code lengths in a dynamic block header
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Deflate streams are read least significant bit first
+struct BitReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    bit_buffer: u32,
+    bit_count: u32,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, count: u32) -> Result<u32, ParseError> {
+        while self.bit_count < count {
+            let byte = *self.data.get(self.offset).ok_or(ParseError::OutOfRange {
+                offset: self.offset,
+                size: 1,
+                file_size: self.data.len(),
+            })?;
+            self.offset += 1;
+            self.bit_buffer |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+        let value = self.bit_buffer & ((1u64 << count) - 1) as u32;
+        self.bit_buffer = ((self.bit_buffer as u64) >> count) as u32;
+        self.bit_count -= count;
+        Ok(value)
+    }
+
+    /// Stored blocks start on a byte boundary
+    fn align_to_byte(&mut self) {
+        self.bit_buffer = 0;
+        self.bit_count = 0;
+    }
+}
+
+/// Canonical Huffman code: number of codes of each length and symbols sorted by code
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Incomplete codes are accepted (a single distance code is legal), over-subscribed
+    /// ones are not
+    fn new(lengths: &[u8], offset: usize) -> Result<Huffman, ParseError> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths.iter() {
+            counts[length as usize] += 1;
+        }
+        let mut left: i32 = 1;
+        for &count in counts.iter().skip(1) {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(ParseError::InvalidData {
+                    structure: "deflate Huffman table",
+                    offset,
+                });
+            }
+        }
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for length in 1..MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, ParseError> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for length in 1..=MAX_BITS {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[length] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(ParseError::InvalidData {
+            structure: "deflate Huffman code",
+            offset: reader.offset,
+        })
+    }
+}
+
+fn fixed_tables() -> Result<(Huffman, Huffman), ParseError> {
+    let mut lengths = [0u8; 288];
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        *length = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    Ok((Huffman::new(&lengths, 0)?, Huffman::new(&[5u8; 30], 0)?))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman), ParseError> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(ParseError::InvalidData {
+            structure: "deflate dynamic block header",
+            offset: reader.offset,
+        });
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+        code_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths, reader.offset)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_length_code.decode(reader)?;
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 if index > 0 => (lengths[index - 1], 3 + reader.bits(2)? as usize),
+            17 => (0, 3 + reader.bits(3)? as usize),
+            18 => (0, 11 + reader.bits(7)? as usize),
+            _ => {
+                return Err(ParseError::InvalidData {
+                    structure: "deflate code lengths",
+                    offset: reader.offset,
+                })
+            }
+        };
+        if index + repeat > lengths.len() {
+            return Err(ParseError::InvalidData {
+                structure: "deflate code lengths",
+                offset: reader.offset,
+            });
+        }
+        lengths[index..index + repeat].fill(length);
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(ParseError::InvalidData {
+            structure: "deflate end of block code",
+            offset: reader.offset,
+        });
+    }
+    Ok((
+        Huffman::new(&lengths[..literal_count], reader.offset)?,
+        Huffman::new(&lengths[literal_count..], reader.offset)?,
+    ))
+}
+
+/// Decompresses a raw deflate stream (RFC 1951). The output is bounded by `max_size`,
+/// the uncompressed size announced by the container, to stop decompression bombs.
+pub fn inflate(data: &[u8], max_size: usize) -> Result<Vec<u8>, ParseError> {
+    let mut reader = BitReader {
+        data,
+        offset: 0,
+        bit_buffer: 0,
+        bit_count: 0,
+    };
+    let mut output: Vec<u8> = Vec::with_capacity(max_size.min(data.len().saturating_mul(8)));
+    let too_large = |offset: usize| ParseError::InvalidSize {
+        structure: "inflated data",
+        offset,
+        size: max_size,
+    };
+    loop {
+        let last = reader.bits(1)? == 1;
+        let block_offset = reader.offset;
+        match reader.bits(2)? {
+            0 => {
+                reader.align_to_byte();
+                let header =
+                    data.get(reader.offset..reader.offset + 4)
+                        .ok_or(ParseError::OutOfRange {
+                            offset: reader.offset,
+                            size: 4,
+                            file_size: data.len(),
+                        })?;
+                let length = u16::from_le_bytes([header[0], header[1]]);
+                let complement = u16::from_le_bytes([header[2], header[3]]);
+                if length != !complement {
+                    return Err(ParseError::InvalidData {
+                        structure: "deflate stored block length",
+                        offset: reader.offset,
+                    });
+                }
+                reader.offset += 4;
+                let stored = data
+                    .get(reader.offset..reader.offset + length as usize)
+                    .ok_or(ParseError::OutOfRange {
+                        offset: reader.offset,
+                        size: length as usize,
+                        file_size: data.len(),
+                    })?;
+                if output.len() + stored.len() > max_size {
+                    return Err(too_large(reader.offset));
+                }
+                output.extend_from_slice(stored);
+                reader.offset += length as usize;
+            }
+            block_type @ (1 | 2) => {
+                let (literals, distances) = if block_type == 1 {
+                    fixed_tables()?
+                } else {
+                    dynamic_tables(&mut reader)?
+                };
+                loop {
+                    let symbol = literals.decode(&mut reader)? as usize;
+                    if symbol < 256 {
+                        if output.len() >= max_size {
+                            return Err(too_large(reader.offset));
+                        }
+                        output.push(symbol as u8);
+                        continue;
+                    }
+                    if symbol == 256 {
+                        break;
+                    }
+                    let symbol = symbol - 257;
+                    if symbol >= LENGTH_BASE.len() {
+                        return Err(ParseError::InvalidData {
+                            structure: "deflate length symbol",
+                            offset: reader.offset,
+                        });
+                    }
+                    let length = LENGTH_BASE[symbol] as usize
+                        + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+                    let distance_symbol = distances.decode(&mut reader)? as usize;
+                    if distance_symbol >= DISTANCE_BASE.len() {
+                        return Err(ParseError::InvalidData {
+                            structure: "deflate distance symbol",
+                            offset: reader.offset,
+                        });
+                    }
+                    let distance = DISTANCE_BASE[distance_symbol] as usize
+                        + reader.bits(DISTANCE_EXTRA[distance_symbol] as u32)? as usize;
+                    if distance > output.len() {
+                        return Err(ParseError::InvalidData {
+                            structure: "deflate distance",
+                            offset: reader.offset,
+                        });
+                    }
+                    if output.len() + length > max_size {
+                        return Err(too_large(reader.offset));
+                    }
+                    // the copy may overlap what it produces, byte by byte
+                    let start = output.len() - distance;
+                    for index in 0..length {
+                        output.push(output[start + index]);
+                    }
+                }
+            }
+            block_type => {
+                return Err(ParseError::InvalidTag {
+                    structure: "deflate block",
+                    offset: block_offset,
+                    tag: block_type as u8,
+                })
+            }
+        }
+        if last {
+            return Ok(output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "abracadabra abracadabra abracadabra", fixed Huffman codes
+    const FIXED: [u8; 15] = [
+        0x4B, 0x4C, 0x2A, 0x4A, 0x4C, 0x4E, 0x4C, 0x49, 0x04, 0x52, 0x0A, 0x89, 0xD8, 0xD9, 0x00,
+    ];
+    const DYNAMIC_TEXT: &[u8] = b"rronaetdaeteeeeeteheernaeiaeeseeieaeioestteeeeeeeioeeeereehedeai";
+    const DYNAMIC: [u8; 44] = [
+        0x25, 0x8A, 0x49, 0x0A, 0x00, 0x30, 0x08, 0x03, 0xDF, 0x2A, 0x38, 0x50, 0x2F, 0x15, 0xD4,
+        0xFF, 0xD3, 0x48, 0x07, 0x02, 0xD9, 0xAA, 0xF2, 0x1A, 0xE3, 0x12, 0xCB, 0x70, 0xA0, 0x54,
+        0x85, 0x41, 0x43, 0x20, 0x9B, 0xF4, 0xFC, 0x99, 0x0D, 0xA2, 0xD0, 0xCF, 0xB1, 0x78,
+    ];
+    const STORED: [u8; 11] = [
+        0x01, 0x06, 0x00, 0xF9, 0xFF, b's', b't', b'o', b'r', b'e', b'd',
+    ];
+
+    #[test]
+    fn all_block_types_inflate() {
+        assert_eq!(
+            inflate(&FIXED, 35).unwrap(),
+            b"abracadabra abracadabra abracadabra"
+        );
+        assert_eq!(inflate(&DYNAMIC, 64).unwrap(), DYNAMIC_TEXT);
+        assert_eq!(inflate(&STORED, 6).unwrap(), b"stored");
+    }
+
+    #[test]
+    fn truncated_streams_are_rejected() {
+        for stream in [&FIXED[..], &DYNAMIC[..], &STORED[..]] {
+            for length in 0..stream.len() {
+                assert!(inflate(&stream[..length], 1024).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn output_is_bounded_by_the_announced_size() {
+        for (stream, size) in [(&FIXED[..], 35), (&DYNAMIC[..], 64), (&STORED[..], 6)] {
+            assert!(matches!(
+                inflate(stream, size - 1),
+                Err(ParseError::InvalidSize {
+                    structure: "inflated data",
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn hostile_streams_are_rejected() {
+        // reserved block type 3
+        assert!(matches!(
+            inflate(&[0x07], 16),
+            Err(ParseError::InvalidTag { tag: 3, .. })
+        ));
+        // stored block whose length complement does not match
+        assert!(matches!(
+            inflate(&[0x01, 0x06, 0x00, 0xF9, 0xFE], 16),
+            Err(ParseError::InvalidData {
+                structure: "deflate stored block length",
+                ..
+            })
+        ));
+        // a match of distance 1 before any output
+        assert!(matches!(
+            inflate(&[0x03, 0x02], 16),
+            Err(ParseError::InvalidData {
+                structure: "deflate distance",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,1424 @@
+use std::collections::HashSet;
+
+use crate::binary_file::{
+    Architecture, BinaryExport, BinaryFile, BinaryImport, BinarySymbol, Bitness, Endianness,
+    FileDetails, Format, SymbolKind,
+};
+use crate::error::ParseError;
+use crate::jvm_structure::{
+    AttributeContent, AttributeInfo, BootstrapMethod, ClassFile, CodeAttribute, ConstantPoolEntry,
+    ExceptionTableEntry, JvmInstruction, JvmOperand, LineNumber, MemberInfo, StackMapFrame,
+    VerificationType,
+};
+use crate::jvm_structure::{
+    ACC_PUBLIC, CLASS_HEADER_SIZE, CLASS_MAGIC, CONSTANT_CLASS, CONSTANT_DOUBLE, CONSTANT_DYNAMIC,
+    CONSTANT_FIELDREF, CONSTANT_FLOAT, CONSTANT_INTEGER, CONSTANT_INTERFACE_METHODREF,
+    CONSTANT_INVOKE_DYNAMIC, CONSTANT_LONG, CONSTANT_METHODREF, CONSTANT_METHOD_HANDLE,
+    CONSTANT_METHOD_TYPE, CONSTANT_MODULE, CONSTANT_NAME_AND_TYPE, CONSTANT_PACKAGE,
+    CONSTANT_STRING, CONSTANT_UTF8,
+};
+use crate::jvm_structure::{
+    ITEM_DOUBLE, ITEM_FLOAT, ITEM_INTEGER, ITEM_LONG, ITEM_NULL, ITEM_OBJECT, ITEM_TOP,
+    ITEM_UNINITIALIZED, ITEM_UNINITIALIZED_THIS,
+};
+use crate::opcodes::jvm_opcodes_instruction::{array_type_name, get_opcodes, JvmOperands};
+use crate::util::{be_to_u16, be_to_u32, be_to_u64, get_bytes, get_header};
+
+/// Class files are read sequentially, every field is big endian
+fn take_bytes<'a>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+    size: usize,
+) -> Result<&'a [u8], ParseError> {
+    let data = get_bytes(bytes, *offset, size)?;
+    *offset += size;
+    Ok(data)
+}
+
+fn take_u8(bytes: &[u8], offset: &mut usize) -> Result<u8, ParseError> {
+    Ok(take_bytes(bytes, offset, 1)?[0])
+}
+
+fn take_u16(bytes: &[u8], offset: &mut usize) -> Result<u16, ParseError> {
+    Ok(be_to_u16(take_bytes(bytes, offset, 2)?))
+}
+
+fn take_u32(bytes: &[u8], offset: &mut usize) -> Result<u32, ParseError> {
+    Ok(be_to_u32(take_bytes(bytes, offset, 4)?))
+}
+
+/// Class files store strings in modified UTF-8: NUL is encoded on two bytes and
+/// supplementary characters as two 3 bytes surrogates
+pub fn decode_modified_utf8(bytes: &[u8]) -> String {
+    let mut units: Vec<u16> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i] as u16;
+        let next = |index: usize| bytes.get(i + index).map(|&byte| byte as u16 & 0x3F);
+        match byte {
+            0x00..=0x7F => {
+                units.push(byte);
+                i += 1;
+            }
+            0xC0..=0xDF if next(1).is_some() => {
+                units.push((byte & 0x1F) << 6 | next(1).unwrap_or(0));
+                i += 2;
+            }
+            0xE0..=0xEF if next(2).is_some() => {
+                units.push((byte & 0x0F) << 12 | next(1).unwrap_or(0) << 6 | next(2).unwrap_or(0));
+                i += 3;
+            }
+            _ => {
+                units.push(0xFFFD);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+pub fn extract_constant_pool(
+    bytes: &[u8],
+    offset: &mut usize,
+    pool_count: u16,
+) -> Result<Vec<ConstantPoolEntry>, ParseError> {
+    let mut pool = vec![ConstantPoolEntry::Unusable];
+    while pool.len() < pool_count as usize {
+        let tag_offset = *offset;
+        let tag = take_u8(bytes, offset)?;
+        let entry = match tag {
+            CONSTANT_UTF8 => {
+                let length = take_u16(bytes, offset)? as usize;
+                ConstantPoolEntry::Utf8(decode_modified_utf8(take_bytes(bytes, offset, length)?))
+            }
+            CONSTANT_INTEGER => ConstantPoolEntry::Integer(take_u32(bytes, offset)? as i32),
+            CONSTANT_FLOAT => ConstantPoolEntry::Float(f32::from_bits(take_u32(bytes, offset)?)),
+            CONSTANT_LONG => {
+                ConstantPoolEntry::Long(be_to_u64(take_bytes(bytes, offset, 8)?) as i64)
+            }
+            CONSTANT_DOUBLE => {
+                ConstantPoolEntry::Double(f64::from_bits(be_to_u64(take_bytes(bytes, offset, 8)?)))
+            }
+            CONSTANT_CLASS => ConstantPoolEntry::Class {
+                name_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_STRING => ConstantPoolEntry::String {
+                string_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_FIELDREF => ConstantPoolEntry::Fieldref {
+                class_index: take_u16(bytes, offset)?,
+                name_and_type_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_METHODREF => ConstantPoolEntry::Methodref {
+                class_index: take_u16(bytes, offset)?,
+                name_and_type_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_INTERFACE_METHODREF => ConstantPoolEntry::InterfaceMethodref {
+                class_index: take_u16(bytes, offset)?,
+                name_and_type_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_NAME_AND_TYPE => ConstantPoolEntry::NameAndType {
+                name_index: take_u16(bytes, offset)?,
+                descriptor_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_METHOD_HANDLE => ConstantPoolEntry::MethodHandle {
+                reference_kind: take_u8(bytes, offset)?,
+                reference_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_METHOD_TYPE => ConstantPoolEntry::MethodType {
+                descriptor_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_DYNAMIC => ConstantPoolEntry::Dynamic {
+                bootstrap_method_attr_index: take_u16(bytes, offset)?,
+                name_and_type_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_INVOKE_DYNAMIC => ConstantPoolEntry::InvokeDynamic {
+                bootstrap_method_attr_index: take_u16(bytes, offset)?,
+                name_and_type_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_MODULE => ConstantPoolEntry::Module {
+                name_index: take_u16(bytes, offset)?,
+            },
+            CONSTANT_PACKAGE => ConstantPoolEntry::Package {
+                name_index: take_u16(bytes, offset)?,
+            },
+            _ => {
+                return Err(ParseError::InvalidTag {
+                    structure: "constant pool",
+                    offset: tag_offset,
+                    tag,
+                })
+            }
+        };
+        pool.push(entry);
+        // 8 bytes constants take two slots
+        if tag == CONSTANT_LONG || tag == CONSTANT_DOUBLE {
+            pool.push(ConstantPoolEntry::Unusable);
+        }
+    }
+    Ok(pool)
+}
+
+pub fn constant_utf8(pool: &[ConstantPoolEntry], index: u16) -> Result<String, ParseError> {
+    match pool.get(index as usize) {
+        Some(ConstantPoolEntry::Utf8(string)) => Ok(string.clone()),
+        _ => Err(ParseError::InvalidStringIndex {
+            table: "constant pool",
+            index: index as usize,
+        }),
+    }
+}
+
+/// Internal name (java/lang/Object) of a CONSTANT_Class entry
+pub fn constant_class_name(pool: &[ConstantPoolEntry], index: u16) -> Result<String, ParseError> {
+    match pool.get(index as usize) {
+        Some(ConstantPoolEntry::Class { name_index }) => constant_utf8(pool, *name_index),
+        _ => Err(ParseError::InvalidStringIndex {
+            table: "constant pool",
+            index: index as usize,
+        }),
+    }
+}
+
+/// Class, member name and descriptor of a field, method or interface method reference
+pub fn constant_member_ref(
+    pool: &[ConstantPoolEntry],
+    index: u16,
+) -> Result<(String, String, String), ParseError> {
+    let (class_index, name_and_type_index) = match pool.get(index as usize) {
+        Some(ConstantPoolEntry::Fieldref {
+            class_index,
+            name_and_type_index,
+        })
+        | Some(ConstantPoolEntry::Methodref {
+            class_index,
+            name_and_type_index,
+        })
+        | Some(ConstantPoolEntry::InterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        }) => (*class_index, *name_and_type_index),
+        _ => {
+            return Err(ParseError::InvalidStringIndex {
+                table: "constant pool",
+                index: index as usize,
+            })
+        }
+    };
+    let (name, descriptor) = constant_name_and_type(pool, name_and_type_index)?;
+    Ok((constant_class_name(pool, class_index)?, name, descriptor))
+}
+
+pub fn constant_name_and_type(
+    pool: &[ConstantPoolEntry],
+    index: u16,
+) -> Result<(String, String), ParseError> {
+    match pool.get(index as usize) {
+        Some(ConstantPoolEntry::NameAndType {
+            name_index,
+            descriptor_index,
+        }) => Ok((
+            constant_utf8(pool, *name_index)?,
+            constant_utf8(pool, *descriptor_index)?,
+        )),
+        _ => Err(ParseError::InvalidStringIndex {
+            table: "constant pool",
+            index: index as usize,
+        }),
+    }
+}
+
+fn extract_verification_type(
+    bytes: &[u8],
+    offset: &mut usize,
+) -> Result<VerificationType, ParseError> {
+    let tag_offset = *offset;
+    Ok(match take_u8(bytes, offset)? {
+        ITEM_TOP => VerificationType::Top,
+        ITEM_INTEGER => VerificationType::Integer,
+        ITEM_FLOAT => VerificationType::Float,
+        ITEM_DOUBLE => VerificationType::Double,
+        ITEM_LONG => VerificationType::Long,
+        ITEM_NULL => VerificationType::Null,
+        ITEM_UNINITIALIZED_THIS => VerificationType::UninitializedThis,
+        ITEM_OBJECT => VerificationType::Object(take_u16(bytes, offset)?),
+        ITEM_UNINITIALIZED => VerificationType::Uninitialized(take_u16(bytes, offset)?),
+        tag => {
+            return Err(ParseError::InvalidTag {
+                structure: "verification type",
+                offset: tag_offset,
+                tag,
+            })
+        }
+    })
+}
+
+fn extract_verification_types(
+    bytes: &[u8],
+    offset: &mut usize,
+    count: usize,
+) -> Result<Vec<VerificationType>, ParseError> {
+    (0..count)
+        .map(|_| extract_verification_type(bytes, offset))
+        .collect()
+}
+
+pub fn extract_stack_map_table(data: &[u8]) -> Result<Vec<StackMapFrame>, ParseError> {
+    let mut offset = 0;
+    let count = take_u16(data, &mut offset)?;
+    let mut frames = Vec::new();
+    for _ in 0..count {
+        let frame_offset = offset;
+        let frame_type = take_u8(data, &mut offset)?;
+        frames.push(match frame_type {
+            0..=63 => StackMapFrame::Same {
+                offset_delta: frame_type as u16,
+            },
+            64..=127 => StackMapFrame::SameLocals1StackItem {
+                offset_delta: frame_type as u16 - 64,
+                stack: extract_verification_type(data, &mut offset)?,
+            },
+            247 => StackMapFrame::SameLocals1StackItem {
+                offset_delta: take_u16(data, &mut offset)?,
+                stack: extract_verification_type(data, &mut offset)?,
+            },
+            248..=250 => StackMapFrame::Chop {
+                offset_delta: take_u16(data, &mut offset)?,
+                chopped: 251 - frame_type,
+            },
+            251 => StackMapFrame::Same {
+                offset_delta: take_u16(data, &mut offset)?,
+            },
+            252..=254 => {
+                let offset_delta = take_u16(data, &mut offset)?;
+                StackMapFrame::Append {
+                    offset_delta,
+                    locals: extract_verification_types(
+                        data,
+                        &mut offset,
+                        (frame_type - 251) as usize,
+                    )?,
+                }
+            }
+            255 => {
+                let offset_delta = take_u16(data, &mut offset)?;
+                let local_count = take_u16(data, &mut offset)? as usize;
+                let locals = extract_verification_types(data, &mut offset, local_count)?;
+                let stack_count = take_u16(data, &mut offset)? as usize;
+                StackMapFrame::Full {
+                    offset_delta,
+                    locals,
+                    stack: extract_verification_types(data, &mut offset, stack_count)?,
+                }
+            }
+            _ => {
+                return Err(ParseError::InvalidTag {
+                    structure: "stack map frame",
+                    offset: frame_offset,
+                    tag: frame_type,
+                })
+            }
+        });
+    }
+    Ok(frames)
+}
+
+pub fn extract_code_attribute<'a>(
+    data: &'a [u8],
+    pool: &[ConstantPoolEntry],
+) -> Result<CodeAttribute<'a>, ParseError> {
+    let mut offset = 0;
+    let max_stack = take_u16(data, &mut offset)?;
+    let max_locals = take_u16(data, &mut offset)?;
+    let code_length = take_u32(data, &mut offset)? as usize;
+    let code = take_bytes(data, &mut offset, code_length)?;
+    let exception_count = take_u16(data, &mut offset)?;
+    let mut exception_table = Vec::new();
+    for _ in 0..exception_count {
+        exception_table.push(ExceptionTableEntry {
+            start_pc: take_u16(data, &mut offset)?,
+            end_pc: take_u16(data, &mut offset)?,
+            handler_pc: take_u16(data, &mut offset)?,
+            catch_type: take_u16(data, &mut offset)?,
+        });
+    }
+    Ok(CodeAttribute {
+        max_stack,
+        max_locals,
+        code,
+        exception_table,
+        attributes: extract_attributes(data, &mut offset, pool)?,
+    })
+}
+
+fn extract_attribute_content<'a>(
+    name: &str,
+    data: &'a [u8],
+    pool: &[ConstantPoolEntry],
+) -> Result<AttributeContent<'a>, ParseError> {
+    let mut offset = 0;
+    Ok(match name {
+        "Code" => AttributeContent::Code(extract_code_attribute(data, pool)?),
+        "LineNumberTable" => {
+            let count = take_u16(data, &mut offset)?;
+            let mut lines = Vec::new();
+            for _ in 0..count {
+                lines.push(LineNumber {
+                    start_pc: take_u16(data, &mut offset)?,
+                    line_number: take_u16(data, &mut offset)?,
+                });
+            }
+            AttributeContent::LineNumberTable(lines)
+        }
+        "StackMapTable" => AttributeContent::StackMapTable(extract_stack_map_table(data)?),
+        "BootstrapMethods" => {
+            let count = take_u16(data, &mut offset)?;
+            let mut methods = Vec::new();
+            for _ in 0..count {
+                let method_ref = take_u16(data, &mut offset)?;
+                let argument_count = take_u16(data, &mut offset)?;
+                methods.push(BootstrapMethod {
+                    method_ref,
+                    arguments: (0..argument_count)
+                        .map(|_| take_u16(data, &mut offset))
+                        .collect::<Result<_, _>>()?,
+                });
+            }
+            AttributeContent::BootstrapMethods(methods)
+        }
+        "SourceFile" => {
+            AttributeContent::SourceFile(constant_utf8(pool, take_u16(data, &mut offset)?)?)
+        }
+        _ => AttributeContent::Unknown,
+    })
+}
+
+/// Reads an attributes_count followed by its attribute_info structures
+pub fn extract_attributes<'a>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+    pool: &[ConstantPoolEntry],
+) -> Result<Vec<AttributeInfo<'a>>, ParseError> {
+    let count = take_u16(bytes, offset)?;
+    let mut attributes = Vec::new();
+    for _ in 0..count {
+        let name = constant_utf8(pool, take_u16(bytes, offset)?)?;
+        let length = take_u32(bytes, offset)? as usize;
+        let raw_data = take_bytes(bytes, offset, length)?;
+        attributes.push(AttributeInfo {
+            content: extract_attribute_content(&name, raw_data, pool)?,
+            name,
+            raw_data,
+        });
+    }
+    Ok(attributes)
+}
+
+/// Reads a fields_count or methods_count followed by its field_info or method_info structures
+pub fn extract_members<'a>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+    pool: &[ConstantPoolEntry],
+) -> Result<Vec<MemberInfo<'a>>, ParseError> {
+    let count = take_u16(bytes, offset)?;
+    let mut members = Vec::new();
+    for _ in 0..count {
+        members.push(MemberInfo {
+            access_flags: take_u16(bytes, offset)?,
+            name: constant_utf8(pool, take_u16(bytes, offset)?)?,
+            descriptor: constant_utf8(pool, take_u16(bytes, offset)?)?,
+            attributes: extract_attributes(bytes, offset, pool)?,
+        });
+    }
+    Ok(members)
+}
+
+pub fn extract_class_file(bytes: &[u8]) -> Result<ClassFile<'_>, ParseError> {
+    let header = get_header(bytes, 0, CLASS_HEADER_SIZE, "class file header")?;
+    if &header[0..4] != CLASS_MAGIC {
+        return Err(ParseError::BadMagic {
+            format: "Java class file",
+            found: header[0..4].to_vec(),
+        });
+    }
+    let mut offset = CLASS_HEADER_SIZE;
+    let constant_pool = extract_constant_pool(bytes, &mut offset, be_to_u16(&header[8..10]))?;
+
+    let access_flags = take_bytes(bytes, &mut offset, 2)?;
+    let this_class = take_bytes(bytes, &mut offset, 2)?;
+    let super_class = take_bytes(bytes, &mut offset, 2)?;
+    let super_index = be_to_u16(super_class);
+    let super_name = if super_index == 0 {
+        None
+    } else {
+        Some(constant_class_name(&constant_pool, super_index)?)
+    };
+
+    let interfaces_count = get_bytes(bytes, offset, 2)?;
+    let mut interfaces = Vec::new();
+    for _ in 0..take_u16(bytes, &mut offset)? {
+        interfaces.push(constant_class_name(
+            &constant_pool,
+            take_u16(bytes, &mut offset)?,
+        )?);
+    }
+    let fields_count = get_bytes(bytes, offset, 2)?;
+    let fields = extract_members(bytes, &mut offset, &constant_pool)?;
+    let method_count = get_bytes(bytes, offset, 2)?;
+    let methods = extract_members(bytes, &mut offset, &constant_pool)?;
+    let attributes_count = get_bytes(bytes, offset, 2)?;
+    let attributes = extract_attributes(bytes, &mut offset, &constant_pool)?;
+
+    Ok(ClassFile {
+        magic: &header[0..4],
+        minor_version: &header[4..6],
+        major_version: &header[6..8],
+        pool_count: &header[8..10],
+        name: constant_class_name(&constant_pool, be_to_u16(this_class))?,
+        constant_pool,
+        access_flags,
+        this_class,
+        super_class,
+        super_name,
+        interfaces_count,
+        interfaces,
+        fields_count,
+        fields,
+        method_count,
+        methods,
+        attributes_count,
+        attributes,
+    })
+}
+
+fn branch_target(code: &[u8], offset: usize, delta: i64) -> Result<usize, ParseError> {
+    usize::try_from(offset as i64 + delta).map_err(|_| ParseError::OutOfRange {
+        offset,
+        size: 0,
+        file_size: code.len(),
+    })
+}
+
+/// Decodes a `Code` attribute bytecode, switch padding is computed from the start of the
+/// code like the JVM does
+pub fn disassemble_jvm_code(code: &[u8]) -> Result<Vec<JvmInstruction>, ParseError> {
+    let opcodes = get_opcodes();
+    let lookup = |opcode: u8, offset: usize| {
+        opcodes
+            .get(&opcode)
+            .copied()
+            .ok_or(ParseError::InvalidOpcode {
+                table: "JVM",
+                offset,
+                opcode,
+            })
+    };
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < code.len() {
+        let start = offset;
+        let mut opcode = take_u8(code, &mut offset)?;
+        let (mut mnemonic, mut layout) = lookup(opcode, start)?;
+        let wide = layout == JvmOperands::Wide;
+        if wide {
+            opcode = take_u8(code, &mut offset)?;
+            (mnemonic, layout) = lookup(opcode, start + 1)?;
+            if layout != JvmOperands::Local && layout != JvmOperands::Iinc {
+                return Err(ParseError::InvalidOpcode {
+                    table: "JVM wide",
+                    offset: start + 1,
+                    opcode,
+                });
+            }
+        }
+        let take_local = |offset: &mut usize| -> Result<u16, ParseError> {
+            if wide {
+                take_u16(code, offset)
+            } else {
+                Ok(take_u8(code, offset)? as u16)
+            }
+        };
+        let operands = match layout {
+            JvmOperands::None => Vec::new(),
+            JvmOperands::Byte => vec![JvmOperand::Immediate(
+                take_u8(code, &mut offset)? as i8 as i32
+            )],
+            JvmOperands::Short => vec![JvmOperand::Immediate(
+                take_u16(code, &mut offset)? as i16 as i32
+            )],
+            JvmOperands::Local => vec![JvmOperand::Local(take_local(&mut offset)?)],
+            JvmOperands::ConstantByte => {
+                vec![JvmOperand::Constant(take_u8(code, &mut offset)? as u16)]
+            }
+            JvmOperands::Constant => vec![JvmOperand::Constant(take_u16(code, &mut offset)?)],
+            JvmOperands::Branch => {
+                let delta = take_u16(code, &mut offset)? as i16 as i64;
+                vec![JvmOperand::Target(branch_target(code, start, delta)?)]
+            }
+            JvmOperands::BranchWide => {
+                let delta = take_u32(code, &mut offset)? as i32 as i64;
+                vec![JvmOperand::Target(branch_target(code, start, delta)?)]
+            }
+            JvmOperands::Iinc => {
+                let local = take_local(&mut offset)?;
+                let increment = if wide {
+                    take_u16(code, &mut offset)? as i16 as i32
+                } else {
+                    take_u8(code, &mut offset)? as i8 as i32
+                };
+                vec![JvmOperand::Local(local), JvmOperand::Immediate(increment)]
+            }
+            JvmOperands::InvokeInterface => {
+                let index = take_u16(code, &mut offset)?;
+                let count = take_u8(code, &mut offset)?;
+                take_u8(code, &mut offset)?;
+                vec![
+                    JvmOperand::Constant(index),
+                    JvmOperand::Immediate(count as i32),
+                ]
+            }
+            JvmOperands::InvokeDynamic => {
+                let index = take_u16(code, &mut offset)?;
+                take_u16(code, &mut offset)?;
+                vec![JvmOperand::Constant(index)]
+            }
+            JvmOperands::NewArray => {
+                let atype = take_u8(code, &mut offset)?;
+                vec![JvmOperand::ArrayType(array_type_name(atype).ok_or(
+                    ParseError::InvalidTag {
+                        structure: "newarray type",
+                        offset: offset - 1,
+                        tag: atype,
+                    },
+                )?)]
+            }
+            JvmOperands::MultiANewArray => {
+                let index = take_u16(code, &mut offset)?;
+                let dimensions = take_u8(code, &mut offset)?;
+                vec![
+                    JvmOperand::Constant(index),
+                    JvmOperand::Immediate(dimensions as i32),
+                ]
+            }
+            JvmOperands::TableSwitch | JvmOperands::LookupSwitch => {
+                offset = (offset + 3) & !3;
+                let default = take_u32(code, &mut offset)? as i32 as i64;
+                let default = branch_target(code, start, default)?;
+                let (range, count) = if layout == JvmOperands::TableSwitch {
+                    let low = take_u32(code, &mut offset)? as i32;
+                    let high = take_u32(code, &mut offset)? as i32;
+                    (Some((low, high)), high as i64 - low as i64 + 1)
+                } else {
+                    (None, take_u32(code, &mut offset)? as i32 as i64)
+                };
+                let entry_size = if range.is_some() { 4 } else { 8 };
+                if count < 0 {
+                    return Err(ParseError::InvalidSize {
+                        structure: mnemonic,
+                        offset: start,
+                        size: count as usize,
+                    });
+                }
+                // checked before allocating, the count comes straight from the file
+                let table = take_bytes(
+                    code,
+                    &mut offset,
+                    (count as usize).saturating_mul(entry_size),
+                )?;
+                let mut cases = Vec::with_capacity(count as usize);
+                for (index, entry) in table.chunks_exact(entry_size).enumerate() {
+                    let (key, delta) = match range {
+                        Some((low, _)) => (low.wrapping_add(index as i32), be_to_u32(entry)),
+                        None => (be_to_u32(&entry[0..4]) as i32, be_to_u32(&entry[4..8])),
+                    };
+                    cases.push((key, branch_target(code, start, delta as i32 as i64)?));
+                }
+                vec![JvmOperand::Switch {
+                    range,
+                    cases,
+                    default,
+                }]
+            }
+            JvmOperands::Wide => {
+                return Err(ParseError::InvalidOpcode {
+                    table: "JVM wide",
+                    offset: start + 1,
+                    opcode,
+                })
+            }
+        };
+        instructions.push(JvmInstruction {
+            offset: start,
+            opcode,
+            mnemonic,
+            wide,
+            operands,
+        });
+    }
+    Ok(instructions)
+}
+
+fn method_handle_kind(reference_kind: u8) -> &'static str {
+    match reference_kind {
+        1 => "REF_getField",
+        2 => "REF_getStatic",
+        3 => "REF_putField",
+        4 => "REF_putStatic",
+        5 => "REF_invokeVirtual",
+        6 => "REF_invokeStatic",
+        7 => "REF_invokeSpecial",
+        8 => "REF_newInvokeSpecial",
+        9 => "REF_invokeInterface",
+        _ => "REF_unknown",
+    }
+}
+
+/// Float.toString and Double.toString digits, as javap prints constants: the shortest
+/// digits that read back to the same value, with ties on the even digit and at least two
+/// digits in scientific notation. Integral values take FloatingDecimal's long path
+/// instead, which only drops the low digits the mantissa width makes insignificant.
+fn java_floating(value: f64, shortest: String, significand_bits: i32) -> String {
+    if value.is_nan() {
+        return String::from("NaN");
+    }
+    if value.is_infinite() {
+        return String::from(if value > 0.0 { "Infinity" } else { "-Infinity" });
+    }
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    let magnitude = value.abs();
+    if magnitude == 0.0 {
+        return format!("{}0.0", sign);
+    }
+    // digits and decimal exponent of a Rust {:e} rendering, as 0.ddd * 10^exponent
+    let decimal = |text: &str| match text.trim_start_matches('-').split_once('e') {
+        Some((mantissa, exponent)) => (
+            mantissa.replace('.', ""),
+            exponent.parse::<i32>().unwrap_or(0) + 1,
+        ),
+        None => (text.to_string(), 1),
+    };
+    let binary_exponent = magnitude.log2().floor() as i32;
+    let (digits, exponent) = if magnitude.fract() == 0.0 && binary_exponent < 63 {
+        // floor(log10(2^n)) digits are beyond the mantissa precision, rounded half up
+        let insignificant = match binary_exponent - significand_bits - 1 {
+            power @ 2.. => power as u32 * 30103 / 100000,
+            _ => 0,
+        };
+        let scale = 10u64.pow(insignificant);
+        let mut integral = magnitude as u64 / scale;
+        if insignificant > 0 && magnitude as u64 % scale >= scale / 2 {
+            integral += 1;
+        }
+        let digits = integral.to_string();
+        let exponent = digits.len() as i32 + insignificant as i32;
+        (digits, exponent)
+    } else {
+        let (digits, exponent) = decimal(&shortest);
+        let length = if (-2..8).contains(&exponent) {
+            digits.len()
+        } else {
+            digits.len().max(2)
+        };
+        if length > 1 {
+            decimal(&format!("{:.*e}", length - 1, magnitude))
+        } else {
+            (digits, exponent)
+        }
+    };
+    let digits = digits.trim_end_matches('0');
+    let body = if (1..8).contains(&exponent) {
+        let split = exponent as usize;
+        if digits.len() <= split {
+            format!("{}{}.0", digits, "0".repeat(split - digits.len()))
+        } else {
+            format!("{}.{}", &digits[..split], &digits[split..])
+        }
+    } else if (-2..=0).contains(&exponent) {
+        format!("0.{}{}", "0".repeat(-exponent as usize), digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() { "0" } else { rest };
+        format!("{}.{}E{}", first, rest, exponent - 1)
+    };
+    format!("{}{}", sign, body)
+}
+
+/// javap quotes special method names and array classes
+fn quote_name(name: &str) -> String {
+    if name.starts_with('<') || name.starts_with('[') {
+        format!("\"{}\"", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Readable form of a constant, as javap prints it in its comments. References to
+/// members of the class itself omit the class name.
+pub fn constant_description(class: &ClassFile, index: u16) -> Result<String, ParseError> {
+    let pool = &class.constant_pool;
+    let member = |index: u16| -> Result<String, ParseError> {
+        let (class_name, name, descriptor) = constant_member_ref(pool, index)?;
+        Ok(if class_name == class.name {
+            format!("{}:{}", quote_name(&name), descriptor)
+        } else {
+            format!(
+                "{}.{}:{}",
+                quote_name(&class_name),
+                quote_name(&name),
+                descriptor
+            )
+        })
+    };
+    Ok(match pool.get(index as usize) {
+        Some(ConstantPoolEntry::Utf8(string)) => format!("Utf8 {}", string),
+        Some(ConstantPoolEntry::Integer(value)) => format!("int {}", value),
+        Some(ConstantPoolEntry::Float(value)) => format!(
+            "float {}f",
+            java_floating(*value as f64, format!("{:e}", value), 24)
+        ),
+        Some(ConstantPoolEntry::Long(value)) => format!("long {}l", value),
+        Some(ConstantPoolEntry::Double(value)) => format!(
+            "double {}d",
+            java_floating(*value, format!("{:e}", value), 53)
+        ),
+        Some(ConstantPoolEntry::Class { .. }) => {
+            format!("class {}", quote_name(&constant_class_name(pool, index)?))
+        }
+        Some(ConstantPoolEntry::String { string_index }) => {
+            let string = constant_utf8(pool, *string_index)?;
+            let escaped: String = string
+                .chars()
+                .map(|character| match character {
+                    '"' => String::from("\\\""),
+                    '\'' => String::from("\\'"),
+                    '\\' => String::from("\\\\"),
+                    '\n' => String::from("\\n"),
+                    '\t' => String::from("\\t"),
+                    '\r' => String::from("\\r"),
+                    _ if character.is_control() => format!("\\u{:04x}", character as u32),
+                    _ => character.to_string(),
+                })
+                .collect();
+            format!("String {}", escaped)
+        }
+        Some(ConstantPoolEntry::Fieldref { .. }) => format!("Field {}", member(index)?),
+        Some(ConstantPoolEntry::Methodref { .. }) => format!("Method {}", member(index)?),
+        Some(ConstantPoolEntry::InterfaceMethodref { .. }) => {
+            format!("InterfaceMethod {}", member(index)?)
+        }
+        Some(ConstantPoolEntry::NameAndType { .. }) => {
+            let (name, descriptor) = constant_name_and_type(pool, index)?;
+            format!("NameAndType {}:{}", quote_name(&name), descriptor)
+        }
+        Some(ConstantPoolEntry::MethodHandle {
+            reference_kind,
+            reference_index,
+        }) => format!(
+            "MethodHandle {}:{}",
+            method_handle_kind(*reference_kind),
+            member(*reference_index)?
+        ),
+        Some(ConstantPoolEntry::MethodType { descriptor_index }) => {
+            format!("MethodType {}", constant_utf8(pool, *descriptor_index)?)
+        }
+        Some(ConstantPoolEntry::Dynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        })
+        | Some(ConstantPoolEntry::InvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }) => {
+            let kind = if matches!(pool[index as usize], ConstantPoolEntry::Dynamic { .. }) {
+                "Dynamic"
+            } else {
+                "InvokeDynamic"
+            };
+            let (name, descriptor) = constant_name_and_type(pool, *name_and_type_index)?;
+            format!(
+                "{} #{}:{}:{}",
+                kind, bootstrap_method_attr_index, name, descriptor
+            )
+        }
+        Some(ConstantPoolEntry::Module { name_index }) => {
+            format!("Module {}", constant_utf8(pool, *name_index)?)
+        }
+        Some(ConstantPoolEntry::Package { name_index }) => {
+            format!("Package {}", constant_utf8(pool, *name_index)?)
+        }
+        Some(ConstantPoolEntry::Unusable) | None => {
+            return Err(ParseError::InvalidStringIndex {
+                table: "constant pool",
+                index: index as usize,
+            })
+        }
+    })
+}
+
+/// javap -c layout: one line per instruction, switch tables spread over several lines
+pub fn format_jvm_instruction(class: &ClassFile, instruction: &JvmInstruction) -> String {
+    let mnemonic = if instruction.wide {
+        format!("{}_w", instruction.mnemonic)
+    } else {
+        instruction.mnemonic.to_string()
+    };
+    let mut operands: Vec<String> = Vec::new();
+    let mut comment = None;
+    for operand in instruction.operands.iter() {
+        match operand {
+            JvmOperand::Immediate(value) => operands.push(value.to_string()),
+            JvmOperand::Local(index) => operands.push(index.to_string()),
+            JvmOperand::Target(target) => operands.push(target.to_string()),
+            // javap leaves an extra space before the newarray element type
+            JvmOperand::ArrayType(name) => operands.push(format!(" {}", name)),
+            JvmOperand::Constant(index) => {
+                operands.push(format!("#{}", index));
+                comment = Some(
+                    constant_description(class, *index).unwrap_or_else(|error| error.to_string()),
+                );
+            }
+            JvmOperand::Switch {
+                range,
+                cases,
+                default,
+            } => {
+                let mut text = match range {
+                    Some((low, high)) => format!("{{ // {} to {}", low, high),
+                    None => format!("{{ // {}", cases.len()),
+                };
+                for (key, target) in cases.iter() {
+                    text.push_str(&format!("\n{:>22}: {}", key, target));
+                }
+                text.push_str(&format!("\n{:>22}: {}\n{:>11}", "default", default, "}"));
+                operands.push(text);
+            }
+        }
+    }
+    // invokeinterface, invokedynamic and multianewarray separate their operands widely
+    let separator = if comment.is_some() { ",  " } else { ", " };
+    let operands = operands.join(separator);
+    let operands = match instruction.mnemonic {
+        "invokedynamic" => format!("{},  0", operands),
+        _ => operands,
+    };
+    let text = if operands.is_empty() {
+        mnemonic
+    } else {
+        format!("{:<13} {}", mnemonic, operands)
+    };
+    let line = format!("    {:>4}: {}", instruction.offset, text);
+    match comment {
+        Some(comment) => format!("{:<44}// {}", line, comment),
+        None => line,
+    }
+}
+
+/// Listing of a method `Code` attribute, empty for abstract and native methods
+pub fn jvm_method_listing(
+    class: &ClassFile,
+    method: &MemberInfo,
+) -> Result<Vec<String>, ParseError> {
+    let mut listing = Vec::new();
+    for attribute in method.attributes.iter() {
+        if let AttributeContent::Code(code) = &attribute.content {
+            for instruction in disassemble_jvm_code(code.code)?.iter() {
+                listing.push(format_jvm_instruction(class, instruction));
+            }
+            if !code.exception_table.is_empty() {
+                listing.push(String::from("    Exception table:"));
+                listing.push(String::from("       from    to  target type"));
+            }
+            for entry in code.exception_table.iter() {
+                // catch type 0 is a finally block
+                let catch_type = match entry.catch_type {
+                    0 => String::from("any"),
+                    index => format!(
+                        "Class {}",
+                        constant_class_name(&class.constant_pool, index)?
+                    ),
+                };
+                listing.push(format!(
+                    "{:>12}{:>6}{:>6}   {}",
+                    entry.start_pc, entry.end_pc, entry.handler_pc, catch_type
+                ));
+            }
+        }
+    }
+    Ok(listing)
+}
+
+/// Adds the methods and fields of a class as symbols, its references to members of other
+/// classes as imports and its public members as exports. Class files have no addresses,
+/// every address is 0.
+pub fn add_class_to_model(
+    class: &ClassFile,
+    symbols: &mut Vec<BinarySymbol>,
+    imports: &mut Vec<BinaryImport>,
+    exports: &mut Vec<BinaryExport>,
+) {
+    let class_public = be_to_u16(class.access_flags) & ACC_PUBLIC != 0;
+    for (members, kind) in [
+        (&class.methods, SymbolKind::Function),
+        (&class.fields, SymbolKind::Object),
+    ] {
+        for member in members.iter() {
+            let name = format!("{}.{}{}", class.name, member.name, member.descriptor);
+            let size = member
+                .attributes
+                .iter()
+                .find_map(|attribute| match &attribute.content {
+                    AttributeContent::Code(code) => Some(code.code.len() as u64),
+                    _ => None,
+                })
+                .unwrap_or(0);
+            if class_public && member.access_flags & ACC_PUBLIC != 0 {
+                exports.push(BinaryExport {
+                    name: name.clone(),
+                    address: 0,
+                });
+            }
+            symbols.push(BinarySymbol {
+                name,
+                demangled_name: None,
+                kind,
+                address: 0,
+                size,
+            });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for index in 0..class.constant_pool.len() {
+        if let Ok((class_name, name, descriptor)) =
+            constant_member_ref(&class.constant_pool, index as u16)
+        {
+            let name = format!("{}{}", name, descriptor);
+            if class_name != class.name && seen.insert((class_name.clone(), name.clone())) {
+                imports.push(BinaryImport {
+                    library: class_name,
+                    name,
+                    address: 0,
+                });
+            }
+        }
+    }
+}
+
+pub fn parse_class(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let class = extract_class_file(bytes)?;
+    let mut symbols = Vec::new();
+    let mut imports = Vec::new();
+    let mut exports = Vec::new();
+    add_class_to_model(&class, &mut symbols, &mut imports, &mut exports);
+
+    Ok(BinaryFile {
+        format: Format::JavaClass,
+        architecture: Architecture::Jvm,
+        endianness: Endianness::Big,
+        bitness: Bitness::Bits32,
+        entry_point: 0,
+        sections: Vec::new(),
+        segments: Vec::new(),
+        symbols,
+        libraries: Vec::new(),
+        imports,
+        exports,
+        details: FileDetails::Jvm(class),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_file::FileDetails;
+
+    fn test_file(name: &str) -> Vec<u8> {
+        std::fs::read(format!(
+            "{}/../testExe/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    fn class_details<'a>(file: &'a BinaryFile<'a>) -> &'a ClassFile<'a> {
+        match &file.details {
+            FileDetails::Jvm(class) => class,
+            _ => unreachable!(),
+        }
+    }
+
+    fn attribute<'a, 'b>(
+        attributes: &'b [AttributeInfo<'a>],
+        name: &str,
+    ) -> &'b AttributeContent<'a> {
+        &attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .unwrap()
+            .content
+    }
+
+    #[test]
+    fn constant_pool_entries_are_decoded() {
+        // record Point(int x, long y) with a lambda, compiled by javac 17
+        let mut bytes = test_file("Java/Point.class");
+        let file = parse_class(&bytes).unwrap();
+        let class = class_details(&file);
+        assert_eq!(class.name, "demo/Point");
+        assert_eq!(class.super_name.as_deref(), Some("java/lang/Record"));
+        let pool = &class.constant_pool;
+        assert_eq!(pool.len(), 106);
+        assert!(matches!(pool[0], ConstantPoolEntry::Unusable));
+        assert!(matches!(
+            pool[1],
+            ConstantPoolEntry::Methodref {
+                class_index: 2,
+                name_and_type_index: 3
+            }
+        ));
+        assert!(matches!(
+            pool[2],
+            ConstantPoolEntry::Class { name_index: 4 }
+        ));
+        assert!(matches!(
+            pool[3],
+            ConstantPoolEntry::NameAndType {
+                name_index: 5,
+                descriptor_index: 6
+            }
+        ));
+        assert!(matches!(&pool[4], ConstantPoolEntry::Utf8(name) if name == "java/lang/Record"));
+        assert!(matches!(
+            pool[7],
+            ConstantPoolEntry::Fieldref {
+                class_index: 8,
+                name_and_type_index: 9
+            }
+        ));
+        assert!(matches!(
+            pool[17],
+            ConstantPoolEntry::InvokeDynamic {
+                bootstrap_method_attr_index: 0,
+                name_and_type_index: 18
+            }
+        ));
+        assert!(matches!(pool[21], ConstantPoolEntry::Integer(100000)));
+        assert!(matches!(
+            pool[22],
+            ConstantPoolEntry::InterfaceMethodref {
+                class_index: 23,
+                name_and_type_index: 24
+            }
+        ));
+        assert!(matches!(pool[27], ConstantPoolEntry::Float(value) if value == 1.5e10));
+        assert!(matches!(pool[28], ConstantPoolEntry::Long(1234567890123)));
+        // longs and doubles take two slots
+        assert!(matches!(pool[29], ConstantPoolEntry::Unusable));
+        assert!(matches!(pool[45], ConstantPoolEntry::Double(value) if value == 0.25));
+        assert!(matches!(pool[46], ConstantPoolEntry::Unusable));
+        assert!(matches!(
+            pool[70],
+            ConstantPoolEntry::MethodHandle {
+                reference_kind: 6,
+                reference_index: 71
+            }
+        ));
+        assert!(matches!(
+            pool[77],
+            ConstantPoolEntry::MethodType {
+                descriptor_index: 26
+            }
+        ));
+        assert!(matches!(
+            pool[87],
+            ConstantPoolEntry::String { string_index: 88 }
+        ));
+
+        // javac does not emit CONSTANT_Dynamic, it shares the layout of InvokeDynamic
+        assert_eq!(bytes[109], 18);
+        bytes[109] = 17;
+        let file = parse_class(&bytes).unwrap();
+        assert!(matches!(
+            class_details(&file).constant_pool[17],
+            ConstantPoolEntry::Dynamic {
+                bootstrap_method_attr_index: 0,
+                name_and_type_index: 18
+            }
+        ));
+
+        let bytes = test_file("Java/module-info.class");
+        let file = parse_class(&bytes).unwrap();
+        let class = class_details(&file);
+        assert_eq!(class.name, "module-info");
+        assert_eq!(class.super_name, None);
+        assert!(matches!(
+            class.constant_pool[6],
+            ConstantPoolEntry::Module { name_index: 7 }
+        ));
+        assert!(matches!(
+            class.constant_pool[11],
+            ConstantPoolEntry::Package { name_index: 7 }
+        ));
+    }
+
+    #[test]
+    fn code_and_class_attributes_are_decoded() {
+        let bytes = test_file("Java/Point.class");
+        let file = parse_class(&bytes).unwrap();
+        let class = class_details(&file);
+        let method = |name: &str| {
+            class
+                .methods
+                .iter()
+                .find(|method| method.name == name)
+                .unwrap()
+        };
+        assert_eq!(method("lambda$sum$0").access_flags, 0x1002);
+
+        let sum = match attribute(&method("sum").attributes, "Code") {
+            AttributeContent::Code(code) => code,
+            content => panic!("{:?}", content),
+        };
+        assert_eq!((sum.max_stack, sum.max_locals), (2, 8));
+        assert_eq!(sum.code.len(), 56);
+        assert!(sum.exception_table.is_empty());
+        match attribute(&sum.attributes, "LineNumberTable") {
+            AttributeContent::LineNumberTable(lines) => {
+                let lines: Vec<(u16, u16)> = lines
+                    .iter()
+                    .map(|line| (line.start_pc, line.line_number))
+                    .collect();
+                assert_eq!(
+                    lines,
+                    [(0, 9), (8, 10), (11, 11), (36, 12), (41, 11), (47, 14)]
+                );
+            }
+            content => panic!("{:?}", content),
+        }
+        match attribute(&sum.attributes, "StackMapTable") {
+            AttributeContent::StackMapTable(frames) => {
+                assert_eq!(frames.len(), 2);
+                match &frames[0] {
+                    StackMapFrame::Full {
+                        offset_delta: 22,
+                        locals,
+                        stack,
+                    } => {
+                        assert_eq!(locals.len(), 7);
+                        assert!(matches!(locals[0], VerificationType::Object(8)));
+                        assert!(matches!(locals[1], VerificationType::Object(54)));
+                        assert!(matches!(locals[3], VerificationType::Integer));
+                        assert!(stack.is_empty());
+                    }
+                    frame => panic!("{:?}", frame),
+                }
+                assert!(matches!(
+                    frames[1],
+                    StackMapFrame::Chop {
+                        offset_delta: 24,
+                        chopped: 3
+                    }
+                ));
+            }
+            content => panic!("{:?}", content),
+        }
+        let squares = match attribute(&method("squares").attributes, "Code") {
+            AttributeContent::Code(code) => code,
+            content => panic!("{:?}", content),
+        };
+        match attribute(&squares.attributes, "StackMapTable") {
+            AttributeContent::StackMapTable(frames) => {
+                assert!(matches!(
+                    &frames[0],
+                    StackMapFrame::Append { offset_delta: 6, locals }
+                        if matches!(
+                            locals[..],
+                            [VerificationType::Object(54), VerificationType::Integer]
+                        )
+                ));
+            }
+            content => panic!("{:?}", content),
+        }
+
+        assert!(matches!(
+            attribute(&class.attributes, "SourceFile"),
+            AttributeContent::SourceFile(name) if name == "Point.java"
+        ));
+        assert!(matches!(
+            attribute(&class.attributes, "Record"),
+            AttributeContent::Unknown
+        ));
+        match attribute(&class.attributes, "BootstrapMethods") {
+            AttributeContent::BootstrapMethods(methods) => {
+                assert_eq!(methods.len(), 3);
+                // the lambda metafactory, then ObjectMethods for the record members
+                assert_eq!(methods[0].method_ref, 70);
+                assert_eq!(methods[0].arguments, [77, 78, 77]);
+                assert_eq!(methods[2].method_ref, 89);
+                assert_eq!(methods[2].arguments, [8, 96, 98, 99]);
+            }
+            content => panic!("{:?}", content),
+        }
+    }
+
+    #[test]
+    fn listing_ends_with_the_exception_table() {
+        let bytes = test_file("Java/Try.class");
+        let file = parse_class(&bytes).unwrap();
+        let class = match &file.details {
+            FileDetails::Jvm(class) => class,
+            _ => unreachable!(),
+        };
+        let method = class
+            .methods
+            .iter()
+            .find(|method| method.name == "f")
+            .unwrap();
+        let listing = jvm_method_listing(class, method).unwrap();
+        assert_eq!(
+            listing[listing.len() - 5..],
+            [
+                "    Exception table:",
+                "       from    to  target type",
+                "           0     5    15   Class java/lang/NumberFormatException",
+                "           0     5    28   any",
+                "          15    18    28   any",
+            ]
+        );
+    }
+
+    #[test]
+    fn listings_match_javap() {
+        let bytes = test_file("Java/Point.class");
+        let file = parse_class(&bytes).unwrap();
+        let class = class_details(&file);
+        let listing = |name: &str| {
+            let method = class
+                .methods
+                .iter()
+                .find(|method| method.name == name)
+                .unwrap();
+            jvm_method_listing(class, method).unwrap()
+        };
+        assert_eq!(
+            listing("scaled"),
+            [
+                "       0: aload_0",
+                "       1: getfield      #7                  // Field x:I",
+                "       4: i2f",
+                "       5: ldc           #27                 // float 1.50000005E10f",
+                "       7: fmul",
+                "       8: freturn",
+            ]
+        );
+        assert_eq!(
+            listing("squares")[..3],
+            [
+                "       0: iload_0",
+                "       1: newarray       int",
+                "       3: astore_1",
+            ]
+        );
+        assert_eq!(listing("squares")[14], "      17: iinc          2, 1");
+    }
+
+    #[test]
+    fn floats_print_like_java() {
+        let float = |value: f32| java_floating(value as f64, format!("{:e}", value), 24);
+        let double = |value: f64| java_floating(value, format!("{:e}", value), 53);
+        assert_eq!(float(1.5e10), "1.50000005E10");
+        assert_eq!(float(1e10), "1.0E10");
+        assert_eq!(float(0.1), "0.1");
+        assert_eq!(float(100.0), "100.0");
+        assert_eq!(float(9999999.0), "9999999.0");
+        assert_eq!(float(1e7), "1.0E7");
+        assert_eq!(float(0.001), "0.001");
+        assert_eq!(float(1e-5), "1.0E-5");
+        assert_eq!(float(-0.0), "-0.0");
+        // ties go to the even digit, scientific notation keeps two digits
+        assert_eq!(float(3812220.0 + 0.25), "3812220.2");
+        assert_eq!(float(f32::MIN_POSITIVE / 8388608.0), "1.4E-45");
+        assert_eq!(double(f64::from_bits(1)), "4.9E-324");
+        assert_eq!(double(123456789012345678.0), "1.2345678901234568E17");
+        assert_eq!(double(f64::NAN), "NaN");
+        assert_eq!(double(f64::NEG_INFINITY), "-Infinity");
+    }
+
+    #[test]
+    fn truncated_class_files_are_rejected() {
+        let bytes = test_file("Java/Try.class");
+        for length in 0..bytes.len() {
+            assert!(parse_class(&bytes[..length]).is_err(), "length {}", length);
+        }
+    }
+
+    #[test]
+    fn hostile_constant_pools_are_rejected() {
+        let mut bytes = test_file("Java/Try.class");
+        bytes[0] = 0xCB;
+        assert!(matches!(
+            parse_class(&bytes),
+            Err(ParseError::BadMagic { .. })
+        ));
+
+        // the tag of the first constant
+        let mut bytes = test_file("Java/Try.class");
+        bytes[10] = 0xFF;
+        assert!(matches!(
+            parse_class(&bytes),
+            Err(ParseError::InvalidTag {
+                structure: "constant pool",
+                offset: 10,
+                tag: 0xFF
+            })
+        ));
+
+        let pool = [ConstantPoolEntry::Unusable, ConstantPoolEntry::Integer(1)];
+        for index in [0, 1, 2, u16::MAX] {
+            assert!(matches!(
+                constant_utf8(&pool, index),
+                Err(ParseError::InvalidStringIndex { .. })
+            ));
+            assert!(constant_class_name(&pool, index).is_err());
+            assert!(constant_member_ref(&pool, index).is_err());
+        }
+    }
+
+    #[test]
+    fn hostile_bytecode_is_rejected() {
+        let invalid_opcode = |code: &[u8]| {
+            matches!(
+                disassemble_jvm_code(code),
+                Err(ParseError::InvalidOpcode { .. })
+            )
+        };
+        assert!(invalid_opcode(&[0xCB]));
+        // wide only applies to local variable instructions
+        assert!(invalid_opcode(&[0xC4, 0x60]));
+        assert!(invalid_opcode(&[0xC4, 0xC4]));
+
+        // truncated operands and a branch before the start of the code
+        assert!(disassemble_jvm_code(&[0x10]).is_err());
+        assert!(disassemble_jvm_code(&[0xC4, 0x15, 0x00]).is_err());
+        assert!(matches!(
+            disassemble_jvm_code(&[0xA7, 0xFF, 0xF0]),
+            Err(ParseError::OutOfRange { .. })
+        ));
+
+        // tableswitch with high < low, lookupswitch with more pairs than the code holds
+        let mut tableswitch = vec![0xAA, 0, 0, 0, 0, 0, 0, 0];
+        tableswitch.extend_from_slice(&5u32.to_be_bytes());
+        tableswitch.extend_from_slice(&1u32.to_be_bytes());
+        assert!(matches!(
+            disassemble_jvm_code(&tableswitch),
+            Err(ParseError::InvalidSize { .. })
+        ));
+        let mut lookupswitch = vec![0xAB, 0, 0, 0, 0, 0, 0, 0];
+        lookupswitch.extend_from_slice(&0x7FFF_FFFFu32.to_be_bytes());
+        assert!(matches!(
+            disassemble_jvm_code(&lookupswitch),
+            Err(ParseError::OutOfRange { .. })
+        ));
+    }
+}
@@ -1,3 +1,240 @@
+pub const CLASS_MAGIC: &[u8] = b"\xCA\xFE\xBA\xBE";
+pub const CLASS_HEADER_SIZE: usize = 10;
+
+pub const CONSTANT_UTF8: u8 = 1;
+pub const CONSTANT_INTEGER: u8 = 3;
+pub const CONSTANT_FLOAT: u8 = 4;
+pub const CONSTANT_LONG: u8 = 5;
+pub const CONSTANT_DOUBLE: u8 = 6;
+pub const CONSTANT_CLASS: u8 = 7;
+pub const CONSTANT_STRING: u8 = 8;
+pub const CONSTANT_FIELDREF: u8 = 9;
+pub const CONSTANT_METHODREF: u8 = 10;
+pub const CONSTANT_INTERFACE_METHODREF: u8 = 11;
+pub const CONSTANT_NAME_AND_TYPE: u8 = 12;
+pub const CONSTANT_METHOD_HANDLE: u8 = 15;
+pub const CONSTANT_METHOD_TYPE: u8 = 16;
+pub const CONSTANT_DYNAMIC: u8 = 17;
+pub const CONSTANT_INVOKE_DYNAMIC: u8 = 18;
+pub const CONSTANT_MODULE: u8 = 19;
+pub const CONSTANT_PACKAGE: u8 = 20;
+
+pub const ACC_PUBLIC: u16 = 0x0001;
+pub const ACC_PRIVATE: u16 = 0x0002;
+pub const ACC_PROTECTED: u16 = 0x0004;
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_FINAL: u16 = 0x0010;
+pub const ACC_SYNCHRONIZED: u16 = 0x0020;
+pub const ACC_VOLATILE: u16 = 0x0040;
+pub const ACC_TRANSIENT: u16 = 0x0080;
+pub const ACC_NATIVE: u16 = 0x0100;
+pub const ACC_INTERFACE: u16 = 0x0200;
+pub const ACC_ABSTRACT: u16 = 0x0400;
+pub const ACC_STRICT: u16 = 0x0800;
+pub const ACC_SYNTHETIC: u16 = 0x1000;
+pub const ACC_ANNOTATION: u16 = 0x2000;
+pub const ACC_ENUM: u16 = 0x4000;
+
+pub const ITEM_TOP: u8 = 0;
+pub const ITEM_INTEGER: u8 = 1;
+pub const ITEM_FLOAT: u8 = 2;
+pub const ITEM_DOUBLE: u8 = 3;
+pub const ITEM_LONG: u8 = 4;
+pub const ITEM_NULL: u8 = 5;
+pub const ITEM_UNINITIALIZED_THIS: u8 = 6;
+pub const ITEM_OBJECT: u8 = 7;
+pub const ITEM_UNINITIALIZED: u8 = 8;
+
+/// Decoded operand of a bytecode instruction
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum JvmOperand {
+    /// bipush, sipush, iinc increment, invokeinterface count, multianewarray dimensions
+    Immediate(i32),
+    Local(u16),
+    Constant(u16),
+    /// Absolute bytecode offset of a branch
+    Target(usize),
+    ArrayType(&'static str),
+    Switch {
+        /// low and high of a tableswitch, None for a lookupswitch
+        range: Option<(i32, i32)>,
+        cases: Vec<(i32, usize)>,
+        default: usize,
+    },
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct JvmInstruction {
+    pub offset: usize,
+    pub opcode: u8,
+    pub mnemonic: &'static str,
+    /// Prefixed by `wide`, 16 bits local index
+    pub wide: bool,
+    pub operands: Vec<JvmOperand>,
+}
+
+/// Constant pool entry, indexes point back into the pool
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum ConstantPoolEntry {
+    Utf8(String),
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    Class {
+        name_index: u16,
+    },
+    String {
+        string_index: u16,
+    },
+    Fieldref {
+        class_index: u16,
+        name_and_type_index: u16,
+    },
+    Methodref {
+        class_index: u16,
+        name_and_type_index: u16,
+    },
+    InterfaceMethodref {
+        class_index: u16,
+        name_and_type_index: u16,
+    },
+    NameAndType {
+        name_index: u16,
+        descriptor_index: u16,
+    },
+    MethodHandle {
+        reference_kind: u8,
+        reference_index: u16,
+    },
+    MethodType {
+        descriptor_index: u16,
+    },
+    Dynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
+    InvokeDynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
+    Module {
+        name_index: u16,
+    },
+    Package {
+        name_index: u16,
+    },
+    /// Index 0 and the slot following a Long or a Double
+    Unusable,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ExceptionTableEntry {
+    pub start_pc: u16,
+    pub end_pc: u16,
+    pub handler_pc: u16,
+    /// 0 catches everything (finally blocks)
+    pub catch_type: u16,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct CodeAttribute<'a> {
+    pub max_stack: u16,
+    pub max_locals: u16,
+    pub code: &'a [u8],
+    pub exception_table: Vec<ExceptionTableEntry>,
+    pub attributes: Vec<AttributeInfo<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct LineNumber {
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum VerificationType {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    /// Constant pool index of the class
+    Object(u16),
+    /// Offset of the `new` instruction
+    Uninitialized(u16),
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum StackMapFrame {
+    Same {
+        offset_delta: u16,
+    },
+    SameLocals1StackItem {
+        offset_delta: u16,
+        stack: VerificationType,
+    },
+    Chop {
+        offset_delta: u16,
+        chopped: u8,
+    },
+    Append {
+        offset_delta: u16,
+        locals: Vec<VerificationType>,
+    },
+    Full {
+        offset_delta: u16,
+        locals: Vec<VerificationType>,
+        stack: Vec<VerificationType>,
+    },
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct BootstrapMethod {
+    pub method_ref: u16,
+    pub arguments: Vec<u16>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum AttributeContent<'a> {
+    Code(CodeAttribute<'a>),
+    LineNumberTable(Vec<LineNumber>),
+    StackMapTable(Vec<StackMapFrame>),
+    BootstrapMethods(Vec<BootstrapMethod>),
+    SourceFile(String),
+    Unknown,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct AttributeInfo<'a> {
+    pub name: String,
+    pub raw_data: &'a [u8],
+    pub content: AttributeContent<'a>,
+}
+
+/// field_info or method_info
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct MemberInfo<'a> {
+    pub access_flags: u16,
+    pub name: String,
+    pub descriptor: String,
+    pub attributes: Vec<AttributeInfo<'a>>,
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct ClassFile<'a> {
@@ -5,16 +242,20 @@ pub struct ClassFile<'a> {
     pub minor_version: &'a [u8],
     pub major_version: &'a [u8],
     pub pool_count: &'a [u8],
-    //todo add pool struct
+    /// Indexed like the JVM does, entry 0 is `Unusable`
+    pub constant_pool: Vec<ConstantPoolEntry>,
     pub access_flags: &'a [u8],
     pub this_class: &'a [u8],
     pub super_class: &'a [u8],
+    pub name: String,
+    /// None for java/lang/Object and module-info
+    pub super_name: Option<String>,
     pub interfaces_count: &'a [u8],
-    //todo add interfaces struct
+    pub interfaces: Vec<String>,
     pub fields_count: &'a [u8],
-    //todo add field struct
+    pub fields: Vec<MemberInfo<'a>>,
     pub method_count: &'a [u8],
-    //todo add method struct
+    pub methods: Vec<MemberInfo<'a>>,
     pub attributes_count: &'a [u8],
-    //todo add attribute struct
+    pub attributes: Vec<AttributeInfo<'a>>,
 }
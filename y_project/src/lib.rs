@@ -1,59 +1,52 @@
-use std::collections::HashMap;
 use std::env;
 use std::fs;
 
+pub mod ar_parser;
+pub mod ar_structure;
+pub mod binary_file;
+pub mod elf_parser;
 pub mod elf_structure;
+pub mod error;
+pub mod inflate;
+pub mod jvm_parser;
 pub mod jvm_structure;
+pub mod macho_parser;
 pub mod macho_structure;
+pub mod opcodes;
+pub mod pe_parser;
 pub mod pe_structure;
 pub mod signature;
+pub mod util;
+pub mod zip_parser;
+pub mod zip_structure;
 
-use crate::pe_structure::COFFHeader;
-use crate::pe_structure::COFFString;
-use crate::pe_structure::COFFStringTable;
-use crate::pe_structure::DOSHeader;
-use crate::pe_structure::DataDirectoryEntry;
-use crate::pe_structure::OptionalHeader;
-use crate::pe_structure::PEFile;
-use crate::pe_structure::RessourceDir;
-use crate::pe_structure::Section;
-use crate::pe_structure::SectionTable;
-use crate::pe_structure::StringTable;
-use crate::pe_structure::Symbol;
-use crate::pe_structure::SymbolTable;
-
-use crate::signature::SIGNATURES;
-
-
-use crate::elf_structure::ELFHeader;
-use crate::elf_structure::ELFIdentification;
-use crate::elf_structure::FileInfoELF;
-
-use crate::macho_structure::MachOHeader;
+use crate::binary_file::{BinaryFile, Endianness, FileDetails};
+use crate::elf_structure::{ELFGnuProperty, ELFNoteContent};
+use crate::error::ParseError;
+use crate::jvm_structure::{AttributeContent, ClassFile};
+use crate::signature::{SignatureDatabase, SignatureMatch};
 
 // context
-struct Ctx {
-    filename: String,
-    byte: bool,
+pub struct Ctx {
+    pub filename: String,
+    pub byte: bool,
+    pub disassemble: bool,
+    /// User signature file, added to the built-in signatures
+    pub signatures: Option<String>,
 }
 
 /****************************************************************************************/
 /******************************** Code Functions ****************************************/
 /****************************************************************************************/
 
-fn reverse_bytes<T: Clone>(slice: &[T]) -> Vec<T> {
-    slice.iter().cloned().rev().collect()
-}
-
-
-pub fn read_file(file_path: &String) -> Vec<u8> {
+pub fn read_file(file_path: &String) -> Result<Vec<u8>, ParseError> {
     println!("*[+] Reading file...");
-    let bytes = fs::read(file_path.to_owned()).unwrap();
+    let bytes = fs::read(file_path)?;
     // for byte in bytes.iter() {
     //     print!("{:X} ", byte);
     // }
     // println!();
-    bytes
+    Ok(bytes)
 }
 
 pub fn help() {
@@ -61,256 +54,337 @@ pub fn help() {
         "Usage:
 -f <filename> - read file
 -b            - show output in byte code
+-d            - disassemble the methods of a Java class file or JAR
+-s <filename> - load additional signatures
 "
     );
 }
 
-fn get_arguments() -> Ctx {
+pub fn get_arguments() -> Ctx {
     let args: Vec<String> = env::args().collect();
     let mut ctx = Ctx {
         filename: String::new(),
         byte: false,
+        disassemble: false,
+        signatures: None,
     };
     if args.len() <= 2 {
         eprintln!("Usage: {} -f <filename>", args[0]);
         std::process::exit(1);
     }
     for i in 0..args.len() {
-        if args[i] == "-f" {
+        if args[i] == "-f" && i + 1 < args.len() {
             ctx.filename = args[i + 1].clone();
         }
         if args[i] == "-b" {
             ctx.byte = true;
         }
+        if args[i] == "-d" {
+            ctx.disassemble = true;
+        }
+        if args[i] == "-s" && i + 1 < args.len() {
+            ctx.signatures = Some(args[i + 1].clone());
+        }
         // println!("arg {} - {}", i, args[i]);
     }
     ctx
 }
 
-pub fn get_sign(bytes: &[u8]) -> String {
-    println!("*[+] Obtaining file signature...");
-    let mut buffer = [0; 1024];
+/// Built-in signatures plus the user signature file given with `-s`
+pub fn load_signatures(context: &Ctx) -> Result<SignatureDatabase, ParseError> {
+    let mut database = SignatureDatabase::builtin();
+    if let Some(path) = &context.signatures {
+        let count = database.load_file(path)?;
+        println!("*[+] {} signatures loaded from {}", count, path);
+    }
+    Ok(database)
+}
 
-    let mut file_signature: String = String::from("unknown");
+pub fn get_sign(database: &SignatureDatabase, bytes: &[u8]) -> String {
+    println!("*[+] Obtaining file signature...");
+    let candidates = database.find_all(bytes);
+    for candidate in candidates.iter() {
+        println!(
+            "*[+] Candidate: {} (priority {}, confidence {}%)",
+            candidate.signature.name, candidate.signature.priority, candidate.confidence
+        );
+    }
+    let file_signature = best_signature(&candidates);
+    println!("*[+] File signature detected: {}", file_signature);
+    file_signature
+}
 
-    let mut symbol_table_for_offset = 0;
-    while symbol_table_for_offset < bytes.len() {
-        let bytes_copy = std::cmp::min(buffer.len(), bytes.len() - symbol_table_for_offset);
+fn best_signature(candidates: &[SignatureMatch]) -> String {
+    candidates
+        .first()
+        .map_or(String::from("unknown"), |candidate| {
+            candidate.signature.name.clone()
+        })
+}
 
-        buffer[..bytes_copy]
-            .copy_from_slice(&bytes[symbol_table_for_offset..symbol_table_for_offset + bytes_copy]);
+/// Parses any supported binary into the format agnostic `BinaryFile` model
+pub fn parse(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let database = SignatureDatabase::builtin();
+    parse_with_signature(&best_signature(&database.find_all(bytes)), bytes)
+}
 
-        for signature in SIGNATURES.iter() {
-            if bytes_copy >= signature.signature.len()
-                && &buffer[0..signature.signature.len()] == signature.signature
-            {
-                file_signature = String::from(signature.name);
-            }
+pub fn parse_with_signature<'a>(
+    file_signature: &str,
+    bytes: &'a [u8],
+) -> Result<BinaryFile<'a>, ParseError> {
+    match file_signature {
+        "DOS MZ executable" => pe_parser::parse_pe(bytes),
+        "Executable and Linkable Format (ELF)" => elf_parser::parse_elf(bytes),
+        "Mach-O binary (32-bit)"
+        | "Mach-O binary (64-bit)"
+        | "Mach-O binary (reverse byte ordering scheme, 32-bit)"
+        | "Mach-O binary (reverse byte ordering scheme, 64-bit)" => {
+            macho_parser::parse_macho(bytes)
         }
-
-        symbol_table_for_offset += bytes_copy;
+        // The first slice, `macho_parser::parse_fat_slice` picks any other one
+        "Mach-O universal binary" | "Mach-O universal binary (64-bit)" => {
+            macho_parser::parse_fat_slice(bytes, 0)
+        }
+        "Java class file" => jvm_parser::parse_class(bytes),
+        "ZIP archive" => zip_parser::parse_zip(bytes),
+        "COFF object file" => pe_parser::parse_coff_object(bytes),
+        "Windows import object" => pe_parser::parse_import_object(bytes),
+        "Unix ar archive" | "Unix ar archive (thin)" => ar_parser::parse_ar(bytes),
+        "UEFI Terse Executable (TE)" => pe_parser::parse_te(bytes),
+        // kernels built with the EFI stub are PE images as well
+        "Linux kernel bzImage" if bytes.starts_with(b"MZ") => pe_parser::parse_pe(bytes),
+        _ => Err(ParseError::UnsupportedFormat(file_signature.to_string())),
     }
-    println!("*[+] File signature detected: {}", file_signature);
-    file_signature
 }
 
-
 pub fn get_file_data(file_signature: &str, bytes: &[u8]) {
     println!("*[+] Obtaining file infos...");
-    match file_signature {
-        "DOS MZ executable" => {
-            let dos_header = extract_dos_header(bytes);
-
-            let dos_stub = extract_dos_stub(bytes, dos_header.pe_offset);
-
-            let coff_header = extract_coff_header(bytes, dos_header.pe_offset);
-
-            let opt_header = extract_opt_header(bytes, dos_header.pe_offset);
-
-            let symbol_table =
-                extract_symbol_table(bytes, dos_header.pe_offset, coff_header.clone());
-
-            let string_table = extract_string_table(bytes, coff_header.clone());
-
-            let mut section_table =
-                extract_section_table(bytes, dos_header.pe_offset, coff_header.clone());
-
-            let mut sections_data = SectionsData {
-                sections: HashMap::new(),
-            };
-
-            replace_section_names(&string_table, &mut section_table);
-
-            extract_section_datas(bytes, &mut section_table, &mut sections_data);
-
-            if let Some(SectionData::Text(text_data)) = sections_data.sections.get(".text") {
-                // println!("Extracted .text section data: {:?}", text_data.extracted_code);
+    if file_signature.starts_with("Mach-O universal binary") {
+        match macho_parser::parse_fat(bytes) {
+            Ok(fat) => {
+                for (index, arch) in fat.architectures.iter().enumerate() {
+                    println!(
+                        "Slice {} {:?} offset {:#x} size {:#x}",
+                        index,
+                        macho_parser::fat_arch_architecture(arch),
+                        util::read_uint(arch.offset, Endianness::Big),
+                        util::read_uint(arch.size, Endianness::Big)
+                    );
+                }
             }
+            Err(error) => eprintln!("*[-] {}", error),
+        }
+    }
+    let file = match parse_with_signature(file_signature, bytes) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("*[-] {}", error);
+            return;
+        }
+    };
 
-            if let Some(SectionData::Rsrc(rsrc_data)) = sections_data.sections.get(".rsrc") {
-                // println!("Extracted .rsrc section data: {:?}", rsrc_data);
+    println!(
+        "File Infos: {:?} {:?} {:?} {:?}, entry point {:#x}",
+        file.format, file.architecture, file.bitness, file.endianness, file.entry_point
+    );
+    for section in file.sections.iter() {
+        println!(
+            "Section {:<20} address {:#010x} size {:#x}",
+            section.name, section.virtual_address, section.virtual_size
+        );
+    }
+    for segment in file.segments.iter() {
+        println!(
+            "Segment {:<20} address {:#010x} size {:#x}",
+            segment.name, segment.virtual_address, segment.virtual_size
+        );
+    }
+    for library in file.libraries.iter() {
+        println!("Library {}", library);
+    }
+    if let FileDetails::Elf(elf) = &file.details {
+        for note in elf.notes.iter() {
+            match &note.content {
+                ELFNoteContent::GnuBuildId(build_id) => println!("Build ID {}", build_id),
+                ELFNoteContent::GnuProperties(properties) => {
+                    for property in properties.iter() {
+                        match property {
+                            ELFGnuProperty::X86Feature1 { ibt, shstk } => {
+                                println!("CET IBT {} SHSTK {}", ibt, shstk)
+                            }
+                            ELFGnuProperty::AArch64Feature1 { bti, pac } => {
+                                println!("BTI {} PAC {}", bti, pac)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
             }
-
-            if let Some(SectionData::IData(idata_data)) = sections_data.sections.get(".idata") {
-                // println!("Extracted .idata section data: {:?}", idata_data);
+        }
+    }
+    if let FileDetails::Jvm(class) = &file.details {
+        println!(
+            "Class {} extends {} version {}.{}",
+            class.name,
+            class.super_name.as_deref().unwrap_or("-"),
+            util::be_to_u16(class.major_version),
+            util::be_to_u16(class.minor_version)
+        );
+        for attribute in class.attributes.iter() {
+            if let AttributeContent::SourceFile(source_file) = &attribute.content {
+                println!("Source file {}", source_file);
             }
-
-            let string_table_offset = symbol_table_pointer + (18 * symbol_count);
-
-            let mut string_table = StringTable {
-                length: le_to_usize(&bytes[string_table_offset..string_table_offset + 4]),
-                strings: Vec::new(),
-            };
-
-            let entire_string_table = String::from_utf8_lossy(
-                &bytes[string_table_offset + 4..string_table_offset + string_table.length],
+        }
+        for method in class.methods.iter() {
+            println!("Method {}{}", method.name, method.descriptor);
+        }
+    }
+    if let FileDetails::Zip(zip) = &file.details {
+        for entry in zip.entries.iter() {
+            println!(
+                "Entry {:<50} method {} size {:#x} compressed {:#x}",
+                entry.name, entry.compression_method, entry.uncompressed_size, entry.compressed_size
             );
-
-            string_table.strings = entire_string_table
-                .split('\0')
-                .map(|s| s.to_string())
-                .collect();
-
-            let mut text_section_data: &[u8] = &[];
-
-            for section in section_table.sections.iter_mut() {
-                if section.name.starts_with("/") {
-                    let mut name: String = section.name.to_string();
-                    name = name.trim_start_matches("/").to_string();
-                    let index: usize = name.parse().unwrap();
-                    section.name = string_table.strings[index].clone();
-                }
-
-                match section.name.as_str() {
-                    ".text" => {
-                        let mut text_section_data: &[u8] = &[];
-                        text_section_data = section.raw_data;
-                    }
-                    ".rsrc" => {
-                        let mut rsrc_section_data: &[u8] = &[];
-                        rsrc_section_data = section.raw_data;
-                        let rsrc_dir = RessourceDir {
-                            characteristics: &rsrc_section_data[0..4],
-                            time_data_stamp: &rsrc_section_data[4..8],
-                            major_version: &rsrc_section_data[8..10],
-                            minor_version: &rsrc_section_data[10..12],
-                            name_entries_number: le_to_usize(&rsrc_section_data[12..14]),
-                            id_entries_number: le_to_usize(&rsrc_section_data[14..16]),
-                        };
-                        // println!("Rsrc Data: {:?}", rsrc_section_data);
-                        // println!("Rsrc Dir: {:?}", rsrc_dir);
-
-                        // fonction récursive qui lis un directory, on lui donne un offset.
-
-                        // elle lis le nombre d'entrée de nom,
-                        // si c'est un subdirectory alors elle se rappelle elle même
-                        // sinon elle stocke les données de la name entry
-
-                        // elle lis le nombre d'entrée d'id
-                        // si c'est un subdirectory alors elle se rappelle elle même
-                        // sinon elle stocke les données de l'id entry
-
-                        // si bit poids fort == 1 alors entrée de donnée sinon subdir
-                        // les 31 autres bits sont l'offset des données
+        }
+        if let Some(manifest) = &zip.manifest {
+            for (name, value) in manifest.main_attributes.iter() {
+                println!("Manifest {}: {}", name, value);
+            }
+        }
+        println!("{} classes", zip.classes.len());
+    }
+    if let FileDetails::Ar(ar) = &file.details {
+        for member in ar.members.iter() {
+            println!("Member {:<40} size {:#x}", member.name, member.size);
+        }
+        for object in ar.objects.iter() {
+            println!(
+                "Object {:<40} {:?} {:?}, {} symbols",
+                object.member_name,
+                object.file.format,
+                object.file.architecture,
+                object.file.symbols.len()
+            );
+        }
+        println!("{} indexed symbols", ar.symbol_index.len());
+    }
+    if let FileDetails::Pe(pe) = &file.details {
+        if let Some(export_table) = &pe.export_table {
+            println!(
+                "Exports of {} (ordinal base {})",
+                export_table.dll_name, export_table.directory.ordinal_base
+            );
+            for function in export_table.functions.iter() {
+                let name = function.name.as_deref().unwrap_or("-");
+                match &function.forwarder {
+                    Some(forwarder) => {
+                        println!(
+                            "Export {:>5} {:<40} -> {}",
+                            function.ordinal, name, forwarder
+                        )
                     }
-
-                    //ToDo: Add common file sections name and extracts their data
-                    _ => println!("Unknown section {}", section.name),
-                    //ToDo: Add extraction of unknow section name by pushing them into a vec containing name and raw data associated
+                    None => println!(
+                        "Export {:>5} {:<40} rva {:#x}",
+                        function.ordinal, name, function.rva
+                    ),
                 }
             }
+        }
+        for resource in pe.resources.data.iter() {
+            println!(
+                "Resource {}/{}/{} size {:#x} codepage {}",
+                pe_parser::resource_type_name(&resource.resource_type),
+                pe_parser::resource_id_name(&resource.name),
+                pe_parser::resource_id_name(&resource.language),
+                resource.data_entry.size,
+                resource.codepage
+            );
+        }
+    }
+    println!(
+        "{} symbols, {} imports, {} exports",
+        file.symbols.len(),
+        file.imports.len(),
+        file.exports.len()
+    );
+}
 
-            //   println!("Dos Header: {:x?}", file_dos_header);
-            //   println!("Dos Stub: {:x?}", file_dos_stub);
-            //   println!("Coff Header: {:x?}", file_coff_header);
-            //   println!("Symbol Table: {:x?}", symbol_table);
-            //   println!("Optionnal Header: {:x?}", file_optional_header);
-            //   println!("Extracted Code: {:x?}", text_section_data);
-            //   println!("Section Table symbol_table_for_offset: {:?}", section_table_offset);
-            //   println!("Section Table: {:?}", section_table);
-            //   println!("String Table: {:?}", string_table);
+pub fn print_disassembly(file_signature: &str, bytes: &[u8]) {
+    let file = match parse_with_signature(file_signature, bytes) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("*[-] {}", error);
+            return;
         }
-        "Executable and Linkable Format (ELF)" => {
-            let file_info_identification: ELFIdentification = ELFIdentification {
-                magic: &bytes[0..4],
-                class: &bytes[4..5],
-                data: &bytes[5..6],
-                version: &bytes[6..7],
-                os_abi: &bytes[7..8],
-                abi_version: &bytes[8..9],
-                padding: &bytes[9..16],
-            };
-            let file_info_header = if file_info_identification.class == b"\x01" {
-                ELFHeader {
-                    file_type: &bytes[16..18],
-                    machine: &bytes[18..20],
-                    version: &bytes[20..22],
-                    entry_point: &bytes[22..26],
-                    program_header_offset: &bytes[26..30],
-                    section_header_offset: &bytes[30..34],
-                    flags: &bytes[34..38],
-                    header_size: &bytes[38..40],
-                    program_header_entry_size: &bytes[40..42],
-                    program_header_entry_count: &bytes[42..44],
-                    section_header_entry_size: &bytes[44..46],
-                    section_header_entry_count: &bytes[46..48],
-                    section_name_string_table_index: &bytes[48..50],
+    };
+    match &file.details {
+        FileDetails::Jvm(class) => print_class_disassembly(class),
+        FileDetails::Zip(zip) => {
+            for entry in zip.entries.iter() {
+                if !zip.classes.iter().any(|class| class.entry_name == entry.name) {
+                    continue;
                 }
-            } else {
-                ELFHeader {
-                    file_type: &bytes[16..18],
-                    machine: &bytes[18..20],
-                    version: &bytes[20..22],
-                    entry_point: &bytes[22..30],
-                    program_header_offset: &bytes[30..38],
-                    section_header_offset: &bytes[38..46],
-                    flags: &bytes[46..50],
-                    header_size: &bytes[50..52],
-                    program_header_entry_size: &bytes[52..54],
-                    program_header_entry_count: &bytes[54..56],
-                    section_header_entry_size: &bytes[56..58],
-                    section_header_entry_count: &bytes[58..60],
-                    section_name_string_table_index: &bytes[60..62],
+                let data = match zip_parser::extract_zip_entry_data(entry) {
+                    Ok(data) => data,
+                    Err(error) => {
+                        eprintln!("*[-] {}", error);
+                        continue;
+                    }
+                };
+                match jvm_parser::extract_class_file(&data) {
+                    Ok(class) => {
+                        println!("{}:", entry.name);
+                        print_class_disassembly(&class);
+                    }
+                    Err(error) => eprintln!("*[-] {}", error),
                 }
-            };
-            let file_dos_header: FileInfoELF = FileInfoELF {
-                identification: file_info_identification,
-                header: file_info_header,
-            };
-
-            println!("File Infos: {:?}", file_dos_header);
-            //TODO: Extract Code
+            }
         }
-        "Mach-O binary (32-bit)" | "Mach-O binary (64-bit)" => {
-            let file_dos_header: MachOHeader = MachOHeader {
-                magic: &bytes[0..4],
-                cputype: &bytes[4..8],
-                cpusubtype: &bytes[8..12],
-                ftype: &bytes[12..16],
-                lcnum: &bytes[16..20],
-                lcsize: &bytes[20..24],
-                flags: &bytes[24..28],
-            };
-            println!("File Infos: {:?}", file_dos_header);
-            //TODO: Extract code
+        _ => eprintln!("*[-] disassembly is only available for Java class files and JARs"),
+    }
+}
+
+fn print_class_disassembly(class: &ClassFile) {
+    for method in class.methods.iter() {
+        println!("  {}{};", method.name, method.descriptor);
+        match jvm_parser::jvm_method_listing(class, method) {
+            Ok(listing) if listing.is_empty() => {}
+            Ok(listing) => {
+                println!("    Code:");
+                for line in listing.iter() {
+                    println!("{}", line);
+                }
+            }
+            Err(error) => eprintln!("*[-] {}", error),
         }
-        "Mach-O binary (reverse byte ordering scheme, 32-bit)"
-        | "Mach-O binary (reverse byte ordering scheme, 64-bit)" => {
-            let file_dos_header: MachOHeader = MachOHeader {
-                magic: &reverse_bytes(&bytes[0..4]),
-                cputype: &reverse_bytes(&bytes[4..8]),
-                cpusubtype: &reverse_bytes(&bytes[8..12]),
-                ftype: &reverse_bytes(&bytes[12..16]),
-                lcnum: &reverse_bytes(&bytes[16..20]),
-                lcsize: &reverse_bytes(&bytes[20..24]),
-                flags: &reverse_bytes(&bytes[24..28]),
-            };
-            println!("File Infos: {:?}", file_dos_header);
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(bytes: &[u8]) -> String {
+        best_signature(&SignatureDatabase::builtin().find_all(bytes))
+    }
+
+    #[test]
+    fn cafebabe_tells_universal_binaries_from_class_files() {
+        // nfat_arch of a universal binary, below 32
+        for nfat_arch in [1u32, 2, 31] {
+            let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE];
+            bytes.extend_from_slice(&nfat_arch.to_be_bytes());
+            bytes.resize(64, 0);
+            assert_eq!(signature(&bytes), "Mach-O universal binary");
         }
-        "Java class file, Mach-O Fat Binary" => {
-            //TODO: Search infos
+        // minor then major version of a class file, from JDK 1.0.2 (45) to Java 17 (61)
+        for major in [45u16, 52, 61] {
+            let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0];
+            bytes.extend_from_slice(&major.to_be_bytes());
+            bytes.resize(64, 0);
+            assert_eq!(signature(&bytes), "Java class file");
         }
-        _ => {}
     }
 }
-
@@ -0,0 +1,2167 @@
+use crate::binary_file::{
+    Architecture, BinaryExport, BinaryFile, BinaryImport, BinarySection, BinarySegment,
+    BinarySymbol, Bitness, Endianness, FileDetails, Format, SymbolKind,
+};
+use crate::error::ParseError;
+use crate::macho_structure::{
+    DyldInfoCommand, DylibCommand, DysymtabCommand, EncryptionInfoCommand, EntryPointCommand,
+    FatArch, FatHeader, FileInfoFat, FileInfoMachO, LinkEditDataCommand, LoadCommand,
+    LoadCommandData, MachOBind, MachOBindKind, MachODyldInfo, MachOExport, MachOHeader,
+    MachOIndirectEntry, MachORebase, MachOSymbol, MachOSymbolTable, PrebindChecksumCommand,
+    RoutineCommand, RunPathCommand, SectionCommand, SegmentCommand, SymsegCommand, SymtabCommand,
+    ThreadCommand, UUIDCommand, VersionMinCommand,
+};
+use crate::macho_structure::{
+    BIND_IMMEDIATE_MASK, BIND_OPCODE_ADD_ADDR_ULEB, BIND_OPCODE_DONE, BIND_OPCODE_DO_BIND,
+    BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED, BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB,
+    BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB, BIND_OPCODE_MASK, BIND_OPCODE_SET_ADDEND_SLEB,
+    BIND_OPCODE_SET_DYLIB_ORDINAL_IMM, BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB,
+    BIND_OPCODE_SET_DYLIB_SPECIAL_IMM, BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB,
+    BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM, BIND_OPCODE_SET_TYPE_IMM, BIND_OPCODE_THREADED,
+    BIND_SYMBOL_FLAGS_WEAK_IMPORT, BIND_TYPE_POINTER,
+};
+use crate::macho_structure::{
+    DYLD_CHAINED_IMPORT, DYLD_CHAINED_IMPORT_ADDEND, DYLD_CHAINED_IMPORT_ADDEND64,
+    DYLD_CHAINED_PTR_32, DYLD_CHAINED_PTR_64, DYLD_CHAINED_PTR_64_OFFSET, DYLD_CHAINED_PTR_ARM64E,
+    DYLD_CHAINED_PTR_ARM64E_USERLAND, DYLD_CHAINED_PTR_ARM64E_USERLAND24,
+    DYLD_CHAINED_PTR_START_MULTI, DYLD_CHAINED_PTR_START_NONE,
+};
+use crate::macho_structure::{
+    DYNAMIC_LOOKUP_ORDINAL, EXECUTABLE_ORDINAL, INDIRECT_SYMBOL_ABS, INDIRECT_SYMBOL_LOCAL,
+    NLIST_64_SIZE, NLIST_SIZE, N_ABS, N_EXT, N_PEXT, N_SECT, N_STAB, N_TYPE, N_UNDF,
+    SELF_LIBRARY_ORDINAL, S_ATTR_PURE_INSTRUCTIONS, S_ATTR_SOME_INSTRUCTIONS,
+    S_LAZY_DYLIB_SYMBOL_POINTERS, S_LAZY_SYMBOL_POINTERS, S_NON_LAZY_SYMBOL_POINTERS,
+    S_SYMBOL_STUBS, S_THREAD_LOCAL_VARIABLE_POINTERS,
+};
+use crate::macho_structure::{
+    EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE, EXPORT_SYMBOL_FLAGS_KIND_MASK, EXPORT_SYMBOL_FLAGS_REEXPORT,
+    EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER,
+};
+use crate::macho_structure::{
+    FAT_ARCH_64_SIZE, FAT_ARCH_SIZE, FAT_HEADER_SIZE, FAT_MAGIC, FAT_MAGIC_64,
+};
+use crate::macho_structure::{
+    LC_CODE_SIGNATURE, LC_DATA_IN_CODE, LC_DYLD_CHAINED_FIXUPS, LC_DYLD_EXPORTS_TRIE, LC_DYLD_INFO,
+    LC_DYLD_INFO_ONLY, LC_DYLIB_CODE_SIGN_DRS, LC_DYSYMTAB, LC_ENCRYPTION_INFO,
+    LC_ENCRYPTION_INFO_64, LC_FUNCTION_STARTS, LC_ID_DYLIB, LC_LAZY_LOAD_DYLIB,
+    LC_LINKER_OPTIMIZATION_HINT, LC_LOAD_DYLIB, LC_LOAD_UPWARD_DYLIB, LC_LOAD_WEAK_DYLIB, LC_MAIN,
+    LC_PREBIND_CKSUM, LC_REEXPORT_DYLIB, LC_ROUTINES, LC_ROUTINES_64, LC_RPATH, LC_SEGMENT,
+    LC_SEGMENT_64, LC_SEGMENT_SPLIT_INFO, LC_SYMSEG, LC_SYMTAB, LC_THREAD, LC_UNIXTHREAD, LC_UUID,
+    LC_VERSION_MIN_IPHONEOS, LC_VERSION_MIN_MACOSX, LC_VERSION_MIN_TVOS, LC_VERSION_MIN_WATCHOS,
+    MH_HEADER_64_SIZE, MH_HEADER_SIZE,
+};
+use crate::macho_structure::{
+    REBASE_IMMEDIATE_MASK, REBASE_OPCODE_ADD_ADDR_IMM_SCALED, REBASE_OPCODE_ADD_ADDR_ULEB,
+    REBASE_OPCODE_DONE, REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB, REBASE_OPCODE_DO_REBASE_IMM_TIMES,
+    REBASE_OPCODE_DO_REBASE_ULEB_TIMES, REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB,
+    REBASE_OPCODE_MASK, REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB, REBASE_OPCODE_SET_TYPE_IMM,
+    REBASE_TYPE_POINTER,
+};
+use crate::macho_structure::{
+    SECTION_64_SIZE, SECTION_SIZE, SECTION_TYPE, SEGMENT_COMMAND_64_SIZE, SEGMENT_COMMAND_SIZE,
+    S_GB_ZEROFILL, S_THREAD_LOCAL_ZEROFILL, S_ZEROFILL,
+};
+use crate::util::{
+    get_bytes, get_header, read_c_string, read_sleb128, read_u16, read_u32, read_u64, read_uint,
+    read_uleb128,
+};
+
+pub fn extract_macho_header(bytes: &[u8]) -> Result<MachOHeader<'_>, ParseError> {
+    let header = get_header(bytes, 0, MH_HEADER_SIZE, "Mach-O header")?;
+    if !matches!(
+        &header[0..4],
+        b"\xFE\xED\xFA\xCE" | b"\xFE\xED\xFA\xCF" | b"\xCE\xFA\xED\xFE" | b"\xCF\xFA\xED\xFE"
+    ) {
+        return Err(ParseError::BadMagic {
+            format: "Mach-O",
+            found: header[0..4].to_vec(),
+        });
+    }
+    let reserved = if is_macho64(&header[0..4]) {
+        &get_header(bytes, 0, MH_HEADER_64_SIZE, "Mach-O header")?[28..32]
+    } else {
+        &header[28..28]
+    };
+    Ok(MachOHeader {
+        magic: &header[0..4],
+        cputype: &header[4..8],
+        cpusubtype: &header[8..12],
+        ftype: &header[12..16],
+        lcnum: &header[16..20],
+        lcsize: &header[20..24],
+        flags: &header[24..28],
+        reserved,
+    })
+}
+
+/// MH_MAGIC_64 ends by 0xCF whatever the byte ordering is
+fn is_macho64(magic: &[u8]) -> bool {
+    magic.contains(&0xCF)
+}
+
+/// "FE ED FA CE" on disk is a big endian file, "CE FA ED FE" the reverse byte ordering scheme
+fn macho_endianness(magic: &[u8]) -> Endianness {
+    if magic[0] == 0xFE {
+        Endianness::Big
+    } else {
+        Endianness::Little
+    }
+}
+
+fn cpu_architecture(cputype: u32) -> Architecture {
+    match cputype {
+        7 => Architecture::X86,
+        0x0100_0007 => Architecture::X86_64,
+        12 => Architecture::Arm,
+        0x0100_000C => Architecture::AArch64,
+        18 => Architecture::PowerPC,
+        0x0100_0012 => Architecture::PowerPC64,
+        _ => Architecture::Unknown(cputype),
+    }
+}
+
+/// Cuts consecutive fields of the given sizes following `cmd` and `cmdsize` out of a load
+/// command, checking first that they all fit in `cmdsize`
+fn command_fields<'a, const N: usize>(
+    bytes: &'a [u8],
+    offset: usize,
+    command_size: usize,
+    sizes: [usize; N],
+    structure: &'static str,
+) -> Result<[&'a [u8]; N], ParseError> {
+    let total_size: usize = 8 + sizes.iter().sum::<usize>();
+    if total_size > command_size {
+        return Err(ParseError::InvalidSize {
+            structure,
+            offset,
+            size: command_size,
+        });
+    }
+    let data = get_header(bytes, offset, total_size, structure)?;
+    let mut fields = [&data[0..0]; N];
+    let mut field_offset = 8;
+    for (field, size) in fields.iter_mut().zip(sizes) {
+        *field = &data[field_offset..field_offset + size];
+        field_offset += size;
+    }
+    Ok(fields)
+}
+
+/// lc_str values are offsets from the start of the load command
+fn load_command_string(
+    bytes: &[u8],
+    offset: usize,
+    command_size: usize,
+    string_offset: &[u8],
+    endianness: Endianness,
+) -> Result<String, ParseError> {
+    let command = get_bytes(bytes, offset, command_size)?;
+    let index = read_u32(string_offset, endianness) as usize;
+    if index >= command.len() {
+        return Err(ParseError::InvalidStringIndex {
+            table: "Mach-O load command",
+            index,
+        });
+    }
+    read_c_string(command, index)
+}
+
+/// segname / sectname are 16 bytes, NUL padded only when shorter
+fn fixed_name(name: &[u8]) -> String {
+    let end = name
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(name.len());
+    String::from_utf8_lossy(&name[..end]).to_string()
+}
+
+/// Zero fill sections take memory but have no bytes in the file
+fn is_zerofill(section_flags: u32) -> bool {
+    matches!(
+        section_flags & SECTION_TYPE,
+        S_ZEROFILL | S_GB_ZEROFILL | S_THREAD_LOCAL_ZEROFILL
+    )
+}
+
+/// segment_command / segment_command_64 followed by its `nsects` section / section_64
+fn extract_segment_command<'a>(
+    bytes: &'a [u8],
+    offset: usize,
+    command_size: usize,
+    load_cmd: LoadCommand<'a>,
+    is_segment_64: bool,
+    endianness: Endianness,
+) -> Result<SegmentCommand<'a>, ParseError> {
+    let (word, header_size, section_size) = if is_segment_64 {
+        (8, SEGMENT_COMMAND_64_SIZE, SECTION_64_SIZE)
+    } else {
+        (4, SEGMENT_COMMAND_SIZE, SECTION_SIZE)
+    };
+    let [segname, vmaddr, vmsize, fileoff, filesize, maxprot, initprot, nsects, flags] =
+        command_fields(
+            bytes,
+            offset,
+            command_size,
+            [16, word, word, word, word, 4, 4, 4, 4],
+            "Mach-O segment command",
+        )?;
+
+    let section_count = read_u32(nsects, endianness) as usize;
+    if header_size + section_count * section_size > command_size {
+        return Err(ParseError::InvalidSize {
+            structure: "Mach-O segment command",
+            offset,
+            size: command_size,
+        });
+    }
+    let mut sections = Vec::new();
+    for i in 0..section_count {
+        let section_offset = offset + header_size + i * section_size;
+        let section = get_header(bytes, section_offset, section_size, "Mach-O section")?;
+        let (addr, size) = (&section[32..32 + word], &section[32 + word..32 + 2 * word]);
+        let fields = &section[32 + 2 * word..];
+        let section_flags = read_u32(&fields[16..20], endianness);
+        let raw_data = if is_zerofill(section_flags) {
+            &[]
+        } else {
+            get_bytes(
+                bytes,
+                read_u32(&fields[0..4], endianness) as usize,
+                read_uint(size, endianness) as usize,
+            )?
+        };
+        sections.push(SectionCommand {
+            sectname: &section[0..16],
+            segname: &section[16..32],
+            name: fixed_name(&section[0..16]),
+            segment_name: fixed_name(&section[16..32]),
+            addr,
+            size,
+            symbol_table_for_offset: &fields[0..4],
+            align: &fields[4..8],
+            reloff: &fields[8..12],
+            nreloc: &fields[12..16],
+            flags: &fields[16..20],
+            reserved1: &fields[20..24],
+            reserved2: &fields[24..28],
+            reserved3: &fields[28..],
+            raw_data,
+        });
+    }
+
+    Ok(SegmentCommand {
+        load_cmd,
+        segname,
+        name: fixed_name(segname),
+        vmaddr,
+        vmsize,
+        fileoff,
+        filesize,
+        maxprot,
+        initprot,
+        nsects,
+        flags,
+        raw_data: get_bytes(
+            bytes,
+            read_uint(fileoff, endianness) as usize,
+            read_uint(filesize, endianness) as usize,
+        )?,
+        sections,
+    })
+}
+
+fn extract_load_command<'a>(
+    bytes: &'a [u8],
+    offset: usize,
+    is_64: bool,
+    endianness: Endianness,
+) -> Result<LoadCommandData<'a>, ParseError> {
+    let header = get_header(bytes, offset, 8, "Mach-O load command")?;
+    let (cmd, cmdsize) = (&header[0..4], &header[4..8]);
+    let command = read_u32(cmd, endianness);
+    let command_size = read_u32(cmdsize, endianness) as usize;
+    let load_cmd = LoadCommand { cmd, cmdsize };
+    // pointer sized fields
+    let word = if is_64 { 8 } else { 4 };
+
+    Ok(match command {
+        LC_SEGMENT | LC_SEGMENT_64 => LoadCommandData::Segment(extract_segment_command(
+            bytes,
+            offset,
+            command_size,
+            load_cmd,
+            command == LC_SEGMENT_64,
+            endianness,
+        )?),
+        LC_SYMTAB => {
+            let [symoff, nsyms, stroff, strsize] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4, 4, 4, 4],
+                "Mach-O symtab command",
+            )?;
+            LoadCommandData::Symtab(SymtabCommand {
+                load_cmd,
+                symoff,
+                nsyms,
+                stroff,
+                strsize,
+            })
+        }
+        LC_SYMSEG => {
+            let [symbol_table_for_offset, size] =
+                command_fields(bytes, offset, command_size, [4, 4], "Mach-O symseg command")?;
+            LoadCommandData::Symseg(SymsegCommand {
+                load_cmd,
+                symbol_table_for_offset,
+                size,
+            })
+        }
+        LC_THREAD | LC_UNIXTHREAD => {
+            let [flavor, count] =
+                command_fields(bytes, offset, command_size, [4, 4], "Mach-O thread command")?;
+            LoadCommandData::Thread(ThreadCommand {
+                load_cmd,
+                flavor,
+                count,
+                state: get_bytes(bytes, offset + 16, command_size - 16)?,
+            })
+        }
+        LC_DYSYMTAB => {
+            let fields = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4; 18],
+                "Mach-O dysymtab command",
+            )?;
+            LoadCommandData::Dysymtab(DysymtabCommand {
+                load_cmd,
+                ilocalsym: fields[0],
+                nlocalsym: fields[1],
+                iextdefsym: fields[2],
+                nextdefsim: fields[3],
+                iundefsym: fields[4],
+                nundefsym: fields[5],
+                tocoff: fields[6],
+                ntoc: fields[7],
+                modtaboff: fields[8],
+                nmodtab: fields[9],
+                extrefsymoff: fields[10],
+                nextrefsyms: fields[11],
+                indirectsymoff: fields[12],
+                nindirectsyms: fields[13],
+                extreloff: fields[14],
+                nextre1: fields[15],
+                locreloff: fields[16],
+                nlocre1: fields[17],
+            })
+        }
+        LC_LOAD_DYLIB | LC_ID_DYLIB | LC_LOAD_WEAK_DYLIB | LC_REEXPORT_DYLIB
+        | LC_LAZY_LOAD_DYLIB | LC_LOAD_UPWARD_DYLIB => {
+            let [name_offset, timestamp, current_version, compatibility_version] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4, 4, 4, 4],
+                "Mach-O dylib command",
+            )?;
+            LoadCommandData::Dymlib(DylibCommand {
+                load_cmd,
+                name_offset,
+                name: load_command_string(bytes, offset, command_size, name_offset, endianness)?,
+                timestamp,
+                current_version,
+                compatibility_version,
+            })
+        }
+        LC_ROUTINES | LC_ROUTINES_64 => {
+            let [init_address, init_module, reserved_bytes] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [word, word, 6 * word],
+                "Mach-O routines command",
+            )?;
+            LoadCommandData::Routine(RoutineCommand {
+                load_cmd,
+                init_address,
+                init_module,
+                reserved_bytes,
+            })
+        }
+        LC_MAIN => {
+            let [entryoff, stacksize] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [8, 8],
+                "Mach-O entry point command",
+            )?;
+            LoadCommandData::EntryPoint(EntryPointCommand {
+                load_cmd,
+                entryoff,
+                stacksize,
+            })
+        }
+        LC_PREBIND_CKSUM => {
+            let [cksum] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4],
+                "Mach-O prebind checksum command",
+            )?;
+            LoadCommandData::PrebindChecksum(PrebindChecksumCommand { load_cmd, cksum })
+        }
+        LC_UUID => {
+            let [uuid] = command_fields(bytes, offset, command_size, [16], "Mach-O uuid command")?;
+            LoadCommandData::UUID(UUIDCommand { load_cmd, uuid })
+        }
+        LC_ENCRYPTION_INFO | LC_ENCRYPTION_INFO_64 => {
+            let [cryptoff, cryptsize, cryptid] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4, 4, 4],
+                "Mach-O encryption info command",
+            )?;
+            LoadCommandData::EncryptionInfo(EncryptionInfoCommand {
+                load_cmd,
+                cryptoff,
+                cryptsize,
+                cryptid,
+            })
+        }
+        LC_VERSION_MIN_MACOSX
+        | LC_VERSION_MIN_IPHONEOS
+        | LC_VERSION_MIN_TVOS
+        | LC_VERSION_MIN_WATCHOS => {
+            let [version, sdk] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4, 4],
+                "Mach-O version min command",
+            )?;
+            LoadCommandData::VersionMin(VersionMinCommand {
+                load_cmd,
+                version,
+                sdk,
+            })
+        }
+        LC_RPATH => {
+            let [symbol_table_for_offset] =
+                command_fields(bytes, offset, command_size, [4], "Mach-O rpath command")?;
+            LoadCommandData::RunPath(RunPathCommand {
+                load_cmd,
+                symbol_table_for_offset,
+                path: load_command_string(
+                    bytes,
+                    offset,
+                    command_size,
+                    symbol_table_for_offset,
+                    endianness,
+                )?,
+            })
+        }
+        LC_DYLD_INFO | LC_DYLD_INFO_ONLY => {
+            let fields = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4; 10],
+                "Mach-O dyld info command",
+            )?;
+            LoadCommandData::DyldInfo(DyldInfoCommand {
+                load_cmd,
+                rebase_off: fields[0],
+                rebase_size: fields[1],
+                bind_off: fields[2],
+                bind_size: fields[3],
+                weak_bind_off: fields[4],
+                weak_bind_size: fields[5],
+                lazy_bind_off: fields[6],
+                lazy_bind_size: fields[7],
+                export_of: fields[8],
+                export_size: fields[9],
+            })
+        }
+        LC_CODE_SIGNATURE
+        | LC_SEGMENT_SPLIT_INFO
+        | LC_FUNCTION_STARTS
+        | LC_DATA_IN_CODE
+        | LC_DYLIB_CODE_SIGN_DRS
+        | LC_LINKER_OPTIMIZATION_HINT
+        | LC_DYLD_EXPORTS_TRIE
+        | LC_DYLD_CHAINED_FIXUPS => {
+            let [dataoff, datasize] = command_fields(
+                bytes,
+                offset,
+                command_size,
+                [4, 4],
+                "Mach-O linkedit data command",
+            )?;
+            LoadCommandData::LinkEditData(LinkEditDataCommand {
+                load_cmd,
+                dataoff,
+                datasize,
+            })
+        }
+        _ => LoadCommandData::Unknown(load_cmd),
+    })
+}
+
+/// Walks the `lcnum` load commands following the mach_header / mach_header_64
+pub fn extract_load_commands<'a>(
+    bytes: &'a [u8],
+    header: &MachOHeader,
+) -> Result<Vec<LoadCommandData<'a>>, ParseError> {
+    let endianness = macho_endianness(header.magic);
+    let is_64 = is_macho64(header.magic);
+    let command_count = read_u32(header.lcnum, endianness) as usize;
+    let mut offset = if is_64 {
+        MH_HEADER_64_SIZE
+    } else {
+        MH_HEADER_SIZE
+    };
+
+    let mut load_commands = Vec::new();
+    for _ in 0..command_count {
+        let command_size = read_u32(
+            get_header(bytes, offset + 4, 4, "Mach-O load command")?,
+            endianness,
+        ) as usize;
+        // load commands are at least `cmd` and `cmdsize` long, anything smaller would never end
+        if command_size < 8 {
+            return Err(ParseError::InvalidSize {
+                structure: "Mach-O load command",
+                offset,
+                size: command_size,
+            });
+        }
+        load_commands.push(extract_load_command(bytes, offset, is_64, endianness)?);
+        offset += command_size;
+    }
+    Ok(load_commands)
+}
+
+/// Initial program counter of a LC_UNIXTHREAD state (rip, eip, pc)
+fn thread_entry_point(cputype: u32, state: &[u8], endianness: Endianness) -> Option<u64> {
+    let (offset, size) = match cputype {
+        0x0100_0007 => (16 * 8, 8),
+        7 => (10 * 4, 4),
+        0x0100_000C => (32 * 8, 8),
+        12 => (15 * 4, 4),
+        _ => return None,
+    };
+    state
+        .get(offset..offset + size)
+        .map(|pc| read_uint(pc, endianness))
+}
+
+/// Names of the string table are indexed by byte offset, 0 is the empty name
+fn macho_string(strings: &[u8], index: usize) -> Result<String, ParseError> {
+    if index == 0 {
+        return Ok(String::new());
+    }
+    if index >= strings.len() {
+        return Err(ParseError::InvalidStringIndex {
+            table: "Mach-O string table",
+            index,
+        });
+    }
+    read_c_string(strings, index)
+}
+
+/// Reads the nlist / nlist_64 entries pointed by LC_SYMTAB
+pub fn extract_macho_symbol_table(
+    bytes: &[u8],
+    header: &MachOHeader,
+    load_commands: &[LoadCommandData],
+) -> Result<MachOSymbolTable, ParseError> {
+    let mut symbol_table = MachOSymbolTable {
+        symbols: Vec::new(),
+    };
+    let symtab = match load_commands
+        .iter()
+        .find_map(|load_command| match load_command {
+            LoadCommandData::Symtab(symtab) => Some(symtab),
+            _ => None,
+        }) {
+        Some(symtab) => symtab,
+        None => return Ok(symbol_table),
+    };
+    let endianness = macho_endianness(header.magic);
+    let is_64 = is_macho64(header.magic);
+    let symbol_size = if is_64 { NLIST_64_SIZE } else { NLIST_SIZE };
+
+    let strings = get_bytes(
+        bytes,
+        read_u32(symtab.stroff, endianness) as usize,
+        read_u32(symtab.strsize, endianness) as usize,
+    )?;
+    let symbol_offset = read_u32(symtab.symoff, endianness) as usize;
+    let symbol_count = read_u32(symtab.nsyms, endianness) as usize;
+    let symbols = get_bytes(
+        bytes,
+        symbol_offset,
+        symbol_count.saturating_mul(symbol_size),
+    )?;
+    for raw_symbol in symbols.chunks_exact(symbol_size) {
+        let name_offset = read_u32(&raw_symbol[0..4], endianness);
+        symbol_table.symbols.push(MachOSymbol {
+            name: macho_string(strings, name_offset as usize)?,
+            name_offset,
+            symbol_type: raw_symbol[4],
+            section: raw_symbol[5],
+            description: read_u16(&raw_symbol[6..8], endianness),
+            value: read_uint(&raw_symbol[8..symbol_size], endianness),
+        });
+    }
+    Ok(symbol_table)
+}
+
+/// Reads the indirect symbol table pointed by LC_DYSYMTAB
+pub fn extract_indirect_symbols(
+    bytes: &[u8],
+    header: &MachOHeader,
+    load_commands: &[LoadCommandData],
+) -> Result<Vec<u32>, ParseError> {
+    let dysymtab = match load_commands
+        .iter()
+        .find_map(|load_command| match load_command {
+            LoadCommandData::Dysymtab(dysymtab) => Some(dysymtab),
+            _ => None,
+        }) {
+        Some(dysymtab) => dysymtab,
+        None => return Ok(Vec::new()),
+    };
+    let endianness = macho_endianness(header.magic);
+    let indirect_symbols = get_bytes(
+        bytes,
+        read_u32(dysymtab.indirectsymoff, endianness) as usize,
+        (read_u32(dysymtab.nindirectsyms, endianness) as usize).saturating_mul(4),
+    )?;
+    Ok(indirect_symbols
+        .chunks_exact(4)
+        .map(|index| read_u32(index, endianness))
+        .collect())
+}
+
+/// Symbol stubs and lazy / non lazy pointer sections use their `reserved1` field as first
+/// index in the indirect symbol table, one index per stub or pointer
+pub fn extract_indirect_entries(
+    header: &MachOHeader,
+    load_commands: &[LoadCommandData],
+    symbol_table: &MachOSymbolTable,
+    indirect_symbols: &[u32],
+) -> Vec<MachOIndirectEntry> {
+    let endianness = macho_endianness(header.magic);
+    let pointer_size = if is_macho64(header.magic) { 8 } else { 4 };
+    let mut indirect_entries = Vec::new();
+
+    for section in load_commands
+        .iter()
+        .filter_map(|load_command| match load_command {
+            LoadCommandData::Segment(segment) => Some(segment.sections.iter()),
+            _ => None,
+        })
+        .flatten()
+    {
+        let section_type = read_u32(section.flags, endianness) & SECTION_TYPE;
+        let entry_size = match section_type {
+            S_SYMBOL_STUBS => read_u32(section.reserved2, endianness) as u64,
+            S_LAZY_SYMBOL_POINTERS
+            | S_NON_LAZY_SYMBOL_POINTERS
+            | S_LAZY_DYLIB_SYMBOL_POINTERS
+            | S_THREAD_LOCAL_VARIABLE_POINTERS => pointer_size,
+            _ => continue,
+        };
+        if entry_size == 0 {
+            continue;
+        }
+        let address = read_uint(section.addr, endianness);
+        let first_index = read_u32(section.reserved1, endianness) as usize;
+        let entry_count = read_uint(section.size, endianness) / entry_size;
+        for i in 0..entry_count {
+            let symbol_index = match indirect_symbols.get(first_index + i as usize) {
+                Some(&symbol_index) => symbol_index,
+                None => break,
+            };
+            // local and absolute slots are not bound to a symbol
+            if symbol_index & (INDIRECT_SYMBOL_LOCAL | INDIRECT_SYMBOL_ABS) != 0 {
+                continue;
+            }
+            if let Some(symbol) = symbol_table.symbols.get(symbol_index as usize) {
+                indirect_entries.push(MachOIndirectEntry {
+                    address: address.wrapping_add(i.wrapping_mul(entry_size)),
+                    section_name: format!("{},{}", section.segment_name, section.name),
+                    section_type,
+                    name: symbol.name.clone(),
+                });
+            }
+        }
+    }
+    indirect_entries
+}
+
+fn segment_commands<'a, 'b>(
+    load_commands: &'b [LoadCommandData<'a>],
+) -> Vec<&'b SegmentCommand<'a>> {
+    load_commands
+        .iter()
+        .filter_map(|load_command| match load_command {
+            LoadCommandData::Segment(segment) => Some(segment),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Address of the mach header once loaded, dyld info offsets are relative to it
+fn image_base(segments: &[&SegmentCommand], endianness: Endianness) -> u64 {
+    segments
+        .iter()
+        .find(|segment| {
+            read_uint(segment.fileoff, endianness) == 0
+                && read_uint(segment.filesize, endianness) != 0
+        })
+        .map_or(0, |segment| read_uint(segment.vmaddr, endianness))
+}
+
+/// Address of a pointer given as segment index and offset by the rebase and bind opcodes
+fn segment_address(
+    segments: &[&SegmentCommand],
+    segment_index: usize,
+    segment_offset: u64,
+    endianness: Endianness,
+) -> Result<u64, ParseError> {
+    let segment = segments
+        .get(segment_index)
+        .ok_or(ParseError::InvalidStringIndex {
+            table: "Mach-O segment list",
+            index: segment_index,
+        })?;
+    let segment_size = read_uint(segment.vmsize, endianness);
+    if segment_offset >= segment_size {
+        return Err(ParseError::OutOfRange {
+            offset: segment_offset as usize,
+            size: 1,
+            file_size: segment_size as usize,
+        });
+    }
+    Ok(read_uint(segment.vmaddr, endianness).wrapping_add(segment_offset))
+}
+
+/// Every fixed up pointer is stored in the file, more entries than pointers means the
+/// opcodes loop on garbage
+fn check_fixup_count(count: usize, bytes: &[u8], pointer_size: u64) -> Result<(), ParseError> {
+    if count as u64 > bytes.len() as u64 / pointer_size {
+        return Err(ParseError::InvalidSize {
+            structure: "Mach-O fixup list",
+            offset: 0,
+            size: count,
+        });
+    }
+    Ok(())
+}
+
+/// Runs the rebase opcode stream of LC_DYLD_INFO
+pub fn extract_rebases(
+    bytes: &[u8],
+    opcodes: &[u8],
+    segments: &[&SegmentCommand],
+    pointer_size: u64,
+    endianness: Endianness,
+) -> Result<Vec<MachORebase>, ParseError> {
+    let mut rebases = Vec::new();
+    let mut rebase_type = 0;
+    let mut segment_index = 0;
+    let mut segment_offset = 0u64;
+    let mut offset = 0;
+
+    while offset < opcodes.len() {
+        let byte = opcodes[offset];
+        let immediate = byte & REBASE_IMMEDIATE_MASK;
+        offset += 1;
+        let (count, skip) = match byte & REBASE_OPCODE_MASK {
+            REBASE_OPCODE_DONE => break,
+            REBASE_OPCODE_SET_TYPE_IMM => {
+                rebase_type = immediate;
+                continue;
+            }
+            REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                segment_index = immediate as usize;
+                segment_offset = read_uleb128(opcodes, &mut offset)?;
+                continue;
+            }
+            REBASE_OPCODE_ADD_ADDR_ULEB => {
+                segment_offset = segment_offset.wrapping_add(read_uleb128(opcodes, &mut offset)?);
+                continue;
+            }
+            REBASE_OPCODE_ADD_ADDR_IMM_SCALED => {
+                segment_offset = segment_offset.wrapping_add(immediate as u64 * pointer_size);
+                continue;
+            }
+            REBASE_OPCODE_DO_REBASE_IMM_TIMES => (immediate as u64, 0),
+            REBASE_OPCODE_DO_REBASE_ULEB_TIMES => (read_uleb128(opcodes, &mut offset)?, 0),
+            REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB => (1, read_uleb128(opcodes, &mut offset)?),
+            REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = read_uleb128(opcodes, &mut offset)?;
+                (count, read_uleb128(opcodes, &mut offset)?)
+            }
+            _ => {
+                return Err(ParseError::InvalidOpcode {
+                    table: "Mach-O rebase",
+                    offset: offset - 1,
+                    opcode: byte,
+                })
+            }
+        };
+        for _ in 0..count {
+            rebases.push(MachORebase {
+                address: segment_address(segments, segment_index, segment_offset, endianness)?,
+                rebase_type,
+                target: None,
+            });
+            check_fixup_count(rebases.len(), bytes, pointer_size)?;
+            segment_offset = segment_offset.wrapping_add(skip + pointer_size);
+        }
+    }
+    Ok(rebases)
+}
+
+/// Runs one of the bind, weak bind or lazy bind opcode streams of LC_DYLD_INFO
+pub fn extract_binds(
+    bytes: &[u8],
+    opcodes: &[u8],
+    kind: MachOBindKind,
+    segments: &[&SegmentCommand],
+    pointer_size: u64,
+    endianness: Endianness,
+) -> Result<Vec<MachOBind>, ParseError> {
+    let mut binds = Vec::new();
+    let mut library_ordinal = 0i64;
+    let mut symbol_name = String::new();
+    let mut symbol_flags = 0;
+    let mut bind_type = BIND_TYPE_POINTER;
+    let mut addend = 0i64;
+    let mut segment_index = 0;
+    let mut segment_offset = 0u64;
+    let mut offset = 0;
+
+    while offset < opcodes.len() {
+        let byte = opcodes[offset];
+        let immediate = byte & BIND_IMMEDIATE_MASK;
+        offset += 1;
+        let (count, skip) = match byte & BIND_OPCODE_MASK {
+            // lazy binds are separated by DONE so each stub can be bound on its own
+            BIND_OPCODE_DONE if kind == MachOBindKind::LazyBind => continue,
+            BIND_OPCODE_DONE => break,
+            BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => {
+                library_ordinal = immediate as i64;
+                continue;
+            }
+            BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => {
+                library_ordinal = read_uleb128(opcodes, &mut offset)? as i64;
+                continue;
+            }
+            // 0 self, negative values are special lookups (main executable, flat...)
+            BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => {
+                library_ordinal = if immediate == 0 {
+                    0
+                } else {
+                    (BIND_OPCODE_MASK | immediate) as i8 as i64
+                };
+                continue;
+            }
+            BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                symbol_flags = immediate;
+                symbol_name = read_c_string(opcodes, offset)?;
+                offset += symbol_name.len() + 1;
+                continue;
+            }
+            BIND_OPCODE_SET_TYPE_IMM => {
+                bind_type = immediate;
+                continue;
+            }
+            BIND_OPCODE_SET_ADDEND_SLEB => {
+                addend = read_sleb128(opcodes, &mut offset)?;
+                continue;
+            }
+            BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                segment_index = immediate as usize;
+                segment_offset = read_uleb128(opcodes, &mut offset)?;
+                continue;
+            }
+            BIND_OPCODE_ADD_ADDR_ULEB => {
+                segment_offset = segment_offset.wrapping_add(read_uleb128(opcodes, &mut offset)?);
+                continue;
+            }
+            BIND_OPCODE_DO_BIND => (1, 0),
+            BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB => (1, read_uleb128(opcodes, &mut offset)?),
+            BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED => (1, immediate as u64 * pointer_size),
+            BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = read_uleb128(opcodes, &mut offset)?;
+                (count, read_uleb128(opcodes, &mut offset)?)
+            }
+            // threaded binds are the arm64e ancestor of chained fixups, never emitted anymore
+            BIND_OPCODE_THREADED => break,
+            _ => {
+                return Err(ParseError::InvalidOpcode {
+                    table: "Mach-O bind",
+                    offset: offset - 1,
+                    opcode: byte,
+                })
+            }
+        };
+        for _ in 0..count {
+            binds.push(MachOBind {
+                kind,
+                address: segment_address(segments, segment_index, segment_offset, endianness)?,
+                symbol_name: symbol_name.clone(),
+                library_ordinal,
+                bind_type,
+                addend,
+                weak_import: symbol_flags & BIND_SYMBOL_FLAGS_WEAK_IMPORT != 0,
+            });
+            check_fixup_count(binds.len(), bytes, pointer_size)?;
+            segment_offset = segment_offset.wrapping_add(skip + pointer_size);
+        }
+    }
+    Ok(binds)
+}
+
+/// Walks the export trie, edges are name fragments and terminal nodes hold the symbol infos
+pub fn extract_export_trie(trie: &[u8], image_base: u64) -> Result<Vec<MachOExport>, ParseError> {
+    let mut exports = Vec::new();
+    let mut visited = vec![false; trie.len()];
+    let mut nodes = vec![(0usize, String::new())];
+
+    while let Some((node_offset, prefix)) = nodes.pop() {
+        if node_offset >= trie.len() {
+            return Err(ParseError::OutOfRange {
+                offset: node_offset,
+                size: 1,
+                file_size: trie.len(),
+            });
+        }
+        // a node reached twice means the trie has a cycle
+        if visited[node_offset] {
+            return Err(ParseError::InvalidStringIndex {
+                table: "Mach-O export trie",
+                index: node_offset,
+            });
+        }
+        visited[node_offset] = true;
+
+        let mut offset = node_offset;
+        let terminal_size = read_uleb128(trie, &mut offset)? as usize;
+        let children_offset = offset.saturating_add(terminal_size);
+        if terminal_size != 0 {
+            let flags = read_uleb128(trie, &mut offset)?;
+            let mut export = MachOExport {
+                name: prefix.clone(),
+                flags,
+                address: 0,
+                reexport: None,
+                resolver: None,
+            };
+            if flags & EXPORT_SYMBOL_FLAGS_REEXPORT != 0 {
+                let library_ordinal = read_uleb128(trie, &mut offset)?;
+                let imported_name = read_c_string(trie, offset)?;
+                export.reexport = Some((library_ordinal, imported_name));
+            } else {
+                let value = read_uleb128(trie, &mut offset)?;
+                export.address =
+                    if flags & EXPORT_SYMBOL_FLAGS_KIND_MASK == EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE {
+                        value
+                    } else {
+                        image_base.wrapping_add(value)
+                    };
+                if flags & EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER != 0 {
+                    export.resolver =
+                        Some(image_base.wrapping_add(read_uleb128(trie, &mut offset)?));
+                }
+            }
+            exports.push(export);
+        }
+
+        let mut offset = children_offset;
+        let child_count = *get_bytes(trie, offset, 1)?.first().unwrap_or(&0);
+        offset += 1;
+        for _ in 0..child_count {
+            let edge = read_c_string(trie, offset)?;
+            offset += edge.len() + 1;
+            let child_offset = read_uleb128(trie, &mut offset)? as usize;
+            nodes.push((child_offset, format!("{}{}", prefix, edge)));
+        }
+    }
+    Ok(exports)
+}
+
+/// Splits a chained pointer into (bind, ordinal or target, addend, next), rebase targets come
+/// back as unslid addresses, `image_base` is added to the formats that store offsets
+fn decode_chained_pointer(
+    pointer_format: u16,
+    raw: u64,
+    image_base: u64,
+) -> Option<(bool, u64, i64, u64)> {
+    Some(match pointer_format {
+        DYLD_CHAINED_PTR_64 | DYLD_CHAINED_PTR_64_OFFSET => {
+            let next = (raw >> 51) & 0xFFF;
+            if raw >> 63 != 0 {
+                (true, raw & 0xFF_FFFF, ((raw >> 24) & 0xFF) as i64, next)
+            } else {
+                let target = raw & 0xF_FFFF_FFFF;
+                let target = if pointer_format == DYLD_CHAINED_PTR_64_OFFSET {
+                    image_base.wrapping_add(target)
+                } else {
+                    target
+                };
+                // high8 holds the top byte of tagged pointers
+                (false, target | (((raw >> 36) & 0xFF) << 56), 0, next)
+            }
+        }
+        DYLD_CHAINED_PTR_ARM64E
+        | DYLD_CHAINED_PTR_ARM64E_USERLAND
+        | DYLD_CHAINED_PTR_ARM64E_USERLAND24 => {
+            let next = (raw >> 51) & 0x7FF;
+            let is_auth = raw >> 63 != 0;
+            let is_bind = (raw >> 62) & 1 != 0;
+            let ordinal_mask = if pointer_format == DYLD_CHAINED_PTR_ARM64E_USERLAND24 {
+                0xFF_FFFF
+            } else {
+                0xFFFF
+            };
+            match (is_bind, is_auth) {
+                (true, true) => (true, raw & ordinal_mask, 0, next),
+                // 19-bit signed addend
+                (true, false) => (
+                    true,
+                    raw & ordinal_mask,
+                    (((raw >> 32) & 0x7_FFFF) as i64) << 45 >> 45,
+                    next,
+                ),
+                // authenticated rebases hold an offset from the image base in every format
+                (false, true) => (false, image_base.wrapping_add(raw & 0xFFFF_FFFF), 0, next),
+                (false, false) => {
+                    let target = raw & 0x7FF_FFFF_FFFF;
+                    // only DYLD_CHAINED_PTR_ARM64E stores vmaddrs, the userland formats offsets
+                    let target = if pointer_format == DYLD_CHAINED_PTR_ARM64E {
+                        target
+                    } else {
+                        image_base.wrapping_add(target)
+                    };
+                    (false, target | (((raw >> 43) & 0xFF) << 56), 0, next)
+                }
+            }
+        }
+        DYLD_CHAINED_PTR_32 => {
+            let next = (raw >> 26) & 0x1F;
+            if raw >> 31 & 1 != 0 {
+                (true, raw & 0xF_FFFF, ((raw >> 20) & 0x3F) as i64, next)
+            } else {
+                (false, raw & 0x3FF_FFFF, 0, next)
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Decodes the imports table of LC_DYLD_CHAINED_FIXUPS then walks the pointer chains of every
+/// page, binds point in the imports table and rebases hold their target
+pub fn extract_chained_fixups(
+    bytes: &[u8],
+    fixups: &[u8],
+    segments: &[&SegmentCommand],
+    image_base: u64,
+    endianness: Endianness,
+) -> Result<(Vec<MachOBind>, Vec<MachORebase>), ParseError> {
+    let header = get_header(fixups, 0, 28, "Mach-O chained fixups header")?;
+    let starts_offset = read_u32(&header[4..8], endianness) as usize;
+    let imports_offset = read_u32(&header[8..12], endianness) as usize;
+    let symbols_offset = read_u32(&header[12..16], endianness) as usize;
+    let imports_count = read_u32(&header[16..20], endianness) as usize;
+    let imports_format = read_u32(&header[20..24], endianness);
+
+    // (name, library ordinal, weak import, addend)
+    let import_size = match imports_format {
+        DYLD_CHAINED_IMPORT => 4,
+        DYLD_CHAINED_IMPORT_ADDEND => 8,
+        DYLD_CHAINED_IMPORT_ADDEND64 => 16,
+        _ => {
+            return Err(ParseError::UnsupportedFormat(format!(
+                "chained fixups imports format {}",
+                imports_format
+            )))
+        }
+    };
+    let raw_imports = get_bytes(
+        fixups,
+        imports_offset,
+        imports_count.saturating_mul(import_size),
+    )?;
+    let symbols = fixups.get(symbols_offset..).unwrap_or(&[]);
+    let mut imports = Vec::new();
+    for raw_import in raw_imports.chunks_exact(import_size) {
+        let (library_ordinal, weak_import, name_offset, addend) = match imports_format {
+            DYLD_CHAINED_IMPORT_ADDEND64 => {
+                let import = read_u64(&raw_import[0..8], endianness);
+                (
+                    (import & 0xFFFF) as u16 as i16 as i64,
+                    (import >> 16) & 1 != 0,
+                    (import >> 32) as usize,
+                    read_u64(&raw_import[8..16], endianness) as i64,
+                )
+            }
+            _ => {
+                let import = read_u32(&raw_import[0..4], endianness);
+                let addend = if imports_format == DYLD_CHAINED_IMPORT_ADDEND {
+                    read_u32(&raw_import[4..8], endianness) as i32 as i64
+                } else {
+                    0
+                };
+                // 0xFF, 0xFE and 0xFD are the special negative ordinals
+                let ordinal = (import & 0xFF) as u8;
+                let library_ordinal = if ordinal >= 0xF0 {
+                    ordinal as i8 as i64
+                } else {
+                    ordinal as i64
+                };
+                (
+                    library_ordinal,
+                    (import >> 8) & 1 != 0,
+                    (import >> 9) as usize,
+                    addend,
+                )
+            }
+        };
+        imports.push((
+            read_c_string(symbols, name_offset)?,
+            library_ordinal,
+            weak_import,
+            addend,
+        ));
+    }
+
+    let mut binds = Vec::new();
+    let mut rebases = Vec::new();
+    if starts_offset == 0 {
+        return Ok((binds, rebases));
+    }
+    let segment_count = read_u32(
+        get_header(fixups, starts_offset, 4, "Mach-O chained starts")?,
+        endianness,
+    ) as usize;
+    let segment_info_offsets =
+        get_bytes(fixups, starts_offset + 4, segment_count.saturating_mul(4))?;
+    for (segment_index, info_offset) in segment_info_offsets.chunks_exact(4).enumerate() {
+        let info_offset = read_u32(info_offset, endianness) as usize;
+        if info_offset == 0 {
+            continue;
+        }
+        let segment = match segments.get(segment_index) {
+            Some(segment) => segment,
+            None => break,
+        };
+        let info_offset = starts_offset + info_offset;
+        let info = get_header(fixups, info_offset, 22, "Mach-O chained starts in segment")?;
+        let page_size = read_u16(&info[4..6], endianness) as u64;
+        // every page would start the same chain
+        if page_size == 0 {
+            return Err(ParseError::InvalidSize {
+                structure: "Mach-O chained fixups page",
+                offset: info_offset + 4,
+                size: 0,
+            });
+        }
+        let pointer_format = read_u16(&info[6..8], endianness);
+        let page_count = read_u16(&info[20..22], endianness) as usize;
+        let page_starts = get_bytes(fixups, info_offset + 22, page_count * 2)?;
+        let (stride, pointer_size) = match pointer_format {
+            DYLD_CHAINED_PTR_ARM64E
+            | DYLD_CHAINED_PTR_ARM64E_USERLAND
+            | DYLD_CHAINED_PTR_ARM64E_USERLAND24 => (8, 8),
+            DYLD_CHAINED_PTR_32 => (4, 4),
+            _ => (4, 8),
+        };
+        for (page_index, page_start) in page_starts.chunks_exact(2).enumerate() {
+            let page_start = read_u16(page_start, endianness);
+            // multiple starts are only used by 32-bit firmware formats
+            if page_start == DYLD_CHAINED_PTR_START_NONE
+                || page_start & DYLD_CHAINED_PTR_START_MULTI != 0
+            {
+                continue;
+            }
+            let page_offset = page_index as u64 * page_size;
+            let mut offset_in_page = page_start as u64;
+            // a chain never leaves its page nor the segment data
+            while offset_in_page < page_size
+                && page_offset + offset_in_page < read_uint(segment.filesize, endianness)
+            {
+                let file_offset = read_uint(segment.fileoff, endianness)
+                    .wrapping_add(page_offset + offset_in_page);
+                let raw = read_uint(
+                    get_bytes(bytes, file_offset as usize, pointer_size)?,
+                    endianness,
+                );
+                let (is_bind, value, addend, next) =
+                    match decode_chained_pointer(pointer_format, raw, image_base) {
+                        Some(pointer) => pointer,
+                        None => {
+                            return Err(ParseError::UnsupportedFormat(format!(
+                                "chained pointer format {}",
+                                pointer_format
+                            )))
+                        }
+                    };
+                let address = read_uint(segment.vmaddr, endianness)
+                    .wrapping_add(page_offset + offset_in_page);
+                if is_bind {
+                    let (name, library_ordinal, weak_import, import_addend) = imports
+                        .get(value as usize)
+                        .ok_or(ParseError::InvalidStringIndex {
+                            table: "Mach-O chained imports",
+                            index: value as usize,
+                        })?;
+                    binds.push(MachOBind {
+                        kind: MachOBindKind::ChainedFixup,
+                        address,
+                        symbol_name: name.clone(),
+                        library_ordinal: *library_ordinal,
+                        bind_type: BIND_TYPE_POINTER,
+                        addend: import_addend.wrapping_add(addend),
+                        weak_import: *weak_import,
+                    });
+                } else {
+                    rebases.push(MachORebase {
+                        address,
+                        rebase_type: REBASE_TYPE_POINTER,
+                        target: Some(value),
+                    });
+                }
+                check_fixup_count(binds.len() + rebases.len(), bytes, pointer_size as u64)?;
+                if next == 0 {
+                    break;
+                }
+                offset_in_page += next * stride;
+            }
+        }
+    }
+    Ok((binds, rebases))
+}
+
+/// Mach-O symbols have no type, code is told apart by the attributes of its section
+fn macho_symbol_kind(
+    symbol: &MachOSymbol,
+    sections: &[&SectionCommand],
+    endianness: Endianness,
+) -> SymbolKind {
+    let section = match (symbol.section as usize)
+        .checked_sub(1)
+        .and_then(|index| sections.get(index))
+    {
+        Some(section) => section,
+        None => return SymbolKind::Other,
+    };
+    if read_u32(section.flags, endianness) & (S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS)
+        != 0
+    {
+        SymbolKind::Function
+    } else {
+        SymbolKind::Object
+    }
+}
+
+/// Linking information of modern binaries: LC_DYLD_INFO opcode streams and export trie, or
+/// LC_DYLD_CHAINED_FIXUPS with LC_DYLD_EXPORTS_TRIE
+pub fn extract_dyld_info(
+    bytes: &[u8],
+    header: &MachOHeader,
+    load_commands: &[LoadCommandData],
+) -> Result<MachODyldInfo, ParseError> {
+    let endianness = macho_endianness(header.magic);
+    let pointer_size = if is_macho64(header.magic) { 8 } else { 4 };
+    let segments = segment_commands(load_commands);
+    let image_base = image_base(&segments, endianness);
+    let linkedit_data = |offset: &[u8], size: &[u8]| {
+        get_bytes(
+            bytes,
+            read_u32(offset, endianness) as usize,
+            read_u32(size, endianness) as usize,
+        )
+    };
+
+    let mut dyld_info = MachODyldInfo::default();
+    for load_command in load_commands.iter() {
+        match load_command {
+            LoadCommandData::DyldInfo(dyld_info_command) => {
+                dyld_info.rebases.extend(extract_rebases(
+                    bytes,
+                    linkedit_data(dyld_info_command.rebase_off, dyld_info_command.rebase_size)?,
+                    &segments,
+                    pointer_size,
+                    endianness,
+                )?);
+                for (kind, offset, size) in [
+                    (
+                        MachOBindKind::Bind,
+                        dyld_info_command.bind_off,
+                        dyld_info_command.bind_size,
+                    ),
+                    (
+                        MachOBindKind::WeakBind,
+                        dyld_info_command.weak_bind_off,
+                        dyld_info_command.weak_bind_size,
+                    ),
+                    (
+                        MachOBindKind::LazyBind,
+                        dyld_info_command.lazy_bind_off,
+                        dyld_info_command.lazy_bind_size,
+                    ),
+                ] {
+                    dyld_info.binds.extend(extract_binds(
+                        bytes,
+                        linkedit_data(offset, size)?,
+                        kind,
+                        &segments,
+                        pointer_size,
+                        endianness,
+                    )?);
+                }
+                dyld_info.exports.extend(extract_export_trie(
+                    linkedit_data(dyld_info_command.export_of, dyld_info_command.export_size)?,
+                    image_base,
+                )?);
+            }
+            LoadCommandData::LinkEditData(linkedit) => {
+                let data = linkedit_data(linkedit.dataoff, linkedit.datasize)?;
+                match read_u32(linkedit.load_cmd.cmd, endianness) {
+                    LC_DYLD_CHAINED_FIXUPS if !data.is_empty() => {
+                        let (chained_binds, chained_rebases) =
+                            extract_chained_fixups(bytes, data, &segments, image_base, endianness)?;
+                        dyld_info.binds.extend(chained_binds);
+                        dyld_info.rebases.extend(chained_rebases);
+                    }
+                    LC_DYLD_EXPORTS_TRIE if !data.is_empty() => {
+                        dyld_info
+                            .exports
+                            .extend(extract_export_trie(data, image_base)?);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(dyld_info)
+}
+
+pub fn extract_fat_header(bytes: &[u8]) -> Result<FatHeader<'_>, ParseError> {
+    let header = get_header(bytes, 0, FAT_HEADER_SIZE, "fat header")?;
+    if &header[0..4] != FAT_MAGIC && &header[0..4] != FAT_MAGIC_64 {
+        return Err(ParseError::BadMagic {
+            format: "Mach-O universal binary",
+            found: header[0..4].to_vec(),
+        });
+    }
+    Ok(FatHeader {
+        magic: &header[0..4],
+        nfat_arch: &header[4..8],
+    })
+}
+
+/// Reads the fat_arch (or fat_arch_64 with FAT_MAGIC_64) entries following the fat header
+pub fn extract_fat_archs<'a>(
+    bytes: &'a [u8],
+    header: &FatHeader,
+) -> Result<Vec<FatArch<'a>>, ParseError> {
+    let is_64 = header.magic == FAT_MAGIC_64;
+    let (arch_size, word) = if is_64 {
+        (FAT_ARCH_64_SIZE, 8)
+    } else {
+        (FAT_ARCH_SIZE, 4)
+    };
+    let arch_count = read_u32(header.nfat_arch, Endianness::Big) as usize;
+    let archs = get_header(
+        bytes,
+        FAT_HEADER_SIZE,
+        arch_count.saturating_mul(arch_size),
+        "fat arch table",
+    )?;
+    Ok(archs
+        .chunks_exact(arch_size)
+        .map(|arch| FatArch {
+            cputype: &arch[0..4],
+            cpusubtype: &arch[4..8],
+            offset: &arch[8..8 + word],
+            size: &arch[8 + word..8 + 2 * word],
+            align: &arch[8 + 2 * word..12 + 2 * word],
+            reserved: &arch[12 + 2 * word..],
+        })
+        .collect())
+}
+
+pub fn parse_fat(bytes: &[u8]) -> Result<FileInfoFat<'_>, ParseError> {
+    let header = extract_fat_header(bytes)?;
+    let architectures = extract_fat_archs(bytes, &header)?;
+    Ok(FileInfoFat {
+        header,
+        architectures,
+    })
+}
+
+pub fn fat_arch_architecture(arch: &FatArch) -> Architecture {
+    cpu_architecture(read_u32(arch.cputype, Endianness::Big))
+}
+
+/// Mach-O file embedded in a universal binary
+pub fn fat_slice<'a>(bytes: &'a [u8], arch: &FatArch) -> Result<&'a [u8], ParseError> {
+    get_bytes(
+        bytes,
+        read_uint(arch.offset, Endianness::Big) as usize,
+        read_uint(arch.size, Endianness::Big) as usize,
+    )
+}
+
+/// Parses the slice at `index` in the fat_arch table as a normal Mach-O
+pub fn parse_fat_slice(bytes: &[u8], index: usize) -> Result<BinaryFile<'_>, ParseError> {
+    let fat = parse_fat(bytes)?;
+    let arch = fat
+        .architectures
+        .get(index)
+        .ok_or(ParseError::InvalidStringIndex {
+            table: "fat arch table",
+            index,
+        })?;
+    parse_macho(fat_slice(bytes, arch)?)
+}
+
+pub fn parse_macho(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let header = extract_macho_header(bytes)?;
+    let endianness = macho_endianness(header.magic);
+    let cputype = read_u32(header.cputype, endianness);
+    let bitness = if is_macho64(header.magic) {
+        Bitness::Bits64
+    } else {
+        Bitness::Bits32
+    };
+    let load_commands = extract_load_commands(bytes, &header)?;
+
+    let libraries: Vec<String> = load_commands
+        .iter()
+        .filter_map(|load_command| match load_command {
+            LoadCommandData::Dymlib(dylib)
+                if read_u32(dylib.load_cmd.cmd, endianness) != LC_ID_DYLIB =>
+            {
+                Some(dylib.name.clone())
+            }
+            _ => None,
+        })
+        .collect();
+
+    // LC_MAIN gives a file offset, the address is found through the segment mapping it
+    let mut entry_point = 0;
+    for load_command in load_commands.iter() {
+        match load_command {
+            LoadCommandData::EntryPoint(main) => {
+                let entry_offset = read_u64(main.entryoff, endianness);
+                entry_point = load_commands
+                    .iter()
+                    .find_map(|load_command| match load_command {
+                        LoadCommandData::Segment(segment) => {
+                            let file_offset = read_uint(segment.fileoff, endianness);
+                            let file_size = read_uint(segment.filesize, endianness);
+                            let segment_offset = entry_offset.checked_sub(file_offset)?;
+                            (segment_offset < file_size).then(|| {
+                                read_uint(segment.vmaddr, endianness).wrapping_add(segment_offset)
+                            })
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or(entry_offset);
+            }
+            LoadCommandData::Thread(thread)
+                if read_u32(thread.load_cmd.cmd, endianness) == LC_UNIXTHREAD =>
+            {
+                entry_point = thread_entry_point(cputype, thread.state, endianness).unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+
+    let symbol_table = extract_macho_symbol_table(bytes, &header, &load_commands)?;
+    let indirect_symbols = extract_indirect_symbols(bytes, &header, &load_commands)?;
+    let indirect_entries =
+        extract_indirect_entries(&header, &load_commands, &symbol_table, &indirect_symbols);
+    let dyld_info = extract_dyld_info(bytes, &header, &load_commands)?;
+
+    let mut sections = Vec::new();
+    let mut segments = Vec::new();
+    for load_command in load_commands.iter() {
+        if let LoadCommandData::Segment(segment) = load_command {
+            segments.push(BinarySegment {
+                name: segment.name.clone(),
+                virtual_address: read_uint(segment.vmaddr, endianness),
+                virtual_size: read_uint(segment.vmsize, endianness),
+                file_offset: read_uint(segment.fileoff, endianness),
+                file_size: read_uint(segment.filesize, endianness),
+                raw_data: segment.raw_data,
+            });
+            // named like otool and objdump do, "__TEXT,__text"
+            sections.extend(segment.sections.iter().map(|section| BinarySection {
+                name: format!("{},{}", section.segment_name, section.name),
+                virtual_address: read_uint(section.addr, endianness),
+                virtual_size: read_uint(section.size, endianness),
+                file_offset: read_u32(section.symbol_table_for_offset, endianness) as u64,
+                file_size: section.raw_data.len() as u64,
+                raw_data: section.raw_data,
+            }));
+        }
+    }
+
+    let section_commands: Vec<&SectionCommand> = load_commands
+        .iter()
+        .filter_map(|load_command| match load_command {
+            LoadCommandData::Segment(segment) => Some(segment.sections.iter()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let mut symbols: Vec<BinarySymbol> = symbol_table
+        .symbols
+        .iter()
+        .filter(|symbol| {
+            symbol.symbol_type & N_STAB == 0
+                && symbol.symbol_type & N_TYPE == N_SECT
+                && !symbol.name.is_empty()
+        })
+        .map(|symbol| BinarySymbol {
+            name: symbol.name.clone(),
+            demangled_name: None,
+            kind: macho_symbol_kind(symbol, &section_commands, endianness),
+            address: symbol.value,
+            size: 0,
+        })
+        .collect();
+    // labels the stubs so calls show up as `call _printf@stub`
+    symbols.extend(
+        indirect_entries
+            .iter()
+            .filter(|indirect_entry| indirect_entry.section_type == S_SYMBOL_STUBS)
+            .map(|indirect_entry| BinarySymbol {
+                name: format!("{}@stub", indirect_entry.name),
+                demangled_name: None,
+                kind: SymbolKind::Function,
+                address: indirect_entry.address,
+                size: 0,
+            }),
+    );
+
+    // calls go through the stub, data through its pointer slot
+    let import_address = |name: &str, bind_address: u64| {
+        indirect_entries
+            .iter()
+            .filter(|indirect_entry| indirect_entry.name == name)
+            .min_by_key(|indirect_entry| indirect_entry.section_type != S_SYMBOL_STUBS)
+            .map_or(bind_address, |indirect_entry| indirect_entry.address)
+    };
+    let library_name = |library_ordinal: i64| {
+        usize::try_from(library_ordinal - 1)
+            .ok()
+            .and_then(|index| libraries.get(index))
+            .cloned()
+            .unwrap_or_default()
+    };
+    let mut imports: Vec<BinaryImport> = Vec::new();
+    if dyld_info.binds.is_empty() {
+        // two-level namespace binaries store the 1-based index of the dylib in n_desc
+        for symbol in symbol_table.symbols.iter().filter(|symbol| {
+            symbol.symbol_type & N_STAB == 0
+                && symbol.symbol_type & N_TYPE == N_UNDF
+                && symbol.symbol_type & N_EXT != 0
+                && !symbol.name.is_empty()
+        }) {
+            let library_ordinal = match (symbol.description >> 8) as u8 {
+                SELF_LIBRARY_ORDINAL | DYNAMIC_LOOKUP_ORDINAL | EXECUTABLE_ORDINAL => 0,
+                ordinal => ordinal as i64,
+            };
+            imports.push(BinaryImport {
+                library: library_name(library_ordinal),
+                name: symbol.name.clone(),
+                address: import_address(&symbol.name, 0),
+            });
+        }
+    } else {
+        // weak binds only coalesce weak definitions, they do not import anything
+        for bind in dyld_info
+            .binds
+            .iter()
+            .filter(|bind| bind.kind != MachOBindKind::WeakBind)
+        {
+            if !imports.iter().any(|import| import.name == bind.symbol_name) {
+                imports.push(BinaryImport {
+                    library: library_name(bind.library_ordinal),
+                    name: bind.symbol_name.clone(),
+                    address: import_address(&bind.symbol_name, bind.address),
+                });
+            }
+        }
+    }
+    let exports = if dyld_info.exports.is_empty() {
+        symbol_table
+            .symbols
+            .iter()
+            .filter(|symbol| {
+                symbol.symbol_type & N_STAB == 0
+                    && matches!(symbol.symbol_type & N_TYPE, N_SECT | N_ABS)
+                    && symbol.symbol_type & N_EXT != 0
+                    && symbol.symbol_type & N_PEXT == 0
+            })
+            .map(|symbol| BinaryExport {
+                name: symbol.name.clone(),
+                address: symbol.value,
+            })
+            .collect()
+    } else {
+        dyld_info
+            .exports
+            .iter()
+            .map(|export| BinaryExport {
+                name: export.name.clone(),
+                address: export.address,
+            })
+            .collect()
+    };
+
+    Ok(BinaryFile {
+        format: Format::MachO,
+        architecture: cpu_architecture(cputype),
+        endianness,
+        bitness,
+        entry_point,
+        sections,
+        segments,
+        symbols,
+        libraries,
+        imports,
+        exports,
+        details: FileDetails::MachO(FileInfoMachO {
+            header,
+            load_commands,
+            symbol_table,
+            indirect_symbols,
+            indirect_entries,
+            dyld_info,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::macho_structure::LC_LOAD_DYLINKER;
+
+    fn push_u32s(bytes: &mut Vec<u8>, values: &[u32]) {
+        for value in values {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn push_u64s(bytes: &mut Vec<u8>, values: &[u64]) {
+        for value in values {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    /// 64-bit x86-64 executable made of the given load commands, padded to 0x200 bytes
+    fn executable(commands: &[Vec<u8>]) -> Vec<u8> {
+        let commands_size = commands.iter().map(Vec::len).sum::<usize>() as u32;
+        let mut bytes = Vec::new();
+        push_u32s(
+            &mut bytes,
+            &[
+                0xFEED_FACF,
+                0x0100_0007,
+                3,
+                2,
+                commands.len() as u32,
+                commands_size,
+                0,
+                0,
+            ],
+        );
+        for command in commands {
+            bytes.extend_from_slice(command);
+        }
+        bytes.resize(0x200, 0);
+        bytes
+    }
+
+    /// LC_SEGMENT_64 named __TEXT, `sections` are section_64 records
+    fn segment(vmaddr: u64, fileoff: u64, filesize: u64, sections: &[Vec<u8>]) -> Vec<u8> {
+        let mut command = Vec::new();
+        push_u32s(
+            &mut command,
+            &[LC_SEGMENT_64, 72 + 80 * sections.len() as u32],
+        );
+        command.extend_from_slice(b"__TEXT\0\0\0\0\0\0\0\0\0\0");
+        push_u64s(&mut command, &[vmaddr, filesize, fileoff, filesize]);
+        push_u32s(&mut command, &[5, 5, sections.len() as u32, 0]);
+        for section in sections {
+            command.extend_from_slice(section);
+        }
+        command
+    }
+
+    fn entry_point_command(entryoff: u64) -> Vec<u8> {
+        let mut command = Vec::new();
+        push_u32s(&mut command, &[LC_MAIN, 24]);
+        push_u64s(&mut command, &[entryoff, 0]);
+        command
+    }
+
+    #[test]
+    fn entry_point_is_mapped_through_its_segment() {
+        let bytes = executable(&[
+            segment(0x1_0000_0000, 0, 0x200, &[]),
+            entry_point_command(0x80),
+        ]);
+        assert_eq!(parse_macho(&bytes).unwrap().entry_point, 0x1_0000_0080);
+    }
+
+    /// Segment at `vmaddr` covering the file, its single page of `page_size` bytes chains the
+    /// `pointers` stored 8 bytes apart from 0x1E0, import 0 is _g of library 1 with an addend of 5
+    fn chained_fixups_executable(
+        vmaddr: u64,
+        page_size: u16,
+        pointer_format: u16,
+        pointers: &[u64],
+    ) -> Vec<u8> {
+        let mut command = Vec::new();
+        push_u32s(&mut command, &[LC_DYLD_CHAINED_FIXUPS, 16, 0x100, 0x80]);
+        let mut bytes = executable(&[segment(vmaddr, 0, 0x200, &[]), command]);
+
+        let mut fixups = Vec::new();
+        push_u32s(
+            &mut fixups,
+            &[0, 0x20, 0x60, 0x70, 1, DYLD_CHAINED_IMPORT_ADDEND64, 0],
+        );
+        fixups.resize(0x20, 0);
+        // starts in image, then starts in segment with a single page
+        push_u32s(&mut fixups, &[1, 8, 24]);
+        fixups.extend_from_slice(&page_size.to_le_bytes());
+        fixups.extend_from_slice(&pointer_format.to_le_bytes());
+        push_u64s(&mut fixups, &[0]);
+        push_u32s(&mut fixups, &[0]);
+        fixups.extend_from_slice(&[1, 0, 0xE0, 0x01]);
+        fixups.resize(0x60, 0);
+        push_u64s(&mut fixups, &[1 | 1 << 32, 5]);
+        fixups.extend_from_slice(b"\0_g\0");
+        bytes[0x100..0x100 + fixups.len()].copy_from_slice(&fixups);
+        for (i, pointer) in pointers.iter().enumerate() {
+            bytes[0x1E0 + i * 8..0x1E8 + i * 8].copy_from_slice(&pointer.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn chained_fixups_bind_imports_and_rebase_pointers() {
+        // DYLD_CHAINED_PTR_64 counts `next` in 4 bytes strides
+        let rebase: u64 = 0x1_0000_3F60 | 0x80 << 36 | 2 << 51;
+        let bind: u64 = 1 << 63 | 1 << 24;
+        let bytes =
+            chained_fixups_executable(0x1_0000_0000, 0x1000, DYLD_CHAINED_PTR_64, &[rebase, bind]);
+        let file = parse_macho(&bytes).unwrap();
+        let dyld_info = &macho_details(&file).dyld_info;
+        assert_eq!(dyld_info.rebases.len(), 1);
+        assert_eq!(dyld_info.rebases[0].address, 0x1_0000_01E0);
+        // high8 is the top byte of the target
+        assert_eq!(dyld_info.rebases[0].target, Some(0x8000_0001_0000_3F60));
+        assert_eq!(dyld_info.binds.len(), 1);
+        let bind = &dyld_info.binds[0];
+        assert_eq!(bind.kind, MachOBindKind::ChainedFixup);
+        assert_eq!(bind.address, 0x1_0000_01E8);
+        assert_eq!(bind.symbol_name, "_g");
+        assert_eq!(bind.library_ordinal, 1);
+        assert_eq!(bind.addend, 6);
+    }
+
+    #[test]
+    fn arm64e_rebases_pick_their_base_per_fixup() {
+        // arm64e counts `next` in 8 bytes strides
+        let next: u64 = 1 << 51;
+        let auth_rebase = 1 << 63 | next | 0x3F60;
+        let auth_bind = 1 << 63 | 1 << 62 | next;
+        let bind = 1 << 62 | 3 << 32;
+        let rebases = |pointer_format: u16, rebase: u64| {
+            let pointers = [auth_rebase, rebase, auth_bind, bind];
+            let bytes = chained_fixups_executable(0x1_0000_0000, 0x1000, pointer_format, &pointers);
+            let file = parse_macho(&bytes).unwrap();
+            let dyld_info = &macho_details(&file).dyld_info;
+            let addends: Vec<(u64, i64)> = dyld_info
+                .binds
+                .iter()
+                .map(|bind| (bind.address, bind.addend))
+                .collect();
+            // authenticated binds have no addend of their own
+            assert_eq!(addends, [(0x1_0000_01F0, 5), (0x1_0000_01F8, 8)]);
+            dyld_info
+                .rebases
+                .iter()
+                .map(|rebase| rebase.target.unwrap())
+                .collect::<Vec<u64>>()
+        };
+        // authenticated rebases are offsets from the image base, plain ones are vmaddrs with
+        // high8 in bits 43..50 for DYLD_CHAINED_PTR_ARM64E and offsets for the userland formats
+        assert_eq!(
+            rebases(DYLD_CHAINED_PTR_ARM64E, 0x80 << 43 | next | 0x1_0000_4000),
+            [0x1_0000_3F60, 0x8000_0001_0000_4000]
+        );
+        for pointer_format in [
+            DYLD_CHAINED_PTR_ARM64E_USERLAND,
+            DYLD_CHAINED_PTR_ARM64E_USERLAND24,
+        ] {
+            assert_eq!(
+                rebases(pointer_format, 0x80 << 43 | next | 0x4000),
+                [0x1_0000_3F60, 0x8000_0001_0000_4000]
+            );
+        }
+    }
+
+    #[test]
+    fn chained_fixups_with_empty_pages_are_rejected() {
+        let bytes = chained_fixups_executable(0x1000, 0, DYLD_CHAINED_PTR_64, &[1 << 63]);
+        assert!(parse_macho(&bytes).is_err());
+    }
+
+    #[test]
+    fn chained_fixups_chain_stays_in_its_page() {
+        let bytes = chained_fixups_executable(0x1000, 0x100, DYLD_CHAINED_PTR_64, &[1 << 63]);
+        let file = parse_macho(&bytes).unwrap();
+        assert!(macho_details(&file).dyld_info.binds.is_empty());
+    }
+
+    #[test]
+    fn dyld_info_opcodes_and_export_trie_are_decoded() {
+        let bytes = test_file("MacOS/MachO-OSX-x64-ls");
+        let file = parse_macho(&bytes).unwrap();
+        let dyld_info = &macho_details(&file).dyld_info;
+        assert_eq!(dyld_info.rebases.len(), 101);
+        assert_eq!(dyld_info.rebases[0].address, 0x1_0000_6000);
+        assert_eq!(dyld_info.rebases[100].address, 0x1_0000_64E0);
+        assert_eq!(dyld_info.rebases[0].rebase_type, REBASE_TYPE_POINTER);
+        assert_eq!(dyld_info.rebases[0].target, None);
+
+        let binds = |kind: MachOBindKind| {
+            dyld_info
+                .binds
+                .iter()
+                .filter(|bind| bind.kind == kind)
+                .collect::<Vec<&MachOBind>>()
+        };
+        let non_lazy = binds(MachOBindKind::Bind);
+        assert_eq!(non_lazy.len(), 6);
+        assert_eq!(non_lazy[0].address, 0x1_0000_6028);
+        assert_eq!(non_lazy[0].symbol_name, "__DefaultRuneLocale");
+        assert_eq!(non_lazy[0].library_ordinal, 3);
+        assert_eq!(non_lazy[5].address, 0x1_0000_6050);
+        assert_eq!(non_lazy[5].symbol_name, "dyld_stub_binder");
+        let lazy = binds(MachOBindKind::LazyBind);
+        assert_eq!(lazy.len(), 74);
+        assert_eq!(lazy[73].address, 0x1_0000_62A8);
+        assert_eq!(lazy[73].symbol_name, "_write");
+        assert!(binds(MachOBindKind::WeakBind).is_empty());
+
+        assert_eq!(dyld_info.exports.len(), 1);
+        assert_eq!(dyld_info.exports[0].name, "__mh_execute_header");
+        assert_eq!(dyld_info.exports[0].address, 0x1_0000_0000);
+        assert_eq!(file.exports[0].address, 0x1_0000_0000);
+    }
+
+    fn test_file(name: &str) -> Vec<u8> {
+        std::fs::read(format!(
+            "{}/../testExe/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    fn macho_details<'a>(file: &'a BinaryFile<'a>) -> &'a FileInfoMachO<'a> {
+        match &file.details {
+            FileDetails::MachO(macho) => macho,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn load_commands_are_walked() {
+        let bytes = test_file("MacOS/hello");
+        let file = parse_macho(&bytes).unwrap();
+        let load_commands = &macho_details(&file).load_commands;
+        assert_eq!(load_commands.len(), 15);
+        let segment_names: Vec<&str> = segment_commands(load_commands)
+            .iter()
+            .map(|segment| segment.name.as_str())
+            .collect();
+        assert_eq!(segment_names, ["__PAGEZERO", "__TEXT", "__LINKEDIT"]);
+        assert!(matches!(load_commands[5], LoadCommandData::Symtab(_)));
+        assert!(matches!(load_commands[6], LoadCommandData::Dysymtab(_)));
+        match &load_commands[7] {
+            LoadCommandData::Unknown(command) => {
+                assert_eq!(read_u32(command.cmd, Endianness::Little), LC_LOAD_DYLINKER);
+                assert_eq!(read_u32(command.cmdsize, Endianness::Little), 32);
+            }
+            command => panic!("{:?}", command),
+        }
+        match &load_commands[8] {
+            LoadCommandData::UUID(uuid) => assert_eq!(
+                uuid.uuid,
+                [
+                    0x21, 0x8C, 0xD0, 0x3C, 0x12, 0x81, 0x38, 0xAB, 0x95, 0x2F, 0xE7, 0x77, 0x4E,
+                    0x48, 0x1B, 0xB2
+                ]
+            ),
+            command => panic!("{:?}", command),
+        }
+        match &load_commands[11] {
+            LoadCommandData::EntryPoint(main) => {
+                assert_eq!(read_u64(main.entryoff, Endianness::Little), 0x3F60)
+            }
+            command => panic!("{:?}", command),
+        }
+        assert_eq!(file.entry_point, 0x1_0000_3F60);
+    }
+
+    #[test]
+    fn segments_and_sections_hold_their_bytes() {
+        let bytes = test_file("MacOS/hello");
+        let file = parse_macho(&bytes).unwrap();
+        let segments = segment_commands(&macho_details(&file).load_commands);
+        // __PAGEZERO reserves address space without file content
+        assert_eq!(
+            read_u64(segments[0].vmsize, Endianness::Little),
+            0x1_0000_0000
+        );
+        assert!(segments[0].raw_data.is_empty());
+        assert_eq!(segments[1].raw_data, &bytes[..0x4000]);
+        assert_eq!(
+            read_u64(segments[2].vmaddr, Endianness::Little),
+            0x1_0000_4000
+        );
+        assert_eq!(segments[2].raw_data, &bytes[0x4000..]);
+
+        let sections = &segments[1].sections;
+        let names: Vec<&str> = sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect();
+        assert_eq!(names, ["__text", "__cstring", "__unwind_info"]);
+        assert_eq!(sections[0].segment_name, "__TEXT");
+        assert_eq!(
+            read_u64(sections[0].addr, Endianness::Little),
+            0x1_0000_3F60
+        );
+        assert_eq!(sections[0].raw_data, &bytes[0x3F60..0x3F88]);
+        assert_eq!(sections[1].raw_data, b"Hello, World!\n\0Hello, World!\n\0");
+        // only section_64 records have reserved3
+        assert_eq!(sections[2].reserved3.len(), 4);
+
+        let cstring = file
+            .sections
+            .iter()
+            .find(|section| section.name == "__TEXT,__cstring")
+            .unwrap();
+        assert_eq!(cstring.virtual_address, 0x1_0000_3F88);
+        assert_eq!(cstring.file_offset, 0x3F88);
+        assert_eq!(cstring.raw_data, sections[1].raw_data);
+        let linkedit = file
+            .segments
+            .iter()
+            .find(|segment| segment.name == "__LINKEDIT")
+            .unwrap();
+        assert_eq!((linkedit.file_offset, linkedit.file_size), (0x4000, 488));
+    }
+
+    #[test]
+    fn stubs_and_pointers_resolve_through_indirect_symbols() {
+        let bytes = test_file("MacOS/MachO-OSX-x64-ls");
+        let file = parse_macho(&bytes).unwrap();
+        let macho = macho_details(&file);
+        assert_eq!(macho.indirect_symbols.len(), 155);
+        // 74 stubs, 5 __got slots and 74 lazy pointers, the two __nl_symbol_ptr slots are
+        // INDIRECT_SYMBOL_ABS
+        let entries = &macho.indirect_entries;
+        assert_eq!(entries.len(), 153);
+        assert_eq!(entries[0].address, 0x1_0000_4DAE);
+        assert_eq!(entries[0].section_name, "__TEXT,__stubs");
+        assert_eq!(entries[0].name, "___error");
+        assert_eq!(entries[73].address, 0x1_0000_4F64);
+        assert_eq!(entries[73].name, "_write");
+        assert_eq!(entries[78].address, 0x1_0000_6048);
+        assert_eq!(entries[78].section_name, "__DATA,__got");
+        assert_eq!(entries[78].name, "_optind");
+        assert_eq!(entries[152].address, 0x1_0000_62A8);
+        assert_eq!(entries[152].section_name, "__DATA,__la_symbol_ptr");
+        assert_eq!(entries[152].name, "_write");
+        assert!(!entries.iter().any(|entry| entry.address == 0x1_0000_6050));
+
+        let stub = file
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == "_write@stub")
+            .unwrap();
+        assert!(matches!(stub.kind, SymbolKind::Function));
+        assert_eq!(stub.address, 0x1_0000_4F64);
+        // calls go through the stub, data through its pointer slot
+        let import = |name: &str| {
+            file.imports
+                .iter()
+                .find(|import| import.name == name)
+                .unwrap()
+        };
+        assert_eq!(import("_write").address, 0x1_0000_4F64);
+        assert_eq!(import("_write").library, "/usr/lib/libSystem.B.dylib");
+        assert_eq!(import("_optind").address, 0x1_0000_6048);
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        for name in ["MacOS/hello", "MacOS/hello.o"] {
+            let bytes = test_file(name);
+            parse_macho(&bytes).unwrap();
+            let header = extract_macho_header(&bytes).unwrap();
+            let commands_end =
+                MH_HEADER_64_SIZE + read_u32(header.lcsize, Endianness::Little) as usize;
+            for length in 0..commands_end {
+                assert!(
+                    parse_macho(&bytes[..length]).is_err(),
+                    "{} {}",
+                    name,
+                    length
+                );
+            }
+            // cuts past the load commands may still parse, they must not panic
+            for length in (commands_end..bytes.len()).step_by(61) {
+                let _ = parse_macho(&bytes[..length]);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_load_commands_are_rejected() {
+        let mut command = Vec::new();
+        push_u32s(&mut command, &[LC_MAIN, 0, 0, 0, 0, 0]);
+        assert!(matches!(
+            parse_macho(&executable(&[command])),
+            Err(ParseError::InvalidSize {
+                structure: "Mach-O load command",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn export_trie_cycles_are_rejected() {
+        // terminal size 0, one child "a" pointing back to the root
+        let trie = [0, 1, b'a', 0, 0];
+        assert!(matches!(
+            extract_export_trie(&trie, 0),
+            Err(ParseError::InvalidStringIndex {
+                table: "Mach-O export trie",
+                index: 0
+            })
+        ));
+        let trie = [0, 1, b'a', 0, 0x80];
+        assert!(extract_export_trie(&trie, 0).is_err());
+        let trie = [0, 1, b'a', 0, 0x7F];
+        assert!(matches!(
+            extract_export_trie(&trie, 0),
+            Err(ParseError::OutOfRange { offset: 0x7F, .. })
+        ));
+    }
+
+    /// Universal binary of the hello executable and object file, slices aligned to 4 KiB
+    fn universal_binary() -> Vec<u8> {
+        let slices = [test_file("MacOS/hello"), test_file("MacOS/hello.o")];
+        let mut bytes = FAT_MAGIC.to_vec();
+        bytes.extend_from_slice(&2u32.to_be_bytes());
+        let mut offset = 0x1000;
+        for slice in &slices {
+            for value in [0x0100_0007, 3, offset, slice.len() as u32, 12] {
+                bytes.extend_from_slice(&u32::to_be_bytes(value));
+            }
+            offset = (offset + slice.len() as u32).next_multiple_of(0x1000);
+        }
+        for slice in &slices {
+            bytes.resize(bytes.len().next_multiple_of(0x1000), 0);
+            bytes.extend_from_slice(slice);
+        }
+        bytes
+    }
+
+    #[test]
+    fn fat_slices_are_parsed_by_index() {
+        let bytes = universal_binary();
+        let fat = parse_fat(&bytes).unwrap();
+        assert_eq!(fat.architectures.len(), 2);
+        assert!(matches!(
+            fat_arch_architecture(&fat.architectures[1]),
+            Architecture::X86_64
+        ));
+        assert_eq!(
+            fat_slice(&bytes, &fat.architectures[1]).unwrap(),
+            test_file("MacOS/hello.o")
+        );
+
+        let executable = parse_fat_slice(&bytes, 0).unwrap();
+        assert_eq!(executable.entry_point, 0x1_0000_3F60);
+        let object = parse_fat_slice(&bytes, 1).unwrap();
+        assert_eq!(
+            read_u32(macho_details(&object).header.ftype, Endianness::Little),
+            1
+        );
+        assert_eq!(object.entry_point, 0);
+        assert_eq!(object.sections[0].name, "__TEXT,__text");
+        assert!(matches!(
+            parse_fat_slice(&bytes, 2),
+            Err(ParseError::InvalidStringIndex {
+                table: "fat arch table",
+                index: 2
+            })
+        ));
+        // the signature does not take a universal binary for a class file
+        assert_eq!(crate::parse(&bytes).unwrap().entry_point, 0x1_0000_3F60);
+    }
+
+    #[test]
+    fn fat_arch_tables_are_bounded() {
+        let mut bytes = FAT_MAGIC.to_vec();
+        bytes.extend_from_slice(&0xFFFF_FFFFu32.to_be_bytes());
+        assert!(matches!(
+            parse_fat(&bytes),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+
+        // one slice past the end of the file
+        let mut bytes = FAT_MAGIC.to_vec();
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        for value in [0x0100_0007, 3, 0x1000, 0x1000, 12] {
+            bytes.extend_from_slice(&u32::to_be_bytes(value));
+        }
+        assert!(matches!(
+            parse_fat_slice(&bytes, 0),
+            Err(ParseError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            parse_fat_slice(&bytes, 1),
+            Err(ParseError::InvalidStringIndex {
+                table: "fat arch table",
+                index: 1
+            })
+        ));
+    }
+}
@@ -8,6 +8,135 @@ pub struct MachOHeader<'a> {
     pub lcnum: &'a [u8],
     pub lcsize: &'a [u8],
     pub flags: &'a [u8],
+    /// Only in mach_header_64, empty for 32-bit files
+    pub reserved: &'a [u8],
+}
+
+/// Universal binaries, the fat headers are always big endian
+pub const FAT_MAGIC: &[u8] = b"\xCA\xFE\xBA\xBE";
+pub const FAT_MAGIC_64: &[u8] = b"\xCA\xFE\xBA\xBF";
+pub const FAT_HEADER_SIZE: usize = 8;
+pub const FAT_ARCH_SIZE: usize = 20;
+pub const FAT_ARCH_64_SIZE: usize = 32;
+
+pub const MH_HEADER_SIZE: usize = 28;
+pub const MH_HEADER_64_SIZE: usize = 32;
+
+/// Size of segment_command / segment_command_64 without the sections following them
+pub const SEGMENT_COMMAND_SIZE: usize = 56;
+pub const SEGMENT_COMMAND_64_SIZE: usize = 72;
+pub const SECTION_SIZE: usize = 68;
+pub const SECTION_64_SIZE: usize = 80;
+
+/// Low byte of a section `flags`
+pub const SECTION_TYPE: u32 = 0xFF;
+pub const S_REGULAR: u32 = 0x0;
+pub const S_ZEROFILL: u32 = 0x1;
+pub const S_CSTRING_LITERALS: u32 = 0x2;
+pub const S_NON_LAZY_SYMBOL_POINTERS: u32 = 0x6;
+pub const S_LAZY_SYMBOL_POINTERS: u32 = 0x7;
+pub const S_SYMBOL_STUBS: u32 = 0x8;
+pub const S_MOD_INIT_FUNC_POINTERS: u32 = 0x9;
+pub const S_GB_ZEROFILL: u32 = 0xC;
+pub const S_LAZY_DYLIB_SYMBOL_POINTERS: u32 = 0x10;
+pub const S_THREAD_LOCAL_ZEROFILL: u32 = 0x12;
+pub const S_THREAD_LOCAL_VARIABLE_POINTERS: u32 = 0x14;
+pub const S_ATTR_PURE_INSTRUCTIONS: u32 = 0x8000_0000;
+pub const S_ATTR_SOME_INSTRUCTIONS: u32 = 0x400;
+
+pub const NLIST_SIZE: usize = 12;
+pub const NLIST_64_SIZE: usize = 16;
+
+/// n_type masks
+pub const N_STAB: u8 = 0xE0;
+pub const N_PEXT: u8 = 0x10;
+pub const N_TYPE: u8 = 0x0E;
+pub const N_EXT: u8 = 0x01;
+/// N_TYPE values
+pub const N_UNDF: u8 = 0x0;
+pub const N_ABS: u8 = 0x2;
+pub const N_SECT: u8 = 0xE;
+pub const N_PBUD: u8 = 0xC;
+pub const N_INDR: u8 = 0xA;
+
+/// Library ordinals stored in the high byte of n_desc
+pub const SELF_LIBRARY_ORDINAL: u8 = 0x0;
+pub const DYNAMIC_LOOKUP_ORDINAL: u8 = 0xFE;
+pub const EXECUTABLE_ORDINAL: u8 = 0xFF;
+
+pub const INDIRECT_SYMBOL_LOCAL: u32 = 0x8000_0000;
+pub const INDIRECT_SYMBOL_ABS: u32 = 0x4000_0000;
+
+/// Commands with this bit set must be understood by dyld to load the file
+pub const LC_REQ_DYLD: u32 = 0x8000_0000;
+pub const LC_SEGMENT: u32 = 0x1;
+pub const LC_SYMTAB: u32 = 0x2;
+pub const LC_SYMSEG: u32 = 0x3;
+pub const LC_THREAD: u32 = 0x4;
+pub const LC_UNIXTHREAD: u32 = 0x5;
+pub const LC_DYSYMTAB: u32 = 0xB;
+pub const LC_LOAD_DYLIB: u32 = 0xC;
+pub const LC_ID_DYLIB: u32 = 0xD;
+pub const LC_LOAD_DYLINKER: u32 = 0xE;
+pub const LC_ID_DYLINKER: u32 = 0xF;
+pub const LC_ROUTINES: u32 = 0x11;
+pub const LC_PREBIND_CKSUM: u32 = 0x17;
+pub const LC_LOAD_WEAK_DYLIB: u32 = 0x18 | LC_REQ_DYLD;
+pub const LC_SEGMENT_64: u32 = 0x19;
+pub const LC_ROUTINES_64: u32 = 0x1A;
+pub const LC_UUID: u32 = 0x1B;
+pub const LC_RPATH: u32 = 0x1C | LC_REQ_DYLD;
+pub const LC_CODE_SIGNATURE: u32 = 0x1D;
+pub const LC_SEGMENT_SPLIT_INFO: u32 = 0x1E;
+pub const LC_REEXPORT_DYLIB: u32 = 0x1F | LC_REQ_DYLD;
+pub const LC_LAZY_LOAD_DYLIB: u32 = 0x20;
+pub const LC_ENCRYPTION_INFO: u32 = 0x21;
+pub const LC_DYLD_INFO: u32 = 0x22;
+pub const LC_DYLD_INFO_ONLY: u32 = 0x22 | LC_REQ_DYLD;
+pub const LC_LOAD_UPWARD_DYLIB: u32 = 0x23 | LC_REQ_DYLD;
+pub const LC_VERSION_MIN_MACOSX: u32 = 0x24;
+pub const LC_VERSION_MIN_IPHONEOS: u32 = 0x25;
+pub const LC_FUNCTION_STARTS: u32 = 0x26;
+pub const LC_DYLD_ENVIRONMENT: u32 = 0x27;
+pub const LC_MAIN: u32 = 0x28 | LC_REQ_DYLD;
+pub const LC_DATA_IN_CODE: u32 = 0x29;
+pub const LC_SOURCE_VERSION: u32 = 0x2A;
+pub const LC_DYLIB_CODE_SIGN_DRS: u32 = 0x2B;
+pub const LC_ENCRYPTION_INFO_64: u32 = 0x2C;
+pub const LC_LINKER_OPTION: u32 = 0x2D;
+pub const LC_LINKER_OPTIMIZATION_HINT: u32 = 0x2E;
+pub const LC_VERSION_MIN_TVOS: u32 = 0x2F;
+pub const LC_VERSION_MIN_WATCHOS: u32 = 0x30;
+pub const LC_NOTE: u32 = 0x31;
+pub const LC_BUILD_VERSION: u32 = 0x32;
+pub const LC_DYLD_EXPORTS_TRIE: u32 = 0x33 | LC_REQ_DYLD;
+pub const LC_DYLD_CHAINED_FIXUPS: u32 = 0x34 | LC_REQ_DYLD;
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FatHeader<'a> {
+    pub magic: &'a [u8],
+    pub nfat_arch: &'a [u8],
+}
+
+/// fat_arch or fat_arch_64, `offset` and `size` locate the Mach-O slice in the file
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FatArch<'a> {
+    pub cputype: &'a [u8],
+    pub cpusubtype: &'a [u8],
+    pub offset: &'a [u8],
+    pub size: &'a [u8],
+    pub align: &'a [u8],
+    /// Only in fat_arch_64, empty otherwise
+    pub reserved: &'a [u8],
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FileInfoFat<'a> {
+    pub header: FatHeader<'a>,
+    pub architectures: Vec<FatArch<'a>>,
 }
 
 #[allow(dead_code)]
@@ -22,6 +151,7 @@ pub struct LoadCommand<'a> {
 pub struct SegmentCommand<'a> {
     pub load_cmd: LoadCommand<'a>,
     pub segname: &'a [u8],
+    pub name: String,
     pub vmaddr: &'a [u8],
     pub vmsize: &'a [u8],
     pub fileoff: &'a [u8],
@@ -30,6 +160,8 @@ pub struct SegmentCommand<'a> {
     pub initprot: &'a [u8],
     pub nsects: &'a [u8],
     pub flags: &'a [u8],
+    pub raw_data: &'a [u8],
+    pub sections: Vec<SectionCommand<'a>>,
 }
 
 #[allow(dead_code)]
@@ -37,6 +169,8 @@ pub struct SegmentCommand<'a> {
 pub struct SectionCommand<'a> {
     pub sectname: &'a [u8],
     pub segname: &'a [u8],
+    pub name: String,
+    pub segment_name: String,
     pub addr: &'a [u8],
     pub size: &'a [u8],
     pub symbol_table_for_offset: &'a [u8],
@@ -44,7 +178,14 @@ pub struct SectionCommand<'a> {
     pub reloff: &'a [u8],
     pub nreloc: &'a [u8],
     pub flags: &'a [u8],
-    pub reserved: &'a [u8],
+    /// Index in the indirect symbol table for stubs and pointer sections
+    pub reserved1: &'a [u8],
+    /// Stub size for S_SYMBOL_STUBS
+    pub reserved2: &'a [u8],
+    /// Only in section_64, empty for 32-bit files
+    pub reserved3: &'a [u8],
+    /// Empty for zero fill sections
+    pub raw_data: &'a [u8],
 }
 
 #[allow(dead_code)]
@@ -52,6 +193,8 @@ pub struct SectionCommand<'a> {
 pub struct DylibCommand<'a> {
     pub load_cmd: LoadCommand<'a>,
     pub name_offset: &'a [u8],
+    /// Install name read at `name_offset`
+    pub name: String,
     pub timestamp: &'a [u8],
     pub current_version: &'a [u8],
     pub compatibility_version: &'a [u8],
@@ -77,7 +220,6 @@ pub struct SymsegCommand<'a> {
 
 #[allow(dead_code)]
 #[derive(Debug)]
-
 pub struct EntryPointCommand<'a> {
     pub load_cmd: LoadCommand<'a>,
     pub entryoff: &'a [u8],
@@ -186,9 +328,11 @@ pub struct DyldInfoCommand<'a> {
 pub struct RunPathCommand<'a> {
     pub load_cmd: LoadCommand<'a>,
     pub symbol_table_for_offset: &'a [u8],
+    pub path: String,
 }
 
 #[allow(dead_code)]
+#[derive(Debug)]
 pub enum LoadCommandData<'a> {
     Segment(SegmentCommand<'a>),
     Symtab(SymtabCommand<'a>),
@@ -205,4 +349,159 @@ pub enum LoadCommandData<'a> {
     RunPath(RunPathCommand<'a>),
     DyldInfo(DyldInfoCommand<'a>),
     LinkEditData(LinkEditDataCommand<'a>),
+    /// Commands the parser does not decode (LC_LOAD_DYLINKER, LC_BUILD_VERSION...)
+    Unknown(LoadCommand<'a>),
+}
+
+/// nlist / nlist_64 entry
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct MachOSymbol {
+    pub name: String,
+    pub name_offset: u32,
+    pub symbol_type: u8,
+    /// 1-based index across the sections of every segment, 0 is NO_SECT
+    pub section: u8,
+    pub description: u16,
+    pub value: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct MachOSymbolTable {
+    pub symbols: Vec<MachOSymbol>,
+}
+
+/// Stub or pointer slot resolved through the indirect symbol table
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct MachOIndirectEntry {
+    pub address: u64,
+    /// "__TEXT,__stubs", "__DATA,__la_symbol_ptr"...
+    pub section_name: String,
+    pub section_type: u32,
+    pub name: String,
+}
+
+pub const REBASE_TYPE_POINTER: u8 = 1;
+pub const BIND_TYPE_POINTER: u8 = 1;
+pub const BIND_SYMBOL_FLAGS_WEAK_IMPORT: u8 = 0x1;
+
+pub const REBASE_OPCODE_MASK: u8 = 0xF0;
+pub const REBASE_IMMEDIATE_MASK: u8 = 0x0F;
+pub const REBASE_OPCODE_DONE: u8 = 0x00;
+pub const REBASE_OPCODE_SET_TYPE_IMM: u8 = 0x10;
+pub const REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB: u8 = 0x20;
+pub const REBASE_OPCODE_ADD_ADDR_ULEB: u8 = 0x30;
+pub const REBASE_OPCODE_ADD_ADDR_IMM_SCALED: u8 = 0x40;
+pub const REBASE_OPCODE_DO_REBASE_IMM_TIMES: u8 = 0x50;
+pub const REBASE_OPCODE_DO_REBASE_ULEB_TIMES: u8 = 0x60;
+pub const REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB: u8 = 0x70;
+pub const REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB: u8 = 0x80;
+
+pub const BIND_OPCODE_MASK: u8 = 0xF0;
+pub const BIND_IMMEDIATE_MASK: u8 = 0x0F;
+pub const BIND_OPCODE_DONE: u8 = 0x00;
+pub const BIND_OPCODE_SET_DYLIB_ORDINAL_IMM: u8 = 0x10;
+pub const BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB: u8 = 0x20;
+pub const BIND_OPCODE_SET_DYLIB_SPECIAL_IMM: u8 = 0x30;
+pub const BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM: u8 = 0x40;
+pub const BIND_OPCODE_SET_TYPE_IMM: u8 = 0x50;
+pub const BIND_OPCODE_SET_ADDEND_SLEB: u8 = 0x60;
+pub const BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB: u8 = 0x70;
+pub const BIND_OPCODE_ADD_ADDR_ULEB: u8 = 0x80;
+pub const BIND_OPCODE_DO_BIND: u8 = 0x90;
+pub const BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB: u8 = 0xA0;
+pub const BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED: u8 = 0xB0;
+pub const BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB: u8 = 0xC0;
+pub const BIND_OPCODE_THREADED: u8 = 0xD0;
+
+pub const EXPORT_SYMBOL_FLAGS_KIND_MASK: u64 = 0x03;
+pub const EXPORT_SYMBOL_FLAGS_KIND_REGULAR: u64 = 0x00;
+pub const EXPORT_SYMBOL_FLAGS_KIND_THREAD_LOCAL: u64 = 0x01;
+pub const EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE: u64 = 0x02;
+pub const EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION: u64 = 0x04;
+pub const EXPORT_SYMBOL_FLAGS_REEXPORT: u64 = 0x08;
+pub const EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER: u64 = 0x10;
+
+pub const DYLD_CHAINED_IMPORT: u32 = 1;
+pub const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+pub const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+
+pub const DYLD_CHAINED_PTR_ARM64E: u16 = 1;
+pub const DYLD_CHAINED_PTR_64: u16 = 2;
+pub const DYLD_CHAINED_PTR_32: u16 = 3;
+pub const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND: u16 = 9;
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND24: u16 = 12;
+pub const DYLD_CHAINED_PTR_START_NONE: u16 = 0xFFFF;
+pub const DYLD_CHAINED_PTR_START_MULTI: u16 = 0x8000;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachOBindKind {
+    Bind,
+    WeakBind,
+    LazyBind,
+    /// Bind found by walking a LC_DYLD_CHAINED_FIXUPS chain
+    ChainedFixup,
+}
+
+/// Pointer dyld sets to the address of an imported symbol
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct MachOBind {
+    pub kind: MachOBindKind,
+    pub address: u64,
+    pub symbol_name: String,
+    /// 1-based dylib index, 0 for the image itself, -1 the main executable, -2 flat lookup
+    pub library_ordinal: i64,
+    pub bind_type: u8,
+    pub addend: i64,
+    pub weak_import: bool,
+}
+
+/// Pointer dyld slides when the image is not loaded at its preferred address
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct MachORebase {
+    pub address: u64,
+    pub rebase_type: u8,
+    /// Unslid pointer value, only known for chained fixups where it is encoded in the chain
+    pub target: Option<u64>,
+}
+
+/// Export trie entry, `address` already has the image base added
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct MachOExport {
+    pub name: String,
+    pub flags: u64,
+    pub address: u64,
+    /// Library ordinal and name of the symbol re-exported with EXPORT_SYMBOL_FLAGS_REEXPORT
+    pub reexport: Option<(u64, String)>,
+    /// Resolver function of EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER exports
+    pub resolver: Option<u64>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct MachODyldInfo {
+    /// From LC_DYLD_INFO opcodes or LC_DYLD_CHAINED_FIXUPS
+    pub binds: Vec<MachOBind>,
+    pub rebases: Vec<MachORebase>,
+    /// From LC_DYLD_INFO or LC_DYLD_EXPORTS_TRIE
+    pub exports: Vec<MachOExport>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FileInfoMachO<'a> {
+    pub header: MachOHeader<'a>,
+    pub load_commands: Vec<LoadCommandData<'a>>,
+    pub symbol_table: MachOSymbolTable,
+    /// Indexes in `symbol_table`, possibly INDIRECT_SYMBOL_LOCAL / INDIRECT_SYMBOL_ABS
+    pub indirect_symbols: Vec<u32>,
+    pub indirect_entries: Vec<MachOIndirectEntry>,
+    pub dyld_info: MachODyldInfo,
 }
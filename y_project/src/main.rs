@@ -1,9 +1,24 @@
 use y_project::*;
 
 fn main() {
-    help();
-    //let context: Ctx = get_arguments();
-    //let bytecode = read_file(&context.filename);
-    //let sign = get_sign(&bytecode);
-    //get_file_data(&sign, &bytecode);
+    let context: Ctx = get_arguments();
+    let bytecode = match read_file(&context.filename) {
+        Ok(bytecode) => bytecode,
+        Err(error) => {
+            eprintln!("*[-] {}", error);
+            std::process::exit(1);
+        }
+    };
+    let database = match load_signatures(&context) {
+        Ok(database) => database,
+        Err(error) => {
+            eprintln!("*[-] {}", error);
+            std::process::exit(1);
+        }
+    };
+    let sign = get_sign(&database, &bytecode);
+    get_file_data(&sign, &bytecode);
+    if context.disassemble {
+        print_disassembly(&sign, &bytecode);
+    }
 }
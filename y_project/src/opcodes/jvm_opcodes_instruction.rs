@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+/// Operand layout following a JVM opcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JvmOperands {
+    None,
+    /// Signed byte (bipush)
+    Byte,
+    /// Signed short (sipush)
+    Short,
+    /// Local variable index, 16 bits after `wide`
+    Local,
+    /// 8 bits constant pool index (ldc)
+    ConstantByte,
+    Constant,
+    /// Signed 16 bits offset from the opcode
+    Branch,
+    /// Signed 32 bits offset from the opcode (goto_w, jsr_w)
+    BranchWide,
+    /// Local index and signed increment, both 16 bits after `wide`
+    Iinc,
+    /// Constant pool index, argument count and a zero byte
+    InvokeInterface,
+    /// Constant pool index and two zero bytes
+    InvokeDynamic,
+    /// Primitive array type code
+    NewArray,
+    /// Constant pool index and dimensions
+    MultiANewArray,
+    /// Padded to 4 bytes, default, low, high and high - low + 1 offsets
+    TableSwitch,
+    /// Padded to 4 bytes, default, pair count and sorted match/offset pairs
+    LookupSwitch,
+    /// Prefix widening the local index of the next opcode
+    Wide,
+}
+
+pub fn get_opcodes() -> HashMap<u8, (&'static str, JvmOperands)> {
+    HashMap::from([
+        (0x00, ("nop", JvmOperands::None)),
+        (0x01, ("aconst_null", JvmOperands::None)),
+        (0x02, ("iconst_m1", JvmOperands::None)),
+        (0x03, ("iconst_0", JvmOperands::None)),
+        (0x04, ("iconst_1", JvmOperands::None)),
+        (0x05, ("iconst_2", JvmOperands::None)),
+        (0x06, ("iconst_3", JvmOperands::None)),
+        (0x07, ("iconst_4", JvmOperands::None)),
+        (0x08, ("iconst_5", JvmOperands::None)),
+        (0x09, ("lconst_0", JvmOperands::None)),
+        (0x0A, ("lconst_1", JvmOperands::None)),
+        (0x0B, ("fconst_0", JvmOperands::None)),
+        (0x0C, ("fconst_1", JvmOperands::None)),
+        (0x0D, ("fconst_2", JvmOperands::None)),
+        (0x0E, ("dconst_0", JvmOperands::None)),
+        (0x0F, ("dconst_1", JvmOperands::None)),
+        (0x10, ("bipush", JvmOperands::Byte)),
+        (0x11, ("sipush", JvmOperands::Short)),
+        (0x12, ("ldc", JvmOperands::ConstantByte)),
+        (0x13, ("ldc_w", JvmOperands::Constant)),
+        (0x14, ("ldc2_w", JvmOperands::Constant)),
+        (0x15, ("iload", JvmOperands::Local)),
+        (0x16, ("lload", JvmOperands::Local)),
+        (0x17, ("fload", JvmOperands::Local)),
+        (0x18, ("dload", JvmOperands::Local)),
+        (0x19, ("aload", JvmOperands::Local)),
+        (0x1A, ("iload_0", JvmOperands::None)),
+        (0x1B, ("iload_1", JvmOperands::None)),
+        (0x1C, ("iload_2", JvmOperands::None)),
+        (0x1D, ("iload_3", JvmOperands::None)),
+        (0x1E, ("lload_0", JvmOperands::None)),
+        (0x1F, ("lload_1", JvmOperands::None)),
+        (0x20, ("lload_2", JvmOperands::None)),
+        (0x21, ("lload_3", JvmOperands::None)),
+        (0x22, ("fload_0", JvmOperands::None)),
+        (0x23, ("fload_1", JvmOperands::None)),
+        (0x24, ("fload_2", JvmOperands::None)),
+        (0x25, ("fload_3", JvmOperands::None)),
+        (0x26, ("dload_0", JvmOperands::None)),
+        (0x27, ("dload_1", JvmOperands::None)),
+        (0x28, ("dload_2", JvmOperands::None)),
+        (0x29, ("dload_3", JvmOperands::None)),
+        (0x2A, ("aload_0", JvmOperands::None)),
+        (0x2B, ("aload_1", JvmOperands::None)),
+        (0x2C, ("aload_2", JvmOperands::None)),
+        (0x2D, ("aload_3", JvmOperands::None)),
+        (0x2E, ("iaload", JvmOperands::None)),
+        (0x2F, ("laload", JvmOperands::None)),
+        (0x30, ("faload", JvmOperands::None)),
+        (0x31, ("daload", JvmOperands::None)),
+        (0x32, ("aaload", JvmOperands::None)),
+        (0x33, ("baload", JvmOperands::None)),
+        (0x34, ("caload", JvmOperands::None)),
+        (0x35, ("saload", JvmOperands::None)),
+        (0x36, ("istore", JvmOperands::Local)),
+        (0x37, ("lstore", JvmOperands::Local)),
+        (0x38, ("fstore", JvmOperands::Local)),
+        (0x39, ("dstore", JvmOperands::Local)),
+        (0x3A, ("astore", JvmOperands::Local)),
+        (0x3B, ("istore_0", JvmOperands::None)),
+        (0x3C, ("istore_1", JvmOperands::None)),
+        (0x3D, ("istore_2", JvmOperands::None)),
+        (0x3E, ("istore_3", JvmOperands::None)),
+        (0x3F, ("lstore_0", JvmOperands::None)),
+        (0x40, ("lstore_1", JvmOperands::None)),
+        (0x41, ("lstore_2", JvmOperands::None)),
+        (0x42, ("lstore_3", JvmOperands::None)),
+        (0x43, ("fstore_0", JvmOperands::None)),
+        (0x44, ("fstore_1", JvmOperands::None)),
+        (0x45, ("fstore_2", JvmOperands::None)),
+        (0x46, ("fstore_3", JvmOperands::None)),
+        (0x47, ("dstore_0", JvmOperands::None)),
+        (0x48, ("dstore_1", JvmOperands::None)),
+        (0x49, ("dstore_2", JvmOperands::None)),
+        (0x4A, ("dstore_3", JvmOperands::None)),
+        (0x4B, ("astore_0", JvmOperands::None)),
+        (0x4C, ("astore_1", JvmOperands::None)),
+        (0x4D, ("astore_2", JvmOperands::None)),
+        (0x4E, ("astore_3", JvmOperands::None)),
+        (0x4F, ("iastore", JvmOperands::None)),
+        (0x50, ("lastore", JvmOperands::None)),
+        (0x51, ("fastore", JvmOperands::None)),
+        (0x52, ("dastore", JvmOperands::None)),
+        (0x53, ("aastore", JvmOperands::None)),
+        (0x54, ("bastore", JvmOperands::None)),
+        (0x55, ("castore", JvmOperands::None)),
+        (0x56, ("sastore", JvmOperands::None)),
+        (0x57, ("pop", JvmOperands::None)),
+        (0x58, ("pop2", JvmOperands::None)),
+        (0x59, ("dup", JvmOperands::None)),
+        (0x5A, ("dup_x1", JvmOperands::None)),
+        (0x5B, ("dup_x2", JvmOperands::None)),
+        (0x5C, ("dup2", JvmOperands::None)),
+        (0x5D, ("dup2_x1", JvmOperands::None)),
+        (0x5E, ("dup2_x2", JvmOperands::None)),
+        (0x5F, ("swap", JvmOperands::None)),
+        (0x60, ("iadd", JvmOperands::None)),
+        (0x61, ("ladd", JvmOperands::None)),
+        (0x62, ("fadd", JvmOperands::None)),
+        (0x63, ("dadd", JvmOperands::None)),
+        (0x64, ("isub", JvmOperands::None)),
+        (0x65, ("lsub", JvmOperands::None)),
+        (0x66, ("fsub", JvmOperands::None)),
+        (0x67, ("dsub", JvmOperands::None)),
+        (0x68, ("imul", JvmOperands::None)),
+        (0x69, ("lmul", JvmOperands::None)),
+        (0x6A, ("fmul", JvmOperands::None)),
+        (0x6B, ("dmul", JvmOperands::None)),
+        (0x6C, ("idiv", JvmOperands::None)),
+        (0x6D, ("ldiv", JvmOperands::None)),
+        (0x6E, ("fdiv", JvmOperands::None)),
+        (0x6F, ("ddiv", JvmOperands::None)),
+        (0x70, ("irem", JvmOperands::None)),
+        (0x71, ("lrem", JvmOperands::None)),
+        (0x72, ("frem", JvmOperands::None)),
+        (0x73, ("drem", JvmOperands::None)),
+        (0x74, ("ineg", JvmOperands::None)),
+        (0x75, ("lneg", JvmOperands::None)),
+        (0x76, ("fneg", JvmOperands::None)),
+        (0x77, ("dneg", JvmOperands::None)),
+        (0x78, ("ishl", JvmOperands::None)),
+        (0x79, ("lshl", JvmOperands::None)),
+        (0x7A, ("ishr", JvmOperands::None)),
+        (0x7B, ("lshr", JvmOperands::None)),
+        (0x7C, ("iushr", JvmOperands::None)),
+        (0x7D, ("lushr", JvmOperands::None)),
+        (0x7E, ("iand", JvmOperands::None)),
+        (0x7F, ("land", JvmOperands::None)),
+        (0x80, ("ior", JvmOperands::None)),
+        (0x81, ("lor", JvmOperands::None)),
+        (0x82, ("ixor", JvmOperands::None)),
+        (0x83, ("lxor", JvmOperands::None)),
+        (0x84, ("iinc", JvmOperands::Iinc)),
+        (0x85, ("i2l", JvmOperands::None)),
+        (0x86, ("i2f", JvmOperands::None)),
+        (0x87, ("i2d", JvmOperands::None)),
+        (0x88, ("l2i", JvmOperands::None)),
+        (0x89, ("l2f", JvmOperands::None)),
+        (0x8A, ("l2d", JvmOperands::None)),
+        (0x8B, ("f2i", JvmOperands::None)),
+        (0x8C, ("f2l", JvmOperands::None)),
+        (0x8D, ("f2d", JvmOperands::None)),
+        (0x8E, ("d2i", JvmOperands::None)),
+        (0x8F, ("d2l", JvmOperands::None)),
+        (0x90, ("d2f", JvmOperands::None)),
+        (0x91, ("i2b", JvmOperands::None)),
+        (0x92, ("i2c", JvmOperands::None)),
+        (0x93, ("i2s", JvmOperands::None)),
+        (0x94, ("lcmp", JvmOperands::None)),
+        (0x95, ("fcmpl", JvmOperands::None)),
+        (0x96, ("fcmpg", JvmOperands::None)),
+        (0x97, ("dcmpl", JvmOperands::None)),
+        (0x98, ("dcmpg", JvmOperands::None)),
+        (0x99, ("ifeq", JvmOperands::Branch)),
+        (0x9A, ("ifne", JvmOperands::Branch)),
+        (0x9B, ("iflt", JvmOperands::Branch)),
+        (0x9C, ("ifge", JvmOperands::Branch)),
+        (0x9D, ("ifgt", JvmOperands::Branch)),
+        (0x9E, ("ifle", JvmOperands::Branch)),
+        (0x9F, ("if_icmpeq", JvmOperands::Branch)),
+        (0xA0, ("if_icmpne", JvmOperands::Branch)),
+        (0xA1, ("if_icmplt", JvmOperands::Branch)),
+        (0xA2, ("if_icmpge", JvmOperands::Branch)),
+        (0xA3, ("if_icmpgt", JvmOperands::Branch)),
+        (0xA4, ("if_icmple", JvmOperands::Branch)),
+        (0xA5, ("if_acmpeq", JvmOperands::Branch)),
+        (0xA6, ("if_acmpne", JvmOperands::Branch)),
+        (0xA7, ("goto", JvmOperands::Branch)),
+        (0xA8, ("jsr", JvmOperands::Branch)),
+        (0xA9, ("ret", JvmOperands::Local)),
+        (0xAA, ("tableswitch", JvmOperands::TableSwitch)),
+        (0xAB, ("lookupswitch", JvmOperands::LookupSwitch)),
+        (0xAC, ("ireturn", JvmOperands::None)),
+        (0xAD, ("lreturn", JvmOperands::None)),
+        (0xAE, ("freturn", JvmOperands::None)),
+        (0xAF, ("dreturn", JvmOperands::None)),
+        (0xB0, ("areturn", JvmOperands::None)),
+        (0xB1, ("return", JvmOperands::None)),
+        (0xB2, ("getstatic", JvmOperands::Constant)),
+        (0xB3, ("putstatic", JvmOperands::Constant)),
+        (0xB4, ("getfield", JvmOperands::Constant)),
+        (0xB5, ("putfield", JvmOperands::Constant)),
+        (0xB6, ("invokevirtual", JvmOperands::Constant)),
+        (0xB7, ("invokespecial", JvmOperands::Constant)),
+        (0xB8, ("invokestatic", JvmOperands::Constant)),
+        (0xB9, ("invokeinterface", JvmOperands::InvokeInterface)),
+        (0xBA, ("invokedynamic", JvmOperands::InvokeDynamic)),
+        (0xBB, ("new", JvmOperands::Constant)),
+        (0xBC, ("newarray", JvmOperands::NewArray)),
+        (0xBD, ("anewarray", JvmOperands::Constant)),
+        (0xBE, ("arraylength", JvmOperands::None)),
+        (0xBF, ("athrow", JvmOperands::None)),
+        (0xC0, ("checkcast", JvmOperands::Constant)),
+        (0xC1, ("instanceof", JvmOperands::Constant)),
+        (0xC2, ("monitorenter", JvmOperands::None)),
+        (0xC3, ("monitorexit", JvmOperands::None)),
+        (0xC4, ("wide", JvmOperands::Wide)),
+        (0xC5, ("multianewarray", JvmOperands::MultiANewArray)),
+        (0xC6, ("ifnull", JvmOperands::Branch)),
+        (0xC7, ("ifnonnull", JvmOperands::Branch)),
+        (0xC8, ("goto_w", JvmOperands::BranchWide)),
+        (0xC9, ("jsr_w", JvmOperands::BranchWide)),
+        (0xCA, ("breakpoint", JvmOperands::None)),
+        (0xFE, ("impdep1", JvmOperands::None)),
+        (0xFF, ("impdep2", JvmOperands::None)),
+    ])
+}
+
+/// Element type of `newarray`
+pub fn array_type_name(atype: u8) -> Option<&'static str> {
+    match atype {
+        4 => Some("boolean"),
+        5 => Some("char"),
+        6 => Some("float"),
+        7 => Some("double"),
+        8 => Some("byte"),
+        9 => Some("short"),
+        10 => Some("int"),
+        11 => Some("long"),
+        _ => None,
+    }
+}
@@ -0,0 +1 @@
+pub mod jvm_opcodes_instruction;
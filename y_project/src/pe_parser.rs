@@ -0,0 +1,1962 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::binary_file::{
+    Architecture, BinaryExport, BinaryFile, BinaryImport, BinarySection, BinarySymbol, Bitness,
+    Endianness, FileDetails, Format, SymbolKind,
+};
+use crate::error::ParseError;
+use crate::pe_structure::COFFHeader;
+use crate::pe_structure::COFFObject;
+use crate::pe_structure::DOSHeader;
+use crate::pe_structure::DataDirectory;
+use crate::pe_structure::DataDirectoryEntry;
+use crate::pe_structure::ExportDirectory;
+use crate::pe_structure::ExportFunction;
+use crate::pe_structure::ExportTable;
+use crate::pe_structure::ImportDescriptor;
+use crate::pe_structure::ImportFunction;
+use crate::pe_structure::ImportLibraries;
+use crate::pe_structure::ImportLibrary;
+use crate::pe_structure::ImportObjectHeader;
+use crate::pe_structure::OptionalHeader;
+use crate::pe_structure::PEFile;
+use crate::pe_structure::ResourceId;
+use crate::pe_structure::RessourceDataEntry;
+use crate::pe_structure::RessourceDir;
+use crate::pe_structure::RessourceDirEntries;
+use crate::pe_structure::RsrcData;
+use crate::pe_structure::RsrcDataList;
+use crate::pe_structure::Section;
+use crate::pe_structure::SectionData;
+use crate::pe_structure::SectionTable;
+use crate::pe_structure::SectionsData;
+use crate::pe_structure::StringTable;
+use crate::pe_structure::Symbol;
+use crate::pe_structure::SymbolTable;
+use crate::pe_structure::TEFile;
+use crate::pe_structure::TEHeader;
+use crate::pe_structure::TextData;
+use crate::pe_structure::UnknownSection;
+use crate::pe_structure::UnknownSections;
+use crate::util::{
+    get_bytes, get_header, le_to_u16, le_to_u32, le_to_u64, le_to_usize, match_codepage,
+    read_c_string,
+};
+
+const COFF_HEADER_SIZE: usize = 24;
+/// COFF header without the PE signature, as found at the start of object files
+const COFF_FILE_HEADER_SIZE: usize = 20;
+const TE_HEADER_SIZE: usize = 40;
+const IMPORT_OBJECT_HEADER_SIZE: usize = 20;
+const SECTION_HEADER_SIZE: usize = 40;
+const SYMBOL_SIZE: usize = 18;
+const IMPORT_DESCRIPTOR_SIZE: usize = 20;
+const EXPORT_DIRECTORY_SIZE: usize = 40;
+const RESOURCE_DIRECTORY_SIZE: usize = 16;
+const RESOURCE_ENTRY_SIZE: usize = 8;
+const RESOURCE_DATA_ENTRY_SIZE: usize = 16;
+/// Set in the name half of an entry for names, in the offset half for subdirectories
+const RESOURCE_HIGH_BIT: usize = 0x8000_0000;
+/// Type, name and language
+const RESOURCE_TREE_DEPTH: usize = 3;
+const DOS_HEADER_SIZE: usize = 64;
+/// Fixed part of the PE32 and PE32+ optional headers, without the data directories
+const PE32_OPTIONAL_HEADER_SIZE: usize = 96;
+const PE32_PLUS_OPTIONAL_HEADER_SIZE: usize = 112;
+const DATA_DIRECTORY_ENTRY_SIZE: usize = 8;
+const DATA_DIRECTORY_COUNT: usize = 16;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+const IMAGE_SYM_CLASS_EXTERNAL: u8 = 2;
+const IMPORT_OBJECT_ORDINAL: u16 = 0;
+const IMPORT_OBJECT_NAME: u16 = 1;
+const IMPORT_OBJECT_NAME_NO_PREFIX: u16 = 2;
+const IMPORT_OBJECT_NAME_UNDECORATE: u16 = 3;
+const IMPORT_OBJECT_CODE: u16 = 0;
+
+pub fn extract_dos_header(bytes: &[u8]) -> Result<DOSHeader<'_>, ParseError> {
+    let bytes = get_header(bytes, 0, DOS_HEADER_SIZE, "DOS header")?;
+    if &bytes[0..2] != b"MZ" {
+        return Err(ParseError::BadMagic {
+            format: "DOS",
+            found: bytes[0..2].to_vec(),
+        });
+    }
+    Ok(DOSHeader {
+        magic: &bytes[0..2],
+        extra_bytes: &bytes[2..4],
+        pages: &bytes[4..6],
+        entries_relocation_table: &bytes[6..8],
+        header_size: &bytes[8..10],
+        min_alloc: &bytes[10..12],
+        max_alloc: &bytes[12..14],
+        initial_ss: &bytes[14..16],
+        initial_sp: &bytes[16..18],
+        checksum: &bytes[18..20],
+        initial_ip: &bytes[20..22],
+        initial_cs: &bytes[22..24],
+        reloc_table_address: &bytes[24..26],
+        overlay: &bytes[26..28],
+        pe_offset: le_to_usize(&bytes[60..64]),
+    })
+}
+
+pub fn extract_dos_stub(bytes: &[u8], pe_offset: usize) -> Result<&[u8], ParseError> {
+    get_bytes(
+        bytes,
+        DOS_HEADER_SIZE,
+        pe_offset.saturating_sub(DOS_HEADER_SIZE),
+    )
+}
+
+pub fn extract_coff_header(bytes: &[u8], pe_offset: usize) -> Result<COFFHeader<'_>, ParseError> {
+    let bytes = get_header(bytes, pe_offset, COFF_HEADER_SIZE, "COFF header")?;
+    if &bytes[0..4] != b"PE\0\0" {
+        return Err(ParseError::BadMagic {
+            format: "PE",
+            found: bytes[0..4].to_vec(),
+        });
+    }
+    Ok(coff_file_header(&bytes[0..4], &bytes[4..]))
+}
+
+/// Object files start directly with the COFF header, there is no PE signature
+pub fn extract_object_coff_header(bytes: &[u8]) -> Result<COFFHeader<'_>, ParseError> {
+    let bytes = get_header(bytes, 0, COFF_FILE_HEADER_SIZE, "COFF header")?;
+    Ok(coff_file_header(&bytes[0..0], bytes))
+}
+
+fn coff_file_header<'a>(magic: &'a [u8], bytes: &'a [u8]) -> COFFHeader<'a> {
+    COFFHeader {
+        magic,
+        machine: &bytes[0..2],
+        section_count: le_to_usize(&bytes[2..4]),
+        timestamp: &bytes[4..8],
+        symbol_table_pointer: le_to_usize(&bytes[8..12]),
+        symbol_count: le_to_usize(&bytes[12..16]),
+        optional_header_size: le_to_usize(&bytes[16..18]),
+        characteristics: &bytes[18..20],
+    }
+}
+
+pub fn extract_te_header(bytes: &[u8]) -> Result<TEHeader<'_>, ParseError> {
+    let bytes = get_header(bytes, 0, TE_HEADER_SIZE, "TE header")?;
+    if &bytes[0..2] != b"VZ" {
+        return Err(ParseError::BadMagic {
+            format: "TE",
+            found: bytes[0..2].to_vec(),
+        });
+    }
+    Ok(TEHeader {
+        magic: &bytes[0..2],
+        machine: &bytes[2..4],
+        section_count: bytes[4] as usize,
+        subsystem: bytes[5],
+        stripped_size: le_to_usize(&bytes[6..8]),
+        entry_point_address: le_to_usize(&bytes[8..12]),
+        base_of_code: &bytes[12..16],
+        image_base: le_to_u64(&bytes[16..24]),
+        base_relocation_table: DataDirectoryEntry {
+            virtual_address: le_to_usize(&bytes[24..28]),
+            size: le_to_usize(&bytes[28..32]),
+            file_offset: None,
+        },
+        debug: DataDirectoryEntry {
+            virtual_address: le_to_usize(&bytes[32..36]),
+            size: le_to_usize(&bytes[36..40]),
+            file_offset: None,
+        },
+    })
+}
+
+pub fn extract_opt_header(
+    bytes: &[u8],
+    pe_offset: usize,
+    coff_header: &COFFHeader,
+) -> Result<OptionalHeader, ParseError> {
+    let optional_header_offset = pe_offset + COFF_HEADER_SIZE;
+    let magic = le_to_u16(get_header(
+        bytes,
+        optional_header_offset,
+        2,
+        "optional header",
+    )?);
+    let pe32_plus = magic == PE32_PLUS_MAGIC;
+    let header_size = if pe32_plus {
+        PE32_PLUS_OPTIONAL_HEADER_SIZE
+    } else {
+        PE32_OPTIONAL_HEADER_SIZE
+    };
+    let header = get_header(
+        bytes,
+        optional_header_offset,
+        header_size,
+        "optional header",
+    )?;
+
+    // PE32+ drops BaseOfData to make room for a 64-bit ImageBase
+    let (base_of_data, image_base) = if pe32_plus {
+        (None, le_to_u64(&header[24..32]))
+    } else {
+        (
+            Some(le_to_u32(&header[24..28])),
+            le_to_u32(&header[28..32]) as u64,
+        )
+    };
+    // the stack and heap sizes are 64 bits wide as well, every later field moves
+    let size_width = if pe32_plus { 8 } else { 4 };
+    let size = |index: usize| {
+        let start = 72 + index * size_width;
+        if pe32_plus {
+            le_to_u64(&header[start..start + 8])
+        } else {
+            le_to_u32(&header[start..start + 4]) as u64
+        }
+    };
+    let loader_flags_offset = 72 + 4 * size_width;
+    let count_offset = loader_flags_offset + 4;
+    let number_of_rva_and_sizes = le_to_u32(&header[count_offset..count_offset + 4]);
+    let data_directory = extract_data_directory(
+        bytes,
+        optional_header_offset + header_size,
+        number_of_rva_and_sizes as usize,
+        coff_header.optional_header_size.saturating_sub(header_size),
+    )?;
+
+    Ok(OptionalHeader {
+        magic,
+        major_linker_version: header[2],
+        minor_linker_version: header[3],
+        code_size: le_to_usize(&header[4..8]),
+        initialized_data_size: le_to_u32(&header[8..12]),
+        uninitialized_data_size: le_to_u32(&header[12..16]),
+        entry_point_address: le_to_usize(&header[16..20]),
+        base_of_code: le_to_u32(&header[20..24]),
+        base_of_data,
+        image_base,
+        section_alignment: le_to_u32(&header[32..36]),
+        file_alignment: le_to_u32(&header[36..40]),
+        major_os_version: le_to_u16(&header[40..42]),
+        minor_os_version: le_to_u16(&header[42..44]),
+        major_image_version: le_to_u16(&header[44..46]),
+        minor_image_version: le_to_u16(&header[46..48]),
+        major_subsystem_version: le_to_u16(&header[48..50]),
+        minor_subsystem_version: le_to_u16(&header[50..52]),
+        win32_version_value: le_to_u32(&header[52..56]),
+        image_size: le_to_u32(&header[56..60]),
+        headers_size: le_to_u32(&header[60..64]),
+        checksum: le_to_u32(&header[64..68]),
+        subsystem: le_to_u16(&header[68..70]),
+        dll_characteristics: le_to_u16(&header[70..72]),
+        stack_reserve_size: size(0),
+        stack_commit_size: size(1),
+        heap_reserve_size: size(2),
+        heap_commit_size: size(3),
+        loader_flags: le_to_u32(&header[loader_flags_offset..count_offset]),
+        number_of_rva_and_sizes,
+        data_directory,
+    })
+}
+
+/// Decodes the `count` data directories at `offset`, as many as fit in `available` bytes of
+/// optional header. The missing ones are left empty.
+fn extract_data_directory(
+    bytes: &[u8],
+    offset: usize,
+    count: usize,
+    available: usize,
+) -> Result<DataDirectory, ParseError> {
+    let count = count
+        .min(DATA_DIRECTORY_COUNT)
+        .min(available / DATA_DIRECTORY_ENTRY_SIZE);
+    let mut entries = Vec::new();
+    for index in 0..count {
+        let entry = get_header(
+            bytes,
+            offset + index * DATA_DIRECTORY_ENTRY_SIZE,
+            DATA_DIRECTORY_ENTRY_SIZE,
+            "data directory",
+        )?;
+        entries.push(DataDirectoryEntry {
+            virtual_address: le_to_usize(&entry[0..4]),
+            size: le_to_usize(&entry[4..8]),
+            file_offset: None,
+        });
+    }
+    let mut entries = entries.into_iter();
+    let mut next = || entries.next().unwrap_or_default();
+    Ok(DataDirectory {
+        export_table: next(),
+        import_table: next(),
+        resource_table: next(),
+        exception_table: next(),
+        certificate_table: next(),
+        base_relocation_table: next(),
+        debug: next(),
+        architecture: next(),
+        global_ptr: next(),
+        tls_table: next(),
+        load_config_table: next(),
+        bound_import: next(),
+        iat: next(),
+        delay_import_descriptor: next(),
+        clr_runtime_header: next(),
+        reserved: next(),
+    })
+}
+
+/// Fills the file offsets of the data directories from the section table
+pub fn resolve_data_directory(data_directory: &mut DataDirectory, section_table: &SectionTable) {
+    let entries = [
+        &mut data_directory.export_table,
+        &mut data_directory.import_table,
+        &mut data_directory.resource_table,
+        &mut data_directory.exception_table,
+        &mut data_directory.base_relocation_table,
+        &mut data_directory.debug,
+        &mut data_directory.architecture,
+        &mut data_directory.global_ptr,
+        &mut data_directory.tls_table,
+        &mut data_directory.load_config_table,
+        &mut data_directory.bound_import,
+        &mut data_directory.iat,
+        &mut data_directory.delay_import_descriptor,
+        &mut data_directory.clr_runtime_header,
+    ];
+    for entry in entries {
+        resolve_data_directory_entry(entry, section_table);
+    }
+    // the certificates are not loaded, their "RVA" already is a file offset
+    let certificate_table = &mut data_directory.certificate_table;
+    if certificate_table.virtual_address != 0 {
+        certificate_table.file_offset = Some(certificate_table.virtual_address);
+    }
+}
+
+fn resolve_data_directory_entry(entry: &mut DataDirectoryEntry, section_table: &SectionTable) {
+    if entry.virtual_address != 0 {
+        entry.file_offset = rva_to_offset(section_table, entry.virtual_address);
+    }
+}
+
+pub fn extract_string_table<'a>(
+    bytes: &'a [u8],
+    coff_header: &COFFHeader,
+) -> Result<StringTable<'a>, ParseError> {
+    let mut string_table = StringTable {
+        length: 0,
+        strings: Vec::new(),
+        data: &[],
+    };
+    if coff_header.symbol_table_pointer == 0 {
+        return Ok(string_table);
+    }
+
+    // the string table directly follows the symbol table
+    let string_table_offset =
+        coff_header.symbol_table_pointer + (SYMBOL_SIZE * coff_header.symbol_count);
+    string_table.length = le_to_usize(get_bytes(bytes, string_table_offset, 4)?);
+    if string_table.length <= 4 {
+        return Ok(string_table);
+    }
+
+    string_table.data = get_bytes(bytes, string_table_offset + 4, string_table.length - 4)?;
+    string_table.strings = string_table
+        .data
+        .split(|&byte| byte == 0)
+        .map(|string| String::from_utf8_lossy(string).to_string())
+        .collect();
+    Ok(string_table)
+}
+
+/// Offsets into the string table count its 4 bytes length field
+fn string_at_offset(string_table: &StringTable, offset: usize) -> Result<String, ParseError> {
+    match offset.checked_sub(4) {
+        Some(index) if index < string_table.data.len() => read_c_string(string_table.data, index),
+        _ => Err(ParseError::InvalidStringIndex {
+            table: "COFF string table",
+            index: offset,
+        }),
+    }
+}
+
+pub fn extract_symbol_table<'a>(
+    bytes: &'a [u8],
+    coff_header: &COFFHeader,
+    string_table: &StringTable,
+) -> Result<SymbolTable<'a>, ParseError> {
+    let mut symbol_table = SymbolTable {
+        symbols: Vec::new(),
+    };
+    if coff_header.symbol_table_pointer == 0 {
+        return Ok(symbol_table);
+    }
+
+    let mut index = 0;
+    while index < coff_header.symbol_count {
+        let bytes = get_bytes(
+            bytes,
+            coff_header.symbol_table_pointer + index * SYMBOL_SIZE,
+            SYMBOL_SIZE,
+        )?;
+        let raw_name = &bytes[0..8];
+        // a name starting by 4 zero bytes is an offset into the string table
+        let name = if le_to_u32(&raw_name[0..4]) == 0 {
+            string_at_offset(string_table, le_to_usize(&raw_name[4..8]))?
+        } else {
+            String::from_utf8_lossy(raw_name)
+                .trim_end_matches('\0')
+                .to_string()
+        };
+        let number_aux_symbols = &bytes[17..18];
+        symbol_table.symbols.push(Symbol {
+            name,
+            value: &bytes[8..12],
+            section_number: &bytes[12..14],
+            data_type: &bytes[14..16],
+            storage_class: &bytes[16..17],
+            number_aux_symbols,
+        });
+        // auxiliary records are not symbols on their own
+        index += 1 + number_aux_symbols[0] as usize;
+    }
+    Ok(symbol_table)
+}
+
+pub fn extract_section_table<'a>(
+    bytes: &'a [u8],
+    pe_offset: usize,
+    coff_header: &COFFHeader,
+) -> Result<SectionTable<'a>, ParseError> {
+    extract_section_headers(
+        bytes,
+        pe_offset + COFF_HEADER_SIZE + coff_header.optional_header_size,
+        coff_header.section_count,
+        0,
+    )
+}
+
+/// `removed_size` bytes were stripped from the start of the file (TE images), the file
+/// offsets of the section headers still count them
+fn extract_section_headers(
+    bytes: &[u8],
+    section_table_offset: usize,
+    section_count: usize,
+    removed_size: usize,
+) -> Result<SectionTable<'_>, ParseError> {
+    let section_headers = get_header(
+        bytes,
+        section_table_offset,
+        section_count * SECTION_HEADER_SIZE,
+        "section table",
+    )?;
+    let mut section_table = SectionTable {
+        sections: Vec::new(),
+    };
+
+    for header in section_headers.chunks_exact(SECTION_HEADER_SIZE) {
+        let raw_data_size = le_to_usize(&header[16..20]);
+        let mut ptr_to_raw_data = le_to_usize(&header[20..24]);
+        // uninitialized data (.bss of object files) has a size but no file data
+        let raw_data = if ptr_to_raw_data == 0 {
+            &bytes[0..0]
+        } else {
+            ptr_to_raw_data =
+                ptr_to_raw_data
+                    .checked_sub(removed_size)
+                    .ok_or(ParseError::OutOfRange {
+                        offset: ptr_to_raw_data,
+                        size: raw_data_size,
+                        file_size: bytes.len(),
+                    })?;
+            get_bytes(bytes, ptr_to_raw_data, raw_data_size)?
+        };
+        section_table.sections.push(Section {
+            name: String::from_utf8_lossy(&header[0..8])
+                .trim_end_matches('\0')
+                .to_string(),
+            virtual_size: le_to_usize(&header[8..12]),
+            virtual_address: le_to_usize(&header[12..16]),
+            raw_data_size,
+            ptr_to_raw_data,
+            ptr_to_relocations: le_to_usize(&header[24..28]),
+            ptr_to_linenumbers: le_to_usize(&header[28..32]),
+            number_of_relocations: le_to_u16(&header[32..34]),
+            number_of_linenumbers: le_to_u16(&header[34..36]),
+            characteristics: le_to_u32(&header[36..40]),
+            raw_data,
+        });
+    }
+    Ok(section_table)
+}
+
+/// Long section names are stored as "/<offset>" into the string table
+pub fn replace_section_names(
+    string_table: &StringTable,
+    section_table: &mut SectionTable,
+) -> Result<(), ParseError> {
+    for section in section_table.sections.iter_mut() {
+        if let Some(offset) = section.name.strip_prefix('/') {
+            if let Ok(offset) = offset.parse() {
+                section.name = string_at_offset(string_table, offset)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// File offset of an RVA. Sections are zero filled past their raw data, an RVA in that
+/// tail has no bytes in the file.
+pub fn rva_to_offset(section_table: &SectionTable, rva: usize) -> Option<usize> {
+    let (section, offset) = section_table.sections.iter().find_map(|section| {
+        let offset = rva.checked_sub(section.virtual_address)?;
+        // some linkers leave VirtualSize at zero, the section then is its raw data
+        let mapped_size = match section.virtual_size {
+            0 => section.raw_data_size,
+            size => size,
+        };
+        (offset < mapped_size).then_some((section, offset))
+    })?;
+    (offset < section.raw_data_size).then(|| section.ptr_to_raw_data + offset)
+}
+
+/// Walks the import descriptors at the RVA of the import data directory, wherever the
+/// linker put them (.idata, .rdata...). Thunks are 64 bits wide in PE32+ images.
+pub fn extract_import_libraries(
+    bytes: &[u8],
+    section_table: &SectionTable,
+    import_directory_rva: usize,
+    pe32_plus: bool,
+) -> Result<ImportLibraries, ParseError> {
+    let mut import_libraries = ImportLibraries {
+        libraries: Vec::new(),
+    };
+    if import_directory_rva == 0 {
+        return Ok(import_libraries);
+    }
+    let (thunk_size, ordinal_flag) = if pe32_plus {
+        (8, 1u64 << 63)
+    } else {
+        (4, 1u64 << 31)
+    };
+
+    let mut offset = rva_to_file_offset(
+        bytes,
+        section_table,
+        import_directory_rva,
+        IMPORT_DESCRIPTOR_SIZE,
+    )?;
+    loop {
+        let raw_descriptor = get_bytes(bytes, offset, IMPORT_DESCRIPTOR_SIZE)?;
+        let descriptor = ImportDescriptor {
+            original_first_thunk: le_to_usize(&raw_descriptor[0..4]),
+            time_date_stamp: le_to_usize(&raw_descriptor[4..8]),
+            forwarder_chain: le_to_usize(&raw_descriptor[8..12]),
+            name_rva: le_to_usize(&raw_descriptor[12..16]),
+            first_thunk: le_to_usize(&raw_descriptor[16..20]),
+        };
+        if descriptor.name_rva == 0 && descriptor.first_thunk == 0 {
+            break;
+        }
+
+        let name_offset = rva_to_file_offset(bytes, section_table, descriptor.name_rva, 1)?;
+        let name = read_c_string(bytes, name_offset)?;
+
+        // the import name table may be missing, the IAT holds the same thunks on disk
+        let thunk_rva = if descriptor.original_first_thunk != 0 {
+            descriptor.original_first_thunk
+        } else {
+            descriptor.first_thunk
+        };
+        let mut functions = Vec::new();
+        if let Some(mut thunk_offset) = rva_to_offset(section_table, thunk_rva) {
+            let mut iat_rva = descriptor.first_thunk;
+            loop {
+                let raw_thunk = get_bytes(bytes, thunk_offset, thunk_size)?;
+                let thunk = if pe32_plus {
+                    le_to_u64(raw_thunk)
+                } else {
+                    le_to_u32(raw_thunk) as u64
+                };
+                if thunk == 0 {
+                    break;
+                }
+                if thunk & ordinal_flag != 0 {
+                    functions.push(ImportFunction {
+                        name: None,
+                        hint: 0,
+                        ordinal: Some(thunk as u16),
+                        iat_rva,
+                    });
+                } else if let Some(hint_offset) =
+                    rva_to_offset(section_table, (thunk & 0x7FFF_FFFF) as usize)
+                {
+                    functions.push(ImportFunction {
+                        name: Some(read_c_string(bytes, hint_offset + 2)?),
+                        hint: le_to_u16(get_bytes(bytes, hint_offset, 2)?),
+                        ordinal: None,
+                        iat_rva,
+                    });
+                }
+                thunk_offset += thunk_size;
+                iat_rva += thunk_size;
+            }
+        }
+
+        import_libraries.libraries.push(ImportLibrary {
+            name,
+            descriptor,
+            functions,
+        });
+        offset += IMPORT_DESCRIPTOR_SIZE;
+    }
+    Ok(import_libraries)
+}
+
+/// File offset of `size` bytes at `rva`, an error when no section maps them
+fn rva_to_file_offset(
+    bytes: &[u8],
+    section_table: &SectionTable,
+    rva: usize,
+    size: usize,
+) -> Result<usize, ParseError> {
+    rva_to_offset(section_table, rva).ok_or(ParseError::OutOfRange {
+        offset: rva,
+        size,
+        file_size: bytes.len(),
+    })
+}
+
+/// Decodes the export directory: the address table indexed by ordinal, the name pointer
+/// table and the ordinal table giving each name its entry. Entries pointing inside the
+/// export directory itself are forwarders (`NTDLL.RtlAllocateHeap`) rather than code.
+pub fn extract_export_table(
+    bytes: &[u8],
+    section_table: &SectionTable,
+    export_directory: &DataDirectoryEntry,
+) -> Result<Option<ExportTable>, ParseError> {
+    if export_directory.virtual_address == 0 {
+        return Ok(None);
+    }
+    let offset = rva_to_file_offset(
+        bytes,
+        section_table,
+        export_directory.virtual_address,
+        EXPORT_DIRECTORY_SIZE,
+    )?;
+    let raw_directory = get_header(bytes, offset, EXPORT_DIRECTORY_SIZE, "export directory")?;
+    let directory = ExportDirectory {
+        characteristics: le_to_u32(&raw_directory[0..4]),
+        time_date_stamp: le_to_u32(&raw_directory[4..8]),
+        major_version: le_to_u16(&raw_directory[8..10]),
+        minor_version: le_to_u16(&raw_directory[10..12]),
+        name_rva: le_to_usize(&raw_directory[12..16]),
+        ordinal_base: le_to_u32(&raw_directory[16..20]),
+        address_table_entries: le_to_usize(&raw_directory[20..24]),
+        name_pointer_count: le_to_usize(&raw_directory[24..28]),
+        export_address_table_rva: le_to_usize(&raw_directory[28..32]),
+        name_pointer_rva: le_to_usize(&raw_directory[32..36]),
+        ordinal_table_rva: le_to_usize(&raw_directory[36..40]),
+    };
+    let dll_name = if directory.name_rva != 0 {
+        read_c_string(
+            bytes,
+            rva_to_file_offset(bytes, section_table, directory.name_rva, 1)?,
+        )?
+    } else {
+        String::new()
+    };
+
+    let table = |rva: usize, count: usize, entry_size: usize| -> Result<&[u8], ParseError> {
+        if count == 0 {
+            return Ok(&[]);
+        }
+        let size = count
+            .checked_mul(entry_size)
+            .ok_or(ParseError::InvalidSize {
+                structure: "export table",
+                offset: rva,
+                size: count,
+            })?;
+        get_bytes(
+            bytes,
+            rva_to_file_offset(bytes, section_table, rva, size)?,
+            size,
+        )
+    };
+    let addresses = table(
+        directory.export_address_table_rva,
+        directory.address_table_entries,
+        4,
+    )?;
+    let name_pointers = table(directory.name_pointer_rva, directory.name_pointer_count, 4)?;
+    let ordinals = table(directory.ordinal_table_rva, directory.name_pointer_count, 2)?;
+
+    // names of each address table entry, the ordinal table holds unbiased indexes
+    let mut names = vec![Vec::new(); directory.address_table_entries];
+    for (name_pointer, index) in name_pointers.chunks(4).zip(ordinals.chunks(2)) {
+        let name_offset = rva_to_file_offset(bytes, section_table, le_to_usize(name_pointer), 1)?;
+        if let Some(entry_names) = names.get_mut(le_to_u16(index) as usize) {
+            entry_names.push(read_c_string(bytes, name_offset)?);
+        }
+    }
+
+    let export_directory_end = export_directory.virtual_address + export_directory.size;
+    let mut functions = Vec::new();
+    for (index, (address, entry_names)) in addresses.chunks(4).zip(names).enumerate() {
+        let rva = le_to_usize(address);
+        if rva == 0 {
+            continue;
+        }
+        let ordinal = directory.ordinal_base.wrapping_add(index as u32);
+        let forwarder = if rva >= export_directory.virtual_address && rva < export_directory_end {
+            Some(read_c_string(
+                bytes,
+                rva_to_file_offset(bytes, section_table, rva, 1)?,
+            )?)
+        } else {
+            None
+        };
+        let rva = if forwarder.is_some() { 0 } else { rva };
+        if entry_names.is_empty() {
+            functions.push(ExportFunction {
+                name: None,
+                ordinal,
+                rva,
+                forwarder: forwarder.clone(),
+            });
+        }
+        for name in entry_names {
+            functions.push(ExportFunction {
+                name: Some(name),
+                ordinal,
+                rva,
+                forwarder: forwarder.clone(),
+            });
+        }
+    }
+
+    Ok(Some(ExportTable {
+        dll_name,
+        directory,
+        functions,
+    }))
+}
+
+/// Name of the predefined resource types (RT_*), custom types keep their name or ID
+pub fn resource_type_name(resource_type: &ResourceId) -> String {
+    let name = match resource_type {
+        ResourceId::Id(1) => "RT_CURSOR",
+        ResourceId::Id(2) => "RT_BITMAP",
+        ResourceId::Id(3) => "RT_ICON",
+        ResourceId::Id(4) => "RT_MENU",
+        ResourceId::Id(5) => "RT_DIALOG",
+        ResourceId::Id(6) => "RT_STRING",
+        ResourceId::Id(7) => "RT_FONTDIR",
+        ResourceId::Id(8) => "RT_FONT",
+        ResourceId::Id(9) => "RT_ACCELERATOR",
+        ResourceId::Id(10) => "RT_RCDATA",
+        ResourceId::Id(11) => "RT_MESSAGETABLE",
+        ResourceId::Id(12) => "RT_GROUP_CURSOR",
+        ResourceId::Id(14) => "RT_GROUP_ICON",
+        ResourceId::Id(16) => "RT_VERSION",
+        ResourceId::Id(17) => "RT_DLGINCLUDE",
+        ResourceId::Id(19) => "RT_PLUGPLAY",
+        ResourceId::Id(20) => "RT_VXD",
+        ResourceId::Id(21) => "RT_ANICURSOR",
+        ResourceId::Id(22) => "RT_ANIICON",
+        ResourceId::Id(23) => "RT_HTML",
+        ResourceId::Id(24) => "RT_MANIFEST",
+        _ => return resource_id_name(resource_type),
+    };
+    name.to_string()
+}
+
+pub fn resource_id_name(id: &ResourceId) -> String {
+    match id {
+        ResourceId::Name(name) => name.clone(),
+        ResourceId::Id(id) => id.to_string(),
+    }
+}
+
+/// Walks the resource tree at the RVA of the resource data directory, type, then name, then
+/// language. Offsets inside the tree are relative to its start.
+pub fn extract_resources<'a>(
+    bytes: &'a [u8],
+    section_table: &SectionTable,
+    resource_directory_rva: usize,
+) -> Result<RsrcDataList<'a>, ParseError> {
+    let mut resources = RsrcDataList { data: Vec::new() };
+    if resource_directory_rva == 0 {
+        return Ok(resources);
+    }
+    let tree_offset = rva_to_file_offset(
+        bytes,
+        section_table,
+        resource_directory_rva,
+        RESOURCE_DIRECTORY_SIZE,
+    )?;
+    extract_resource_directory(
+        bytes,
+        section_table,
+        tree_offset,
+        0,
+        &mut Vec::new(),
+        &mut HashSet::new(),
+        &mut resources,
+    )?;
+    Ok(resources)
+}
+
+/// Reads the named entries then the ID entries of a directory. The high bit of an entry
+/// tells a subdirectory from a data entry, the 31 other bits are its offset.
+fn extract_resource_directory<'a>(
+    bytes: &'a [u8],
+    section_table: &SectionTable,
+    tree_offset: usize,
+    directory_offset: usize,
+    path: &mut Vec<ResourceId>,
+    visited: &mut HashSet<usize>,
+    resources: &mut RsrcDataList<'a>,
+) -> Result<(), ParseError> {
+    // a directory listed twice, or its own subdirectory, is only walked once
+    if !visited.insert(directory_offset) {
+        return Ok(());
+    }
+    let offset = tree_offset + directory_offset;
+    let raw_directory = get_header(bytes, offset, RESOURCE_DIRECTORY_SIZE, "resource directory")?;
+    let directory = RessourceDir {
+        characteristics: &raw_directory[0..4],
+        time_date_stamp: &raw_directory[4..8],
+        major_version: &raw_directory[8..10],
+        minor_version: &raw_directory[10..12],
+        name_entries_number: le_to_usize(&raw_directory[12..14]),
+        id_entries_number: le_to_usize(&raw_directory[14..16]),
+    };
+
+    let entry_count = directory.name_entries_number + directory.id_entries_number;
+    for index in 0..entry_count {
+        let raw_entry = get_bytes(
+            bytes,
+            offset + RESOURCE_DIRECTORY_SIZE + index * RESOURCE_ENTRY_SIZE,
+            RESOURCE_ENTRY_SIZE,
+        )?;
+        let entry = RessourceDirEntries {
+            name_offset: le_to_usize(&raw_entry[0..4]),
+            data_entry_offset: &raw_entry[4..8],
+        };
+        let id = if entry.name_offset & RESOURCE_HIGH_BIT != 0 {
+            let name_offset = tree_offset + (entry.name_offset & !RESOURCE_HIGH_BIT);
+            ResourceId::Name(extract_resource_name(bytes, name_offset)?)
+        } else {
+            ResourceId::Id(entry.name_offset as u16)
+        };
+        let target = le_to_usize(entry.data_entry_offset);
+
+        path.push(id);
+        if target & RESOURCE_HIGH_BIT == 0 {
+            let data = extract_resource_data(bytes, section_table, tree_offset + target, path)?;
+            resources.data.push(data);
+        } else if path.len() < RESOURCE_TREE_DEPTH {
+            extract_resource_directory(
+                bytes,
+                section_table,
+                tree_offset,
+                target & !RESOURCE_HIGH_BIT,
+                path,
+                visited,
+                resources,
+            )?;
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+/// Length prefixed UTF-16 string naming a resource directory entry
+fn extract_resource_name(bytes: &[u8], offset: usize) -> Result<String, ParseError> {
+    let length = le_to_usize(get_bytes(bytes, offset, 2)?);
+    let units: Vec<u16> = get_bytes(bytes, offset + 2, length * 2)?
+        .chunks(2)
+        .map(le_to_u16)
+        .collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn extract_resource_data<'a>(
+    bytes: &'a [u8],
+    section_table: &SectionTable,
+    offset: usize,
+    path: &[ResourceId],
+) -> Result<RsrcData<'a>, ParseError> {
+    let raw_entry = get_header(
+        bytes,
+        offset,
+        RESOURCE_DATA_ENTRY_SIZE,
+        "resource data entry",
+    )?;
+    let data_entry = RessourceDataEntry {
+        data_rva: le_to_usize(&raw_entry[0..4]),
+        size: le_to_usize(&raw_entry[4..8]),
+        codepage: &raw_entry[8..12],
+        reserved: &raw_entry[12..16],
+    };
+    // unlike the tree, the data is addressed by RVA
+    let extracted_raw = if data_entry.size != 0 {
+        let data_offset =
+            rva_to_file_offset(bytes, section_table, data_entry.data_rva, data_entry.size)?;
+        get_bytes(bytes, data_offset, data_entry.size)?
+    } else {
+        &[]
+    };
+    let codepage = u16::try_from(le_to_u32(data_entry.codepage)).map_or("Unknown", match_codepage);
+    // a data entry above the language level belongs to a malformed tree
+    let level = |index: usize| path.get(index).cloned().unwrap_or(ResourceId::Id(0));
+    Ok(RsrcData {
+        resource_type: level(0),
+        name: level(1),
+        language: level(2),
+        data_entry,
+        extracted_raw,
+        codepage,
+    })
+}
+
+pub fn extract_section_datas<'a>(
+    section_table: &SectionTable<'a>,
+    sections_data: &mut SectionsData<'a>,
+) -> Result<(), ParseError> {
+    let mut unknown_sections = UnknownSections {
+        sections: Vec::new(),
+    };
+
+    for section in section_table.sections.iter() {
+        match section.name.as_str() {
+            ".text" => {
+                sections_data.sections.insert(
+                    section.name.clone(),
+                    SectionData::Text(TextData {
+                        extracted_code: section.raw_data,
+                    }),
+                );
+            }
+            //ToDo: Add common file sections name and extracts their data
+            _ => unknown_sections.sections.push(UnknownSection {
+                section_name: section.name.clone(),
+                extracted_raw: section.raw_data,
+            }),
+        }
+    }
+
+    sections_data.sections.insert(
+        String::from("unknown"),
+        SectionData::Unknown(unknown_sections),
+    );
+    Ok(())
+}
+
+fn coff_symbol_kind(symbol: &Symbol) -> SymbolKind {
+    match (le_to_u16(symbol.data_type) >> 4, symbol.storage_class[0]) {
+        // IMAGE_SYM_DTYPE_FUNCTION
+        (2, _) => SymbolKind::Function,
+        // IMAGE_SYM_CLASS_FILE
+        (_, 103) => SymbolKind::File,
+        // IMAGE_SYM_CLASS_SECTION
+        (_, 104) => SymbolKind::Section,
+        _ => SymbolKind::Other,
+    }
+}
+
+fn machine_architecture(machine: u16) -> Architecture {
+    match machine {
+        0x014C => Architecture::X86,
+        0x8664 => Architecture::X86_64,
+        0x01C0 | 0x01C2 | 0x01C4 => Architecture::Arm,
+        0xAA64 => Architecture::AArch64,
+        0x0166 | 0x0169 => Architecture::Mips,
+        0x01F0 | 0x01F1 => Architecture::PowerPC,
+        0x5032 | 0x5064 => Architecture::RiscV,
+        _ => Architecture::Unknown(machine as u32),
+    }
+}
+
+fn machine_bitness(machine: u16) -> Bitness {
+    match machine {
+        0x8664 | 0xAA64 | 0x5064 => Bitness::Bits64,
+        _ => Bitness::Bits32,
+    }
+}
+
+fn binary_sections<'a>(
+    section_table: &SectionTable<'a>,
+    image_base: u64,
+) -> Vec<BinarySection<'a>> {
+    section_table
+        .sections
+        .iter()
+        .map(|section| BinarySection {
+            name: section.name.clone(),
+            virtual_address: image_base.wrapping_add(section.virtual_address as u64),
+            virtual_size: section.virtual_size as u64,
+            file_offset: section.ptr_to_raw_data as u64,
+            file_size: section.raw_data_size as u64,
+            raw_data: section.raw_data,
+        })
+        .collect()
+}
+
+fn binary_symbols(
+    symbol_table: &SymbolTable,
+    section_table: &SectionTable,
+    image_base: u64,
+) -> Vec<BinarySymbol> {
+    symbol_table
+        .symbols
+        .iter()
+        .map(|symbol| {
+            // the value of a symbol defined in a section is relative to this section
+            let section_number = le_to_u16(symbol.section_number) as i16;
+            let section_address = if section_number > 0 {
+                section_table
+                    .sections
+                    .get(section_number as usize - 1)
+                    .map_or(0, |section| {
+                        image_base.wrapping_add(section.virtual_address as u64)
+                    })
+            } else {
+                0
+            };
+            BinarySymbol {
+                name: symbol.name.clone(),
+                demangled_name: None,
+                kind: coff_symbol_kind(symbol),
+                address: section_address.wrapping_add(le_to_u32(symbol.value) as u64),
+                size: 0,
+            }
+        })
+        .collect()
+}
+
+pub fn parse_pe(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let dos_header = extract_dos_header(bytes)?;
+    let dos_stub = extract_dos_stub(bytes, dos_header.pe_offset)?;
+    let coff_header = extract_coff_header(bytes, dos_header.pe_offset)?;
+    let mut opt_header = extract_opt_header(bytes, dos_header.pe_offset, &coff_header)?;
+    let string_table = extract_string_table(bytes, &coff_header)?;
+    let symbol_table = extract_symbol_table(bytes, &coff_header, &string_table)?;
+    let mut section_table = extract_section_table(bytes, dos_header.pe_offset, &coff_header)?;
+    replace_section_names(&string_table, &mut section_table)?;
+    resolve_data_directory(&mut opt_header.data_directory, &section_table);
+
+    let mut sections_data = SectionsData {
+        sections: HashMap::new(),
+    };
+    extract_section_datas(&section_table, &mut sections_data)?;
+
+    let pe32_plus = opt_header.magic == PE32_PLUS_MAGIC;
+    let import_libraries = extract_import_libraries(
+        bytes,
+        &section_table,
+        opt_header.data_directory.import_table.virtual_address,
+        pe32_plus,
+    )?;
+    let export_table = extract_export_table(
+        bytes,
+        &section_table,
+        &opt_header.data_directory.export_table,
+    )?;
+
+    let image_base = opt_header.image_base;
+    let bitness = if pe32_plus {
+        Bitness::Bits64
+    } else {
+        Bitness::Bits32
+    };
+
+    let sections = binary_sections(&section_table, image_base);
+    let symbols = binary_symbols(&symbol_table, &section_table, image_base);
+
+    let mut libraries = Vec::new();
+    let mut imports = Vec::new();
+    for library in import_libraries.libraries.iter() {
+        // a library can have several descriptors, one per import table the linker merged
+        if !libraries.contains(&library.name) {
+            libraries.push(library.name.clone());
+        }
+        for function in library.functions.iter() {
+            imports.push(BinaryImport {
+                library: library.name.clone(),
+                name: function
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("#{}", function.ordinal.unwrap_or_default())),
+                address: image_base.wrapping_add(function.iat_rva as u64),
+            });
+        }
+    }
+
+    let resources = extract_resources(
+        bytes,
+        &section_table,
+        opt_header.data_directory.resource_table.virtual_address,
+    )?;
+
+    let exports = export_table
+        .iter()
+        .flat_map(|export_table| export_table.functions.iter())
+        .map(|function| BinaryExport {
+            name: function
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("#{}", function.ordinal)),
+            // forwarded exports have no code in this image
+            address: if function.forwarder.is_some() {
+                0
+            } else {
+                image_base.wrapping_add(function.rva as u64)
+            },
+        })
+        .collect();
+
+    Ok(BinaryFile {
+        format: Format::Pe,
+        architecture: machine_architecture(le_to_u16(coff_header.machine)),
+        endianness: Endianness::Little,
+        bitness,
+        entry_point: image_base.wrapping_add(opt_header.entry_point_address as u64),
+        sections,
+        segments: Vec::new(),
+        symbols,
+        libraries,
+        imports,
+        exports,
+        details: FileDetails::Pe(PEFile {
+            mz_header: dos_header,
+            dos_stub,
+            pe_header: coff_header,
+            optional_header: opt_header,
+            symbol_table,
+            string_table,
+            section_table,
+            import_libraries,
+            export_table,
+            resources,
+        }),
+    })
+}
+
+pub fn parse_coff_object(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let coff_header = extract_object_coff_header(bytes)?;
+    let string_table = extract_string_table(bytes, &coff_header)?;
+    let symbol_table = extract_symbol_table(bytes, &coff_header, &string_table)?;
+    let mut section_table = extract_section_headers(
+        bytes,
+        COFF_FILE_HEADER_SIZE + coff_header.optional_header_size,
+        coff_header.section_count,
+        0,
+    )?;
+    replace_section_names(&string_table, &mut section_table)?;
+
+    // external symbols are either defined in a section or left for the linker to resolve,
+    // a value without section is a common symbol the linker allocates
+    let mut imports = Vec::new();
+    let mut exports = Vec::new();
+    for symbol in symbol_table.symbols.iter() {
+        if symbol.storage_class[0] != IMAGE_SYM_CLASS_EXTERNAL {
+            continue;
+        }
+        let section_number = le_to_u16(symbol.section_number) as i16;
+        if section_number > 0 {
+            exports.push(BinaryExport {
+                name: symbol.name.clone(),
+                address: le_to_u32(symbol.value) as u64,
+            });
+        } else if section_number == 0 && le_to_u32(symbol.value) == 0 {
+            imports.push(BinaryImport {
+                library: String::new(),
+                name: symbol.name.clone(),
+                address: 0,
+            });
+        }
+    }
+
+    // object files leave VirtualSize at zero, sections are only laid out by the linker
+    let mut sections = binary_sections(&section_table, 0);
+    for section in sections.iter_mut() {
+        section.virtual_size = section.file_size;
+    }
+
+    let machine = le_to_u16(coff_header.machine);
+    Ok(BinaryFile {
+        format: Format::Coff,
+        architecture: machine_architecture(machine),
+        endianness: Endianness::Little,
+        bitness: machine_bitness(machine),
+        entry_point: 0,
+        sections,
+        segments: Vec::new(),
+        symbols: binary_symbols(&symbol_table, &section_table, 0),
+        libraries: Vec::new(),
+        imports,
+        exports,
+        details: FileDetails::Coff(COFFObject {
+            coff_header,
+            symbol_table,
+            string_table,
+            section_table,
+        }),
+    })
+}
+
+pub fn parse_te(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let mut te_header = extract_te_header(bytes)?;
+    // the TE header takes the place of part of the stripped headers
+    let removed_size =
+        te_header
+            .stripped_size
+            .checked_sub(TE_HEADER_SIZE)
+            .ok_or(ParseError::InvalidSize {
+                structure: "TE stripped headers",
+                offset: 6,
+                size: te_header.stripped_size,
+            })?;
+    let section_table =
+        extract_section_headers(bytes, TE_HEADER_SIZE, te_header.section_count, removed_size)?;
+    resolve_data_directory_entry(&mut te_header.base_relocation_table, &section_table);
+    resolve_data_directory_entry(&mut te_header.debug, &section_table);
+
+    let machine = le_to_u16(te_header.machine);
+    Ok(BinaryFile {
+        format: Format::Te,
+        architecture: machine_architecture(machine),
+        endianness: Endianness::Little,
+        bitness: machine_bitness(machine),
+        entry_point: te_header
+            .image_base
+            .wrapping_add(te_header.entry_point_address as u64),
+        sections: binary_sections(&section_table, te_header.image_base),
+        segments: Vec::new(),
+        symbols: Vec::new(),
+        libraries: Vec::new(),
+        imports: Vec::new(),
+        exports: Vec::new(),
+        details: FileDetails::Te(TEFile {
+            te_header,
+            section_table,
+        }),
+    })
+}
+
+pub fn extract_import_object_header(bytes: &[u8]) -> Result<ImportObjectHeader, ParseError> {
+    let header = get_header(bytes, 0, IMPORT_OBJECT_HEADER_SIZE, "import object header")?;
+    if header[0..4] != [0x00, 0x00, 0xFF, 0xFF] {
+        return Err(ParseError::BadMagic {
+            format: "import object",
+            found: header[0..4].to_vec(),
+        });
+    }
+    let data_size = le_to_usize(&header[12..16]);
+    let data = get_bytes(bytes, IMPORT_OBJECT_HEADER_SIZE, data_size)?;
+    // symbol name, DLL name and optionally the exported name, NUL terminated
+    let mut strings = data
+        .split(|&byte| byte == 0)
+        .map(|string| String::from_utf8_lossy(string).to_string());
+    let type_bits = le_to_u16(&header[18..20]);
+    Ok(ImportObjectHeader {
+        version: le_to_u16(&header[4..6]),
+        machine: le_to_u16(&header[6..8]),
+        timestamp: le_to_u32(&header[8..12]),
+        data_size,
+        ordinal_or_hint: le_to_u16(&header[16..18]),
+        import_type: type_bits & 0x3,
+        name_type: (type_bits >> 2) & 0x7,
+        symbol_name: strings.next().unwrap_or_default(),
+        dll_name: strings.next().unwrap_or_default(),
+        export_name: strings.next().filter(|name| !name.is_empty()),
+    })
+}
+
+/// Name the loader looks up in the DLL
+fn import_object_name(header: &ImportObjectHeader) -> String {
+    // a single leading ?, @ or _ is the prefix
+    let without_prefix = || {
+        let name = header.symbol_name.as_str();
+        name.strip_prefix(['?', '@', '_']).unwrap_or(name)
+    };
+    match header.name_type {
+        IMPORT_OBJECT_ORDINAL => format!("#{}", header.ordinal_or_hint),
+        IMPORT_OBJECT_NAME => header.symbol_name.clone(),
+        IMPORT_OBJECT_NAME_NO_PREFIX => without_prefix().to_string(),
+        IMPORT_OBJECT_NAME_UNDECORATE => without_prefix()
+            .split('@')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        _ => header
+            .export_name
+            .clone()
+            .unwrap_or_else(|| header.symbol_name.clone()),
+    }
+}
+
+pub fn parse_import_object(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let header = extract_import_object_header(bytes)?;
+    // the linker resolves calls with the symbol, code imports also get a thunk
+    let mut exports = vec![BinaryExport {
+        name: format!("__imp_{}", header.symbol_name),
+        address: 0,
+    }];
+    if header.import_type == IMPORT_OBJECT_CODE {
+        exports.push(BinaryExport {
+            name: header.symbol_name.clone(),
+            address: 0,
+        });
+    }
+    let imports = vec![BinaryImport {
+        library: header.dll_name.clone(),
+        name: import_object_name(&header),
+        address: 0,
+    }];
+
+    Ok(BinaryFile {
+        format: Format::Coff,
+        architecture: machine_architecture(header.machine),
+        endianness: Endianness::Little,
+        bitness: machine_bitness(header.machine),
+        entry_point: 0,
+        sections: Vec::new(),
+        segments: Vec::new(),
+        symbols: Vec::new(),
+        libraries: vec![header.dll_name.clone()],
+        imports,
+        exports,
+        details: FileDetails::ImportObject(header),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_file(name: &str) -> Vec<u8> {
+        std::fs::read(format!(
+            "{}/../testExe/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    fn pe_details<'a>(file: &'a BinaryFile<'a>) -> &'a PEFile<'a> {
+        match &file.details {
+            FileDetails::Pe(pe) => pe,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn long_names_come_from_the_string_table() {
+        let mut bytes = test_file("Windows/32BitPEWindows.exe");
+        let names = |bytes: &[u8]| {
+            let file = parse_pe(bytes).unwrap();
+            let pe = pe_details(&file);
+            let sections: Vec<String> = pe
+                .section_table
+                .sections
+                .iter()
+                .map(|section| section.name.clone())
+                .collect();
+            let symbols: Vec<String> = pe
+                .symbol_table
+                .symbols
+                .iter()
+                .take(3)
+                .map(|symbol| symbol.name.clone())
+                .collect();
+            (sections, symbols)
+        };
+        let (sections, symbols) = names(&bytes);
+        assert_eq!(sections[3], ".eh_frame");
+        assert_eq!(sections[8..10], [".debug_aranges", ".debug_info"]);
+        assert_eq!(
+            symbols,
+            [
+                "__mingw32_init_mainargs",
+                "_mainCRTStartup",
+                "_WinMainCRTStartup"
+            ]
+        );
+
+        // an invalid UTF-8 byte only changes the name it belongs to
+        let pe_offset = le_to_usize(&bytes[0x3C..0x40]);
+        let symbol_table = le_to_usize(&bytes[pe_offset + 12..pe_offset + 16]);
+        let symbol_count = le_to_usize(&bytes[pe_offset + 16..pe_offset + 20]);
+        bytes[symbol_table + symbol_count * SYMBOL_SIZE + 5] = 0xFF;
+        let (patched_sections, patched_symbols) = names(&bytes);
+        assert_eq!(patched_sections[3], ".\u{FFFD}h_frame");
+        assert_eq!(patched_sections[8..], sections[8..]);
+        assert_eq!(patched_symbols, symbols);
+    }
+
+    #[test]
+    fn named_ordinal_only_and_forwarded_exports() {
+        let bytes = test_file("Windows/PE64WindowsExports.dll");
+        let file = parse_pe(&bytes).unwrap();
+        let export_table = pe_details(&file).export_table.as_ref().unwrap();
+        assert_eq!(export_table.dll_name, "PE64WindowsExports.dll");
+        assert_eq!(export_table.directory.ordinal_base, 10);
+
+        let functions: Vec<_> = export_table
+            .functions
+            .iter()
+            .map(|function| {
+                (
+                    function.ordinal,
+                    function.name.as_deref(),
+                    function.rva,
+                    function.forwarder.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                (10, Some("Alpha"), 0x1000, None),
+                (11, None, 0x1004, None),
+                (12, Some("HeapAlloc"), 0, Some("NTDLL.RtlAllocateHeap")),
+            ]
+        );
+
+        let exports: Vec<_> = file
+            .exports
+            .iter()
+            .map(|export| (export.name.as_str(), export.address))
+            .collect();
+        assert_eq!(
+            exports,
+            [
+                ("Alpha", 0x1_4000_1000),
+                ("#11", 0x1_4000_1004),
+                ("HeapAlloc", 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_by_name_and_by_ordinal() {
+        // the same imports in both images, thunks are 8 bytes wide in PE32+
+        for (name, image_base, thunk_size) in [
+            ("Windows/PE64WindowsImports.exe", 0x1_4000_0000u64, 8),
+            ("Windows/PE32WindowsImports.exe", 0x40_0000, 4),
+        ] {
+            let bytes = test_file(name);
+            let file = parse_pe(&bytes).unwrap();
+            let libraries = &pe_details(&file).import_libraries.libraries;
+            assert_eq!(file.libraries, ["KERNEL32.dll", "WS2_32.dll"]);
+
+            let kernel32 = &libraries[0].functions;
+            assert_eq!(kernel32[0].name.as_deref(), Some("ExitProcess"));
+            assert_eq!(kernel32[0].hint, 0x11);
+            assert_eq!(kernel32[1].name.as_deref(), Some("GetLastError"));
+            assert_eq!(kernel32[1].hint, 0x22);
+            assert_eq!(kernel32[1].iat_rva - kernel32[0].iat_rva, thunk_size);
+
+            let ws2_32 = &libraries[1].functions;
+            assert_eq!(ws2_32[0].name, None);
+            assert_eq!(ws2_32[0].ordinal, Some(23));
+            assert_eq!(ws2_32[1].name.as_deref(), Some("connect"));
+            assert_eq!(ws2_32[1].ordinal, None);
+
+            let imports: Vec<(&str, &str, u64)> = file
+                .imports
+                .iter()
+                .map(|import| {
+                    (
+                        import.library.as_str(),
+                        import.name.as_str(),
+                        import.address - image_base,
+                    )
+                })
+                .collect();
+            // the IATs follow the descriptors and the two import name tables
+            let iat = 0x2000 + 3 * 20 + 6 * thunk_size as u64;
+            let step = thunk_size as u64;
+            assert_eq!(
+                imports,
+                [
+                    ("KERNEL32.dll", "ExitProcess", iat),
+                    ("KERNEL32.dll", "GetLastError", iat + step),
+                    ("WS2_32.dll", "#23", iat + 3 * step),
+                    ("WS2_32.dll", "connect", iat + 4 * step),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn imports_without_a_name_table_are_read_from_the_iat() {
+        let functions = |bytes: &[u8]| {
+            let file = parse_pe(bytes).unwrap();
+            let library = &pe_details(&file).import_libraries.libraries[1];
+            let functions: Vec<(Option<String>, Option<u16>, usize)> = library
+                .functions
+                .iter()
+                .map(|function| (function.name.clone(), function.ordinal, function.iat_rva))
+                .collect();
+            (library.descriptor.original_first_thunk, functions)
+        };
+        let mut bytes = test_file("Windows/PE64WindowsImports.exe");
+        let (name_table, expected) = functions(&bytes);
+        assert_ne!(name_table, 0);
+
+        // OriginalFirstThunk of the WS2_32.dll descriptor, .rdata starts at file offset 0x400
+        bytes[0x400 + IMPORT_DESCRIPTOR_SIZE..0x400 + IMPORT_DESCRIPTOR_SIZE + 4].fill(0);
+        let (name_table, functions) = functions(&bytes);
+        assert_eq!(name_table, 0);
+        assert_eq!(functions, expected);
+    }
+
+    #[test]
+    fn libraries_with_several_descriptors_are_listed_once() {
+        let bytes = test_file("Windows/32BitPEWindows.exe");
+        let file = parse_pe(&bytes).unwrap();
+        let descriptors: Vec<&str> = pe_details(&file)
+            .import_libraries
+            .libraries
+            .iter()
+            .map(|library| library.name.as_str())
+            .collect();
+        assert_eq!(
+            descriptors,
+            [
+                "KERNEL32.dll",
+                "msvcrt.dll",
+                "msvcrt.dll",
+                "libgcc_s_dw2-1.dll",
+                "libstdc++-6.dll"
+            ]
+        );
+        assert_eq!(
+            file.libraries,
+            [
+                "KERNEL32.dll",
+                "msvcrt.dll",
+                "libgcc_s_dw2-1.dll",
+                "libstdc++-6.dll"
+            ]
+        );
+        // the imports of both descriptors are kept
+        assert!(file
+            .imports
+            .iter()
+            .any(|import| import.library == "msvcrt.dll" && import.name == "_strdup"));
+        assert!(file
+            .imports
+            .iter()
+            .any(|import| import.library == "msvcrt.dll" && import.name == "malloc"));
+    }
+
+    #[test]
+    fn data_directories_get_their_file_offsets() {
+        let mut bytes = test_file("Windows/32BitPEWindows.exe");
+        // PE32 data directories of the image at 0x80
+        let directories = 0x80 + 24 + 96;
+        let entries: [(u32, Option<usize>); 16] = [
+            (0x1010, Some(0x410)),
+            (0x8000, Some(0x4400)),
+            (0x5020, Some(0x3420)),
+            (0x6000, Some(0x3800)),
+            // the certificate table is a file offset, not an RVA
+            (0xAE00, Some(0xAE00)),
+            (0x9008, Some(0x4C08)),
+            (0xC100, Some(0x5300)),
+            (0, None),
+            // .bss has no raw data
+            (0x7010, None),
+            (0xA004, Some(0x4E04)),
+            (0xE010, Some(0x7010)),
+            // past the VirtualSize of .data, in the padding of its raw data
+            (0x4100, None),
+            (0x817C, Some(0x457C)),
+            (0xF000, Some(0x7200)),
+            (0x1_0030, Some(0x7430)),
+            // the reserved entry is never resolved
+            (0x1010, None),
+        ];
+        for (index, (rva, _)) in entries.iter().enumerate() {
+            let offset = directories + index * 8;
+            bytes[offset..offset + 4].copy_from_slice(&rva.to_le_bytes());
+            bytes[offset + 4..offset + 8].copy_from_slice(&8u32.to_le_bytes());
+        }
+        // parse_pe would also decode the export and resource directories
+        let coff_header = extract_coff_header(&bytes, 0x80).unwrap();
+        let mut optional_header = extract_opt_header(&bytes, 0x80, &coff_header).unwrap();
+        let section_table = extract_section_table(&bytes, 0x80, &coff_header).unwrap();
+        resolve_data_directory(&mut optional_header.data_directory, &section_table);
+        let data_directory = &optional_header.data_directory;
+        let resolved = [
+            &data_directory.export_table,
+            &data_directory.import_table,
+            &data_directory.resource_table,
+            &data_directory.exception_table,
+            &data_directory.certificate_table,
+            &data_directory.base_relocation_table,
+            &data_directory.debug,
+            &data_directory.architecture,
+            &data_directory.global_ptr,
+            &data_directory.tls_table,
+            &data_directory.load_config_table,
+            &data_directory.bound_import,
+            &data_directory.iat,
+            &data_directory.delay_import_descriptor,
+            &data_directory.clr_runtime_header,
+            &data_directory.reserved,
+        ];
+        for (index, (entry, (rva, file_offset))) in resolved.iter().zip(entries).enumerate() {
+            assert_eq!(entry.virtual_address, rva as usize, "entry {}", index);
+            assert_eq!(entry.size, 8, "entry {}", index);
+            assert_eq!(entry.file_offset, file_offset, "entry {}", index);
+        }
+    }
+
+    #[test]
+    fn rvas_past_the_raw_data_have_no_file_offset() {
+        let mut bytes = test_file("Windows/32BitPEWindows.exe");
+        // SizeOfRawData of .data, first section header at 0x178
+        let raw_data_size = 0x178 + 40 + 16;
+        bytes[raw_data_size..raw_data_size + 4].copy_from_slice(&0x10u32.to_le_bytes());
+        let file = parse_pe(&bytes).unwrap();
+        let section_table = &pe_details(&file).section_table;
+        assert_eq!(rva_to_offset(section_table, 0x400F), Some(0x320F));
+        // zero filled up to the VirtualSize, 0x1C
+        assert_eq!(rva_to_offset(section_table, 0x4010), None);
+        assert_eq!(rva_to_offset(section_table, 0x401B), None);
+        assert_eq!(rva_to_offset(section_table, 0x5000), Some(0x3400));
+        assert_eq!(rva_to_offset(section_table, 0xFFF), None);
+    }
+
+    #[test]
+    fn pe32_and_pe32_plus_optional_headers() {
+        let bytes = test_file("Windows/32BitPEWindows.exe");
+        let file = parse_pe(&bytes).unwrap();
+        let header = &pe_details(&file).optional_header;
+        assert_eq!(header.magic, 0x10B);
+        assert_eq!(file.bitness, Bitness::Bits32);
+        assert_eq!(header.base_of_data, Some(0x4000));
+        assert_eq!(header.image_base, 0x40_0000);
+        assert_eq!(header.stack_reserve_size, 0x20_0000);
+        assert_eq!(header.stack_commit_size, 0x1000);
+        assert_eq!(header.heap_reserve_size, 0x10_0000);
+        assert_eq!(header.heap_commit_size, 0x1000);
+        assert_eq!(header.loader_flags, 0);
+        assert_eq!(header.number_of_rva_and_sizes, 16);
+        assert_eq!(header.data_directory.import_table.virtual_address, 0x8000);
+
+        // sizes past 32 bits, which only PE32+ can hold
+        let mut bytes = test_file("Windows/PE64WindowsExports.dll");
+        let optional_header = 0x40 + COFF_HEADER_SIZE;
+        let sizes = [
+            0x1_0010_0000u64,
+            0x2_0000_1000,
+            0x3_0010_0000,
+            0x4_0000_1000,
+        ];
+        for (index, size) in sizes.iter().enumerate() {
+            let offset = optional_header + 72 + index * 8;
+            bytes[offset..offset + 8].copy_from_slice(&size.to_le_bytes());
+        }
+        bytes[optional_header + 104..optional_header + 108]
+            .copy_from_slice(&0xABCDu32.to_le_bytes());
+        let file = parse_pe(&bytes).unwrap();
+        let header = &pe_details(&file).optional_header;
+        assert_eq!(header.magic, PE32_PLUS_MAGIC);
+        assert_eq!(file.bitness, Bitness::Bits64);
+        assert_eq!(header.base_of_code, 0x1000);
+        assert_eq!(header.base_of_data, None);
+        assert_eq!(header.image_base, 0x1_4000_0000);
+        assert_eq!(
+            [
+                header.stack_reserve_size,
+                header.stack_commit_size,
+                header.heap_reserve_size,
+                header.heap_commit_size
+            ],
+            sizes
+        );
+        assert_eq!(header.loader_flags, 0xABCD);
+        assert_eq!(header.number_of_rva_and_sizes, 16);
+        // the data directories follow at 112
+        assert_ne!(header.data_directory.export_table.virtual_address, 0);
+        assert!(pe_details(&file).export_table.is_some());
+    }
+
+    /// Address arithmetic wraps in every parser, like the loaders do, instead of failing the
+    /// whole file or saturating
+    #[test]
+    fn addresses_at_the_top_of_the_address_space_wrap() {
+        let mut bytes = test_file("Windows/PE64WindowsExports.dll");
+        // ImageBase of the PE32+ optional header, after the PE signature and COFF header
+        let image_base_offset = le_to_usize(&bytes[0x3C..0x40]) + COFF_HEADER_SIZE + 24;
+        bytes[image_base_offset..image_base_offset + 8]
+            .copy_from_slice(&0xFFFF_FFFF_FFFF_F000u64.to_le_bytes());
+        let file = parse_pe(&bytes).unwrap();
+        assert_eq!(file.entry_point, 0);
+        assert_eq!(file.sections[0].virtual_address, 0);
+        assert_eq!(file.sections[1].virtual_address, 0x1000);
+        assert!(file.imports.iter().all(|import| import.address >= 0x1000));
+        assert_eq!(file.exports[0].address, 0);
+        assert_eq!(file.exports[1].address, 4);
+    }
+
+    #[test]
+    fn resources_are_named_by_type_name_and_language() {
+        let bytes = test_file("Windows/32BitPEWindowsWithRsrc.exe");
+        let file = parse_pe(&bytes).unwrap();
+        let resources = &pe_details(&file).resources.data;
+        assert_eq!(resources.len(), 1);
+        let resource = &resources[0];
+        assert_eq!(resource_type_name(&resource.resource_type), "RT_RCDATA");
+        assert_eq!(resource.name, ResourceId::Id(101));
+        assert_eq!(resource.language, ResourceId::Id(1033));
+        assert_eq!(resource.data_entry.size, 0xd);
+        assert_eq!(le_to_u16(resource.data_entry.codepage), 0);
+        assert_eq!(resource.codepage, "Unknown");
+        assert_eq!(resource.extracted_raw, b"Hello, World!");
+    }
+
+    #[test]
+    fn truncated_images_are_rejected() {
+        let bytes = test_file("Windows/PE64WindowsExports.dll");
+        for length in 0..bytes.len() {
+            assert!(parse_pe(&bytes[..length]).is_err(), "length {}", length);
+        }
+    }
+
+    #[test]
+    fn hostile_headers_are_rejected() {
+        let bytes = test_file("Windows/PE64WindowsExports.dll");
+        let pe_offset = le_to_usize(&bytes[0x3C..0x40]);
+        let patched = |offset: usize, value: &[u8]| {
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + value.len()].copy_from_slice(value);
+            parse_pe(&bytes).map(|_| ())
+        };
+        assert!(patched(0x3C, &0xFFFF_FFF0u32.to_le_bytes()).is_err());
+        assert!(matches!(
+            patched(pe_offset, b"PX\0\0"),
+            Err(ParseError::BadMagic { .. })
+        ));
+        // NumberOfSections, PointerToSymbolTable and NumberOfSymbols
+        assert!(patched(pe_offset + 6, &0xFFFFu16.to_le_bytes()).is_err());
+        assert!(patched(pe_offset + 12, &0x7FFF_FFF0u32.to_le_bytes()).is_err());
+        let mut symbols = 0x200u32.to_le_bytes().to_vec();
+        symbols.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        assert!(patched(pe_offset + 12, &symbols).is_err());
+        // SizeOfRawData of .rdata past the end of the file
+        let rdata = pe_offset + COFF_HEADER_SIZE + 240 + SECTION_HEADER_SIZE;
+        assert!(matches!(
+            patched(rdata + 16, &0x7FFF_0000u32.to_le_bytes()),
+            Err(ParseError::OutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn hostile_export_directories_are_rejected() {
+        let bytes = test_file("Windows/PE64WindowsExports.dll");
+        let file = parse_pe(&bytes).unwrap();
+        let pe = pe_details(&file);
+        let directory = rva_to_file_offset(
+            &bytes,
+            &pe.section_table,
+            pe.optional_header
+                .data_directory
+                .export_table
+                .virtual_address,
+            EXPORT_DIRECTORY_SIZE,
+        )
+        .unwrap();
+        let patched = |offset: usize, value: u32| {
+            let mut bytes = bytes.clone();
+            bytes[directory + offset..directory + offset + 4].copy_from_slice(&value.to_le_bytes());
+            parse_pe(&bytes).map(|_| ())
+        };
+        // NumberOfFunctions, NumberOfNames, the name pointer table and the DLL name
+        for offset in [20, 24] {
+            assert!(patched(offset, 0xFFFF_FFFF).is_err());
+            assert!(patched(offset, 0x1000).is_err());
+        }
+        assert!(patched(32, 0x7000_0000).is_err());
+        assert!(patched(12, 0xFFFF_FFFF).is_err());
+    }
+
+    #[test]
+    fn hostile_resource_trees_are_rejected() {
+        let bytes = test_file("Windows/32BitPEWindowsWithRsrc.exe");
+        let file = parse_pe(&bytes).unwrap();
+        let pe = pe_details(&file);
+        let tree = rva_to_file_offset(
+            &bytes,
+            &pe.section_table,
+            pe.optional_header
+                .data_directory
+                .resource_table
+                .virtual_address,
+            RESOURCE_DIRECTORY_SIZE,
+        )
+        .unwrap();
+        let first_entry = tree + RESOURCE_DIRECTORY_SIZE;
+        let patched = |offset: usize, value: u32| {
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            parse_pe(&bytes).map(|file| pe_details(&file).resources.data.len())
+        };
+        // the root directory as its own subdirectory is walked once
+        assert_eq!(patched(first_entry + 4, 0x8000_0000).unwrap(), 0);
+        // a name and a data entry out of the file
+        assert!(patched(first_entry, 0xFFFF_FFFF).is_err());
+        assert!(patched(first_entry + 4, 0x7FFF_FFF0).is_err());
+        // more entries than the section holds
+        assert!(patched(tree + 12, 0xFFFF_FFFF).is_err());
+    }
+
+    fn te_image(image_base: u64, entry_point: u32, section_address: u32) -> Vec<u8> {
+        let mut bytes = vec![0; TE_HEADER_SIZE + SECTION_HEADER_SIZE];
+        bytes[0..2].copy_from_slice(b"VZ");
+        bytes[2..4].copy_from_slice(&0x8664u16.to_le_bytes());
+        bytes[4] = 1;
+        bytes[6..8].copy_from_slice(&(TE_HEADER_SIZE as u16).to_le_bytes());
+        bytes[8..12].copy_from_slice(&entry_point.to_le_bytes());
+        bytes[16..24].copy_from_slice(&image_base.to_le_bytes());
+        let section = &mut bytes[TE_HEADER_SIZE..];
+        section[0..5].copy_from_slice(b".text");
+        section[8..12].copy_from_slice(&0x1000u32.to_le_bytes());
+        section[12..16].copy_from_slice(&section_address.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn te_sections_and_directories_skip_the_stripped_headers() {
+        let mut bytes = te_image(0x1_4000_0000, 0x1004, 0x1000);
+        // EFI application whose original headers took 0x200 bytes, .text was at 0x400
+        bytes[5] = 10;
+        bytes[6..8].copy_from_slice(&0x200u16.to_le_bytes());
+        bytes[32..36].copy_from_slice(&0x1008u32.to_le_bytes());
+        bytes[36..40].copy_from_slice(&0x1Cu32.to_le_bytes());
+        bytes[TE_HEADER_SIZE + 16..TE_HEADER_SIZE + 20].copy_from_slice(&0x30u32.to_le_bytes());
+        bytes[TE_HEADER_SIZE + 20..TE_HEADER_SIZE + 24].copy_from_slice(&0x400u32.to_le_bytes());
+        bytes.resize(0x228, 0);
+        bytes.extend((0..0x30).map(|byte| byte as u8));
+
+        let file = parse_te(&bytes).unwrap();
+        assert_eq!(file.format, Format::Te);
+        assert!(matches!(file.architecture, Architecture::X86_64));
+        assert_eq!(file.entry_point, 0x1_4000_1004);
+        assert_eq!(file.sections[0].name, ".text");
+        assert_eq!(file.sections[0].virtual_address, 0x1_4000_1000);
+        assert_eq!(file.sections[0].file_offset, 0x228);
+        assert_eq!(file.sections[0].raw_data, &bytes[0x228..0x258]);
+        let te_header = match &file.details {
+            FileDetails::Te(te) => &te.te_header,
+            _ => unreachable!(),
+        };
+        assert_eq!(te_header.subsystem, 10);
+        assert_eq!(te_header.stripped_size, 0x200);
+        assert_eq!(te_header.debug.size, 0x1C);
+        assert_eq!(te_header.debug.file_offset, Some(0x230));
+        assert_eq!(te_header.base_relocation_table.virtual_address, 0);
+        assert_eq!(te_header.base_relocation_table.file_offset, None);
+    }
+
+    #[test]
+    fn te_stripped_size_smaller_than_the_header_is_rejected() {
+        let mut bytes = te_image(0x1000, 0, 0);
+        bytes[6..8].copy_from_slice(&0u16.to_le_bytes());
+        assert!(matches!(
+            parse_te(&bytes),
+            Err(ParseError::InvalidSize { offset: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_te_headers_are_rejected() {
+        let bytes = te_image(0x1000, 0, 0);
+        assert!(matches!(
+            parse_te(&bytes[..TE_HEADER_SIZE - 1]),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+        assert!(parse_te(&bytes[..TE_HEADER_SIZE + 1]).is_err());
+    }
+
+    #[test]
+    fn hostile_coff_objects_are_rejected() {
+        let mut bytes = vec![0; COFF_FILE_HEADER_SIZE + 4];
+        bytes[0..2].copy_from_slice(&0x8664u16.to_le_bytes());
+        bytes[8..12].copy_from_slice(&(COFF_FILE_HEADER_SIZE as u32).to_le_bytes());
+        bytes[COFF_FILE_HEADER_SIZE..].copy_from_slice(&4u32.to_le_bytes());
+        assert!(parse_coff_object(&bytes).is_ok());
+        assert!(matches!(
+            parse_coff_object(&bytes[..COFF_FILE_HEADER_SIZE - 1]),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+
+        let patched = |offset: usize, value: u32| {
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            parse_coff_object(&bytes).map(|_| ())
+        };
+        // NumberOfSymbols, the string table size, then sections past the end
+        assert!(patched(12, 0xFFFF_FFFF).is_err());
+        assert!(matches!(
+            patched(COFF_FILE_HEADER_SIZE, 0xFFFF_FFFF),
+            Err(ParseError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            patched(2, 0xFFFF),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+    }
+
+    fn import_object(name_type: u16, symbol_name: &str) -> ImportObjectHeader {
+        ImportObjectHeader {
+            version: 0,
+            machine: 0x14C,
+            timestamp: 0,
+            data_size: 0,
+            ordinal_or_hint: 7,
+            import_type: IMPORT_OBJECT_CODE,
+            name_type,
+            symbol_name: symbol_name.to_string(),
+            dll_name: "user32.dll".to_string(),
+            export_name: None,
+        }
+    }
+
+    #[test]
+    fn import_object_names_lose_a_single_prefix_character() {
+        let name =
+            |name_type, symbol_name| import_object_name(&import_object(name_type, symbol_name));
+        assert_eq!(name(IMPORT_OBJECT_ORDINAL, "_Beep@8"), "#7");
+        assert_eq!(name(IMPORT_OBJECT_NAME, "_Beep@8"), "_Beep@8");
+        assert_eq!(
+            name(IMPORT_OBJECT_NAME_NO_PREFIX, "__imp_Beep"),
+            "_imp_Beep"
+        );
+        assert_eq!(name(IMPORT_OBJECT_NAME_NO_PREFIX, "??0Class"), "?0Class");
+        assert_eq!(name(IMPORT_OBJECT_NAME_UNDECORATE, "__Beep@8"), "_Beep");
+        assert_eq!(name(IMPORT_OBJECT_NAME_UNDECORATE, "Beep"), "Beep");
+    }
+
+    #[test]
+    fn import_object_data_is_bounded() {
+        let mut bytes = vec![0; IMPORT_OBJECT_HEADER_SIZE];
+        bytes[2..4].copy_from_slice(&[0xFF, 0xFF]);
+        bytes[12..16].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        assert!(matches!(
+            parse_import_object(&bytes),
+            Err(ParseError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            parse_import_object(&bytes[..IMPORT_OBJECT_HEADER_SIZE - 1]),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+    }
+}
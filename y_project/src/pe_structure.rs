@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct DOSHeader<'a> {
@@ -32,78 +34,150 @@ pub struct COFFHeader<'a> {
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
-pub struct DataDirectoryEntry<'a> {
-    pub virtual_address: &'a [u8],
-    pub size: &'a [u8],
+#[derive(Debug, Default)]
+pub struct DataDirectoryEntry {
+    /// 0 when the image has no such directory
+    pub virtual_address: usize,
+    pub size: usize,
+    /// Where the directory starts in the file, None when no section maps the RVA
+    pub file_offset: Option<usize>,
 }
 
+/// The 16 data directories, entries past NumberOfRvaAndSizes are left empty
 #[allow(dead_code)]
-#[derive(Debug)]
-pub struct DataDirectory<'a> {
-    pub export_table: DataDirectoryEntry<'a>,
-    pub import_table: DataDirectoryEntry<'a>,
-    pub resource_table: DataDirectoryEntry<'a>,
-    pub exception_table: DataDirectoryEntry<'a>,
-    pub certificate_table: DataDirectoryEntry<'a>,
-    pub base_relocation_table: DataDirectoryEntry<'a>,
-    pub debug: DataDirectoryEntry<'a>,
-    pub architecture: DataDirectoryEntry<'a>,
-    pub global_ptr: DataDirectoryEntry<'a>,
-    pub tls_table: DataDirectoryEntry<'a>,
-    pub load_config_table: DataDirectoryEntry<'a>,
-    pub bound_import: DataDirectoryEntry<'a>,
-    pub iat: DataDirectoryEntry<'a>,
-    pub delay_import_descriptor: DataDirectoryEntry<'a>,
-    pub clr_runtime_header: DataDirectoryEntry<'a>,
-    pub reserved: DataDirectoryEntry<'a>,
+#[derive(Debug, Default)]
+pub struct DataDirectory {
+    pub export_table: DataDirectoryEntry,
+    pub import_table: DataDirectoryEntry,
+    pub resource_table: DataDirectoryEntry,
+    pub exception_table: DataDirectoryEntry,
+    /// Not mapped in memory, its virtual address is a file offset
+    pub certificate_table: DataDirectoryEntry,
+    pub base_relocation_table: DataDirectoryEntry,
+    pub debug: DataDirectoryEntry,
+    pub architecture: DataDirectoryEntry,
+    pub global_ptr: DataDirectoryEntry,
+    pub tls_table: DataDirectoryEntry,
+    pub load_config_table: DataDirectoryEntry,
+    pub bound_import: DataDirectoryEntry,
+    pub iat: DataDirectoryEntry,
+    pub delay_import_descriptor: DataDirectoryEntry,
+    pub clr_runtime_header: DataDirectoryEntry,
+    pub reserved: DataDirectoryEntry,
 }
 
+/// PE32 and PE32+ optional header, the PE32+ layout has no BaseOfData and 64-bit image base,
+/// stack and heap sizes
 #[allow(dead_code)]
 #[derive(Debug)]
-pub struct OptionalHeader<'a> {
+pub struct OptionalHeader {
     //Standard COFF fields
-    pub magic: &'a [u8],
-    pub major_linker_version: &'a [u8],
-    pub minor_linker_version: &'a [u8],
+    /// 0x10B for PE32, 0x20B for PE32+
+    pub magic: u16,
+    pub major_linker_version: u8,
+    pub minor_linker_version: u8,
     pub code_size: usize,
-    pub initialized_data_size: &'a [u8],
-    pub uninitialized_data_size: &'a [u8],
+    pub initialized_data_size: u32,
+    pub uninitialized_data_size: u32,
     pub entry_point_address: usize,
-    pub base_of_code: &'a [u8],
-    pub base_of_data: &'a [u8],
+    pub base_of_code: u32,
+    /// None in PE32+ images
+    pub base_of_data: Option<u32>,
     //Windows Specific Fields
-    pub image_base: &'a [u8],
-    pub section_alignment: &'a [u8],
-    pub file_alignment: &'a [u8],
-    pub major_os_version: &'a [u8],
-    pub minor_os_version: &'a [u8],
-    pub major_image_version: &'a [u8],
-    pub minor_image_version: &'a [u8],
-    pub major_subsystem_version: &'a [u8],
-    pub minor_subsystem_version: &'a [u8],
-    pub win32_version_value: &'a [u8],
-    pub image_size: &'a [u8],
-    pub headers_size: &'a [u8],
-    pub checksum: &'a [u8],
-    pub subsystem: &'a [u8],
-    pub dll_characteristics: &'a [u8],
-    pub stack_reserve_size: &'a [u8],
-    pub stack_commit_size: &'a [u8],
-    pub heap_reserve_size: &'a [u8],
-    pub heap_commit_size: &'a [u8],
-    pub loader_flags: &'a [u8],
-    pub number_of_rva_and_sizes: &'a [u8],
+    pub image_base: u64,
+    pub section_alignment: u32,
+    pub file_alignment: u32,
+    pub major_os_version: u16,
+    pub minor_os_version: u16,
+    pub major_image_version: u16,
+    pub minor_image_version: u16,
+    pub major_subsystem_version: u16,
+    pub minor_subsystem_version: u16,
+    pub win32_version_value: u32,
+    pub image_size: u32,
+    pub headers_size: u32,
+    pub checksum: u32,
+    pub subsystem: u16,
+    pub dll_characteristics: u16,
+    pub stack_reserve_size: u64,
+    pub stack_commit_size: u64,
+    pub heap_reserve_size: u64,
+    pub heap_commit_size: u64,
+    pub loader_flags: u32,
+    pub number_of_rva_and_sizes: u32,
     //Data directories
-    pub data_directory: DataDirectoryEntry<'a>,
+    pub data_directory: DataDirectory,
 }
 
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct PEFile<'a> {
     pub mz_header: DOSHeader<'a>,
+    pub dos_stub: &'a [u8],
     pub pe_header: COFFHeader<'a>,
-    pub optional_header: OptionalHeader<'a>,
+    pub optional_header: OptionalHeader,
+    pub symbol_table: SymbolTable<'a>,
+    pub string_table: StringTable<'a>,
+    pub section_table: SectionTable<'a>,
+    pub import_libraries: ImportLibraries,
+    /// None when the image exports nothing
+    pub export_table: Option<ExportTable>,
+    pub resources: RsrcDataList<'a>,
+}
+
+/// Relocatable object file (.obj): a COFF header without DOS header, PE signature
+/// and optional header
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct COFFObject<'a> {
+    pub coff_header: COFFHeader<'a>,
+    pub symbol_table: SymbolTable<'a>,
+    pub string_table: StringTable<'a>,
+    pub section_table: SectionTable<'a>,
+}
+
+/// Short import object, what a Windows import library holds for each DLL export
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ImportObjectHeader {
+    pub version: u16,
+    pub machine: u16,
+    pub timestamp: u32,
+    pub data_size: usize,
+    pub ordinal_or_hint: u16,
+    /// IMPORT_OBJECT_CODE, IMPORT_OBJECT_DATA or IMPORT_OBJECT_CONST
+    pub import_type: u16,
+    /// How the name imported from the DLL derives from the symbol name
+    pub name_type: u16,
+    pub symbol_name: String,
+    pub dll_name: String,
+    /// Exported name given explicitly (IMPORT_OBJECT_NAME_EXPORTAS)
+    pub export_name: Option<String>,
+}
+
+/// Terse Executable header, replaces the DOS, COFF and optional headers of UEFI images
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct TEHeader<'a> {
+    pub magic: &'a [u8],
+    pub machine: &'a [u8],
+    pub section_count: usize,
+    pub subsystem: u8,
+    /// Bytes of the original PE headers removed, section file offsets still count them
+    pub stripped_size: usize,
+    pub entry_point_address: usize,
+    pub base_of_code: &'a [u8],
+    pub image_base: u64,
+    pub base_relocation_table: DataDirectoryEntry,
+    pub debug: DataDirectoryEntry,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct TEFile<'a> {
+    pub te_header: TEHeader<'a>,
+    /// Section file offsets are already adjusted to the TE file
+    pub section_table: SectionTable<'a>,
 }
 
 #[allow(dead_code)]
@@ -131,9 +205,11 @@ pub struct SectionTable<'a> {
 
 #[allow(dead_code)]
 #[derive(Debug)]
-pub struct StringTable {
+pub struct StringTable<'a> {
     pub length: usize,
     pub strings: Vec<String>,
+    /// Table content after the length field, names are looked up here by offset
+    pub data: &'a [u8],
 }
 
 #[allow(dead_code)]
@@ -193,14 +269,17 @@ pub struct RessourceAdresses<'a> {
     pub adresses: Vec<RessourceAdress<'a>>,
 }
 
-
+#[derive(Debug)]
 pub enum SectionData<'a> {
     Text(TextData<'a>),
-    Rsrc(RsrcDataList<'a>),
-    IData(ImportLibraries),
     Unknown(UnknownSections<'a>)
 }
 
+#[derive(Debug)]
+pub struct SectionsData<'a> {
+    pub sections: HashMap<String, SectionData<'a>>,
+}
+
 #[derive(Debug)]
 pub struct UnknownSections<'a> {
     pub sections : Vec<UnknownSection<'a>>,
@@ -222,9 +301,23 @@ pub struct RsrcDataList<'a> {
     pub data: Vec<RsrcData<'a>>,
 }
 
+/// Resource directory entries are identified by a UTF-16 name or an integer ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceId {
+    Name(String),
+    Id(u16),
+}
+
+/// Leaf of the resource tree: type, then name, then language
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct RsrcData<'a> {
+    /// RT_ICON, RT_VERSION... or a name for custom types
+    pub resource_type: ResourceId,
+    pub name: ResourceId,
+    /// LANGID of the resource, 0 for language neutral
+    pub language: ResourceId,
+    pub data_entry: RessourceDataEntry<'a>,
     pub extracted_raw: &'a[u8],
     pub codepage: &'a str
 }
@@ -240,14 +333,65 @@ pub struct ImportDescriptor{
     pub first_thunk: usize
 }
 
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ImportFunction{
+    /// None when imported by ordinal
+    pub name: Option<String>,
+    /// Index in the export name table of the DLL the loader tries first
+    pub hint: u16,
+    pub ordinal: Option<u16>,
+    /// RVA of the IAT slot the loader overwrites with the address of the function
+    pub iat_rva: usize
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct ImportLibrary{
     pub name: String,
-    pub functions: Vec<String>
+    pub descriptor: ImportDescriptor,
+    pub functions: Vec<ImportFunction>
 }
 
 #[derive(Debug)]
 pub struct ImportLibraries{
     pub libraries: Vec<ImportLibrary>
-}
\ No newline at end of file
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ExportDirectory {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub name_rva: usize,
+    /// Ordinal of the first entry of the export address table
+    pub ordinal_base: u32,
+    pub address_table_entries: usize,
+    pub name_pointer_count: usize,
+    pub export_address_table_rva: usize,
+    pub name_pointer_rva: usize,
+    pub ordinal_table_rva: usize,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ExportFunction {
+    /// None when only exported by ordinal
+    pub name: Option<String>,
+    pub ordinal: u32,
+    /// 0 for forwarded exports
+    pub rva: usize,
+    /// `DLL.Function` or `DLL.#ordinal` the loader resolves instead
+    pub forwarder: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ExportTable {
+    pub dll_name: String,
+    pub directory: ExportDirectory,
+    /// Ordered by ordinal, a function exported under several names appears once per name
+    pub functions: Vec<ExportFunction>,
+}
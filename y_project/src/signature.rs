@@ -1,35 +1,358 @@
-pub struct Signature<'a> {
-    pub name: &'a str,
-    pub signature: &'a [u8],
-}
-
-pub const SIGNATURES: [Signature; 7] = [
-    Signature {
-        name: "DOS MZ executable",
-        signature: b"\x4D\x5A",
-    },
-    Signature {
-        name: "Executable and Linkable Format (ELF)",
-        signature: b"\x7F\x45\x4C\x46",
-    },
-    Signature {
-        name: "Mach-O binary (32-bit)",
-        signature: b"\xFE\xED\xFA\xCE",
-    },
-    Signature {
-        name: "Mach-O binary (64-bit)",
-        signature: b"\xFE\xED\xFA\xCF",
-    },
-    Signature {
-        name: "Mach-O binary (reverse byte ordering scheme, 32-bit)",
-        signature: b"\xCE\xFA\xED\xFE",
-    },
-    Signature {
-        name: "Mach-O binary (reverse byte ordering scheme, 64-bit)",
-        signature: b"\xCF\xFA\xED\xFE",
-    },
-    Signature {
-        name: "Java class file, Mach-O Fat Binary",
-        signature: b"\xCA\xFE\xBA\xBE",
-    },
-];
+use std::fs;
+
+use crate::error::ParseError;
+
+/// Built-in signatures, in the same format as user signature files: one signature per line,
+/// `priority offset min_length pattern[/mask] name`, `??` matches any byte and `#` starts a
+/// comment at the start of a line or of a field up to the name. The name is what `parse_with_signature` dispatches on.
+pub const BUILTIN_SIGNATURES: &str = "\
+# priority offset min_length pattern[/mask] name
+10 0 64 4D5A DOS MZ executable
+50 0 52 7F454C46 Executable and Linkable Format (ELF)
+50 0 28 FEEDFACE Mach-O binary (32-bit)
+50 0 32 FEEDFACF Mach-O binary (64-bit)
+50 0 28 CEFAEDFE Mach-O binary (reverse byte ordering scheme, 32-bit)
+50 0 32 CFFAEDFE Mach-O binary (reverse byte ordering scheme, 64-bit)
+# CA FE BA BE is followed by nfat_arch in universal binaries (a handful of slices) and by
+# the minor and major version in class files (major version 45 and later)
+60 0 8 CAFEBABE00000000/FFFFFFFFFFFFFFE0 Mach-O universal binary
+50 0 10 CAFEBABE Java class file
+50 0 8 CAFEBABF Mach-O universal binary (64-bit)
+40 0 30 504B0304 ZIP archive
+50 0 8 213C617263683E0A Unix ar archive
+50 0 8 213C7468696E3E0A Unix ar archive (thin)
+# members of Windows import libraries: both signatures then version 0
+50 0 20 0000FFFF0000 Windows import object
+50 0 8 0061736D01000000 WebAssembly module
+50 0 0x70 6465780A??????00 Dalvik executable (DEX)
+50 0 40 6465790A??????00 Optimized Dalvik executable (ODEX)
+# boot sector signature, then the setup header magic after its jump, EFI stub kernels also
+# start with MZ
+60 0x1FE 0x260 55AA????48647253 Linux kernel bzImage
+# object files have no magic: a known machine and no optional header
+20 0 20 4C01????????????????????????????0000 COFF object file
+20 0 20 6486????????????????????????????0000 COFF object file
+20 0 20 64AA????????????????????????????0000 COFF object file
+20 0 20 C401????????????????????????????0000 COFF object file
+30 0 40 565A4C01 UEFI Terse Executable (TE)
+30 0 40 565A6486 UEFI Terse Executable (TE)
+30 0 40 565A64AA UEFI Terse Executable (TE)
+30 0 40 565AC401 UEFI Terse Executable (TE)
+30 0 40 565A6450 UEFI Terse Executable (TE)
+# text firmware images: a record mark followed by ASCII, Intel HEX record types are 0x
+15 0 11 3A00000000000030/FF808080808080FF Intel HEX
+15 0 10 53300000/FFF08080 Motorola S-record
+";
+
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub name: String,
+    pub offset: usize,
+    pub pattern: Vec<u8>,
+    /// Bits of `pattern` that have to match, same length as the pattern
+    pub mask: Vec<u8>,
+    /// Files shorter than this cannot be of this format
+    pub min_length: usize,
+    /// Orders the candidates, the most specific signature of overlapping ones wins
+    pub priority: u32,
+}
+
+#[derive(Debug)]
+pub struct SignatureMatch<'a> {
+    pub signature: &'a Signature,
+    /// 0 to 100, from the number of bits the signature checks (32 bits and more is 100)
+    pub confidence: u8,
+}
+
+#[derive(Debug, Default)]
+pub struct SignatureDatabase {
+    pub signatures: Vec<Signature>,
+}
+
+fn parse_hex(text: &str, line: usize) -> Result<(Vec<u8>, Vec<u8>), ParseError> {
+    let invalid = || ParseError::InvalidSignature {
+        line,
+        reason: "pattern is not hexadecimal",
+    };
+    if text.is_empty() || !text.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    let mut bytes = Vec::new();
+    let mut mask = Vec::new();
+    for pair in text.as_bytes().chunks(2) {
+        if pair == b"??" {
+            bytes.push(0);
+            mask.push(0);
+        } else {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(pair, 16).map_err(|_| invalid())?);
+            mask.push(0xFF);
+        }
+    }
+    Ok((bytes, mask))
+}
+
+/// First whitespace separated field and the rest of the line
+fn split_field(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    text.split_at(text.find(char::is_whitespace).unwrap_or(text.len()))
+}
+
+/// Cuts a comment starting at a field up to the name, names may contain `#` after that
+fn strip_comment(line: &str) -> &str {
+    let mut rest = line;
+    for _ in 0..5 {
+        let field_start = line.len() - rest.trim_start().len();
+        let (field, remainder) = split_field(rest);
+        if field.starts_with('#') {
+            return &line[..field_start];
+        }
+        rest = remainder;
+    }
+    line
+}
+
+impl Signature {
+    /// Parses a `priority offset min_length pattern[/mask] name` line
+    pub fn from_line(text: &str, line: usize) -> Result<Signature, ParseError> {
+        let mut rest = text;
+        let mut next_field = || {
+            let (field, remainder) = split_field(rest);
+            rest = remainder;
+            field
+        };
+        // offsets and lengths are often easier to read in hexadecimal
+        let mut number = |reason: &'static str| -> Result<usize, ParseError> {
+            let field = next_field();
+            match field.strip_prefix("0x") {
+                Some(hex) => usize::from_str_radix(hex, 16).ok(),
+                None => field.parse().ok(),
+            }
+            .ok_or(ParseError::InvalidSignature { line, reason })
+        };
+        let priority = u32::try_from(number("invalid priority")?).map_err(|_| {
+            ParseError::InvalidSignature {
+                line,
+                reason: "invalid priority",
+            }
+        })?;
+        let offset = number("invalid offset")?;
+        let min_length = number("invalid minimum length")?;
+        let (pattern, name) = split_field(rest);
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ParseError::InvalidSignature {
+                line,
+                reason: "missing name",
+            });
+        }
+
+        let (pattern, mask) = match pattern.split_once('/') {
+            Some((pattern, mask)) => {
+                let (pattern, wildcards) = parse_hex(pattern, line)?;
+                let (mask, _) = parse_hex(mask, line)?;
+                if mask.len() != pattern.len() {
+                    return Err(ParseError::InvalidSignature {
+                        line,
+                        reason: "mask and pattern lengths differ",
+                    });
+                }
+                let mask = mask
+                    .iter()
+                    .zip(wildcards)
+                    .map(|(mask, wildcard)| mask & wildcard);
+                (pattern, mask.collect())
+            }
+            None => parse_hex(pattern, line)?,
+        };
+        Ok(Signature {
+            name: name.to_string(),
+            offset,
+            pattern,
+            mask,
+            min_length,
+            priority,
+        })
+    }
+
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        if bytes.len() < self.min_length {
+            return false;
+        }
+        match self
+            .offset
+            .checked_add(self.pattern.len())
+            .and_then(|end| bytes.get(self.offset..end))
+        {
+            Some(data) => data
+                .iter()
+                .zip(self.pattern.iter().zip(self.mask.iter()))
+                .all(|(byte, (pattern, mask))| byte & mask == pattern & mask),
+            None => false,
+        }
+    }
+
+    pub fn confidence(&self) -> u8 {
+        let bits: u32 = self.mask.iter().map(|mask| mask.count_ones()).sum();
+        (bits * 100 / 32).min(100) as u8
+    }
+}
+
+impl SignatureDatabase {
+    pub fn builtin() -> SignatureDatabase {
+        let mut database = SignatureDatabase::default();
+        database
+            .load_text(BUILTIN_SIGNATURES)
+            .expect("invalid built-in signature");
+        database
+    }
+
+    /// Adds the signatures of a text in the `BUILTIN_SIGNATURES` format, returns how many
+    pub fn load_text(&mut self, text: &str) -> Result<usize, ParseError> {
+        let mut count = 0;
+        for (index, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if !line.is_empty() {
+                self.signatures.push(Signature::from_line(line, index + 1)?);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Adds a user signature file on top of what is already loaded
+    pub fn load_file(&mut self, path: &str) -> Result<usize, ParseError> {
+        let text = fs::read_to_string(path)?;
+        self.load_text(&text)
+    }
+
+    /// Every signature matching the file, best candidate first
+    pub fn find_all(&self, bytes: &[u8]) -> Vec<SignatureMatch<'_>> {
+        let mut matches: Vec<SignatureMatch> = self
+            .signatures
+            .iter()
+            .filter(|signature| signature.matches(bytes))
+            .map(|signature| SignatureMatch {
+                signature,
+                confidence: signature.confidence(),
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            (b.signature.priority, b.confidence).cmp(&(a.signature.priority, a.confidence))
+        });
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_may_contain_a_hash() {
+        let mut database = SignatureDatabase::default();
+        let text = "# comment line\n  # indented comment\n50 0 4 4D5A C# assembly\n";
+        assert_eq!(database.load_text(text).unwrap(), 1);
+        assert_eq!(database.signatures[0].name, "C# assembly");
+    }
+
+    #[test]
+    fn comments_end_the_line_before_the_name() {
+        let mut database = SignatureDatabase::default();
+        assert!(matches!(
+            database.load_text("50 0 4 4D5A #the name is missing"),
+            Err(ParseError::InvalidSignature {
+                line: 1,
+                reason: "missing name"
+            })
+        ));
+        assert!(matches!(
+            database.load_text("50 0 #4 4D5A name"),
+            Err(ParseError::InvalidSignature {
+                line: 1,
+                reason: "invalid minimum length"
+            })
+        ));
+    }
+
+    #[test]
+    fn builtin_signatures_load() {
+        let database = SignatureDatabase::builtin();
+        assert_eq!(
+            database.signatures.len(),
+            BUILTIN_SIGNATURES
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .count()
+        );
+    }
+
+    #[test]
+    fn priorities_fit_in_32_bits() {
+        assert_eq!(
+            Signature::from_line("4294967295 0 0 4D5A name", 1)
+                .unwrap()
+                .priority,
+            u32::MAX
+        );
+        assert!(matches!(
+            Signature::from_line("4294967296 0 0 4D5A name", 1),
+            Err(ParseError::InvalidSignature {
+                line: 1,
+                reason: "invalid priority"
+            })
+        ));
+    }
+
+    #[test]
+    fn offsets_masks_and_wildcards_select_the_bytes() {
+        let signature = Signature::from_line("1 2 0 AB??CD/FF00F0 name", 1).unwrap();
+        assert_eq!(signature.mask, [0xFF, 0x00, 0xF0]);
+        // 8 + 0 + 4 of 32 bits
+        assert_eq!(signature.confidence(), 37);
+        assert!(signature.matches(&[0, 0, 0xAB, 0x77, 0xC5]));
+        assert!(!signature.matches(&[0, 0, 0xAB, 0x77, 0xD5]));
+        assert!(!signature.matches(&[0xAB, 0x77, 0xC5, 0, 0]));
+        assert!(!signature.matches(&[0, 0, 0xAB, 0x77]));
+
+        let signature = Signature::from_line("1 0 0x10 4D5A name", 1).unwrap();
+        assert!(signature.matches(&[b'M', b'Z', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(!signature.matches(&[b'M', b'Z', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn candidates_are_ordered_by_priority_then_confidence() {
+        let mut database = SignatureDatabase::default();
+        let text = "10 0 0 4D5A90 low priority\n50 0 0 4D short\n50 0 0 4D5A9000 long\n\
+                    50 0 0 4D5B other\n";
+        database.load_text(text).unwrap();
+        let candidates = database.find_all(&[0x4D, 0x5A, 0x90, 0x00]);
+        let order: Vec<(&str, u8)> = candidates
+            .iter()
+            .map(|candidate| (candidate.signature.name.as_str(), candidate.confidence))
+            .collect();
+        assert_eq!(order, [("long", 100), ("short", 25), ("low priority", 75)]);
+    }
+
+    #[test]
+    fn only_the_signature_offset_is_matched() {
+        // get_sign used to match every signature at the start of each 1 KiB chunk, so an MZ
+        // at a chunk boundary took over an ELF file
+        let mut bytes = vec![0; 4096];
+        bytes[..4].copy_from_slice(b"\x7FELF");
+        bytes[1024..1026].copy_from_slice(b"MZ");
+        bytes[2048..2050].copy_from_slice(b"MZ");
+        let database = SignatureDatabase::builtin();
+        let candidates = database.find_all(&bytes);
+        assert_eq!(
+            candidates[0].signature.name,
+            "Executable and Linkable Format (ELF)"
+        );
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.signature.name != "DOS MZ executable"));
+
+        bytes[..4].fill(0);
+        assert!(database.find_all(&bytes).is_empty());
+    }
+}
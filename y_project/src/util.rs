@@ -0,0 +1,417 @@
+use std::convert::TryInto;
+
+use crate::binary_file::Endianness;
+use crate::error::ParseError;
+
+pub fn match_codepage<'a>(u16_codepage: u16) -> &'a str {
+    match u16_codepage {
+        37 => "IBM037",
+        437 => "IBM437",
+        500 => "IBM500",
+        708 => "ASMO-708",
+        709 => "",
+        710 => "",
+        720 => "DOS-720",
+        737 => "ibm737",
+        775 => "ibm775",
+        850 => "ibm850",
+        852 => "ibm852",
+        855 => "IBM855",
+        857 => "ibm857",
+        858 => "IBM00858",
+        860 => "IBM860",
+        861 => "ibm861",
+        862 => "DOS-862",
+        863 => "IBM863",
+        864 => "IBM864",
+        865 => "IBM865",
+        866 => "cp866",
+        869 => "ibm869",
+        870 => "IBM870",
+        874 => "windows-874",
+        875 => "cp875",
+        932 => "shift_jis",
+        936 => "gb2312",
+        949 => "ks_c_5601-1987",
+        950 => "big5",
+        1026 => "IBM1026",
+        1047 => "IBM01047",
+        1140 => "IBM01140",
+        1141 => "IBM01141",
+        1142 => "IBM01142",
+        1143 => "IBM01143",
+        1144 => "IBM01144",
+        1145 => "IBM01145",
+        1146 => "IBM01146",
+        1147 => "IBM01147",
+        1148 => "IBM01148",
+        1149 => "IBM01149",
+        1200 => "utf-16",
+        1201 => "unicodeFFFE",
+        1250 => "windows-1250",
+        1251 => "windows-1251",
+        1252 => "windows-1252",
+        1253 => "windows-1253",
+        1254 => "windows-1254",
+        1255 => "windows-1255",
+        1256 => "windows-1256",
+        1257 => "windows-1257",
+        1258 => "windows-1258",
+        1361 => "Johab",
+        10000 => "macintosh",
+        10001 => "x-mac-japanese",
+        10002 => "x-mac-chinesetrad",
+        10003 => "x-mac-korean",
+        10004 => "x-mac-arabic",
+        10005 => "x-mac-hebrew",
+        10006 => "x-mac-greek",
+        10007 => "x-mac-cyrillic",
+        10008 => "x-mac-chinesesimp",
+        10010 => "x-mac-romanian",
+        10017 => "x-mac-ukrainian",
+        10021 => "x-mac-thai",
+        10029 => "x-mac-ce",
+        10079 => "x-mac-icelandic",
+        10081 => "x-mac-turkish",
+        10082 => "x-mac-croatian",
+        12000 => "utf-32",
+        12001 => "utf-32BE",
+        20000 => "x-Chinese_CNS",
+        20001 => "x-cp20001",
+        20002 => "x_Chinese-Eten",
+        20003 => "x-cp20003",
+        20004 => "x-cp20004",
+        20005 => "x-cp20005",
+        20105 => "x-IA5",
+        20106 => "x-IA5-German",
+        20107 => "x-IA5-Swedish",
+        20108 => "x-IA5-Norwegian",
+        20127 => "us-ascii",
+        20261 => "x-cp20261",
+        20269 => "x-cp20269",
+        20273 => "IBM273",
+        20277 => "IBM277",
+        20278 => "IBM278",
+        20280 => "IBM280",
+        20284 => "IBM284",
+        20285 => "IBM285",
+        20290 => "IBM290",
+        20297 => "IBM297",
+        20420 => "IBM420",
+        20423 => "IBM423",
+        20424 => "IBM424",
+        20833 => "x-EBCDIC-KoreanExtended",
+        20838 => "IBM-Thai",
+        20866 => "koi8-r",
+        20871 => "IBM871",
+        20880 => "IBM880",
+        20905 => "IBM905",
+        20924 => "IBM00924",
+        20932 => "EUC-JP",
+        20936 => "x-cp20936",
+        20949 => "x-cp20949",
+        21025 => "cp1025",
+        21866 => "koi8-u",
+        28591 => "iso-8859-1",
+        28592 => "iso-8859-2",
+        28593 => "iso-8859-3",
+        28594 => "iso-8859-4",
+        28595 => "iso-8859-5",
+        28596 => "iso-8859-6",
+        28597 => "iso-8859-7",
+        28598 => "iso-8859-8",
+        28599 => "iso-8859-9",
+        28603 => "iso-8859-13",
+        28605 => "iso-8859-15",
+        29001 => "x-Europa",
+        38598 => "iso-8859-8-i",
+        50220 => "iso-2022-jp",
+        50221 => "csISO2022JP",
+        50222 => "iso-2022-jp",
+        50225 => "iso-2022-kr",
+        50227 => "x-cp50227",
+        50229 => "",
+        50930 => "",
+        50931 => "",
+        50933 => "",
+        50935 => "",
+        50936 => "",
+        50937 => "",
+        50939 => "",
+        51932 => "euc-jp",
+        51936 => "EUC-CN",
+        51949 => "euc-kr",
+        51950 => "",
+        52936 => "hz-gb-2312",
+        54936 => "GB18030",
+        57002 => "x-iscii-de",
+        57003 => "x-iscii-be",
+        57004 => "x-iscii-ta",
+        57005 => "x-iscii-te",
+        57006 => "x-iscii-as",
+        57007 => "x-iscii-or",
+        57008 => "x-iscii-ka",
+        57009 => "x-iscii-ma",
+        57010 => "x-iscii-gu",
+        57011 => "x-iscii-pa",
+        65000 => "utf-7",
+        65001 => "utf-8",
+        _ => "Unknown",
+    }
+}
+
+pub fn le_to_u32(bytes: &[u8]) -> u32 {
+    let array: [u8; 4] = bytes[0..4].try_into().expect("wrong size length");
+    u32::from_le_bytes(array)
+}
+
+pub fn le_to_u64(bytes: &[u8]) -> u64 {
+    let array: [u8; 8] = bytes[0..8].try_into().expect("wrong size length");
+    u64::from_le_bytes(array)
+}
+
+pub fn le_to_u16(bytes: &[u8]) -> u16 {
+    let array: [u8; 2] = bytes[0..2].try_into().expect("wrong size length");
+    u16::from_le_bytes(array)
+}
+
+pub fn be_to_u16(bytes: &[u8]) -> u16 {
+    let array: [u8; 2] = bytes[0..2].try_into().expect("wrong size length");
+    u16::from_be_bytes(array)
+}
+
+pub fn be_to_u32(bytes: &[u8]) -> u32 {
+    let array: [u8; 4] = bytes[0..4].try_into().expect("wrong size length");
+    u32::from_be_bytes(array)
+}
+
+pub fn be_to_u64(bytes: &[u8]) -> u64 {
+    let array: [u8; 8] = bytes[0..8].try_into().expect("wrong size length");
+    u64::from_be_bytes(array)
+}
+
+pub fn read_u16(bytes: &[u8], endianness: Endianness) -> u16 {
+    match endianness {
+        Endianness::Little => le_to_u16(bytes),
+        Endianness::Big => be_to_u16(bytes),
+    }
+}
+
+pub fn read_u32(bytes: &[u8], endianness: Endianness) -> u32 {
+    match endianness {
+        Endianness::Little => le_to_u32(bytes),
+        Endianness::Big => be_to_u32(bytes),
+    }
+}
+
+pub fn read_u64(bytes: &[u8], endianness: Endianness) -> u64 {
+    match endianness {
+        Endianness::Little => le_to_u64(bytes),
+        Endianness::Big => be_to_u64(bytes),
+    }
+}
+
+/// Reads a field whose width depends on the file class (ELF addresses, offsets...)
+pub fn read_uint(bytes: &[u8], endianness: Endianness) -> u64 {
+    debug_assert!(
+        matches!(bytes.len(), 1 | 2 | 4 | 8),
+        "no {}-byte integer field",
+        bytes.len()
+    );
+    match bytes.len() {
+        1 => bytes[0] as u64,
+        2 => read_u16(bytes, endianness) as u64,
+        4 => read_u32(bytes, endianness) as u64,
+        _ => read_u64(bytes, endianness),
+    }
+}
+
+pub fn le_to_usize(bytes: &[u8]) -> usize {
+    let mut array = [0u8; std::mem::size_of::<usize>()];
+    for (i, &byte) in bytes.iter().enumerate() {
+        array[i] = byte;
+    }
+    usize::from_le_bytes(array)
+}
+
+/// Bounds checked slice of `size` bytes starting at `offset`
+pub fn get_bytes(bytes: &[u8], offset: usize, size: usize) -> Result<&[u8], ParseError> {
+    offset
+        .checked_add(size)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or(ParseError::OutOfRange {
+            offset,
+            size,
+            file_size: bytes.len(),
+        })
+}
+
+/// Same as `get_bytes` but reports a truncated header instead of a bad offset
+pub fn get_header<'a>(
+    bytes: &'a [u8],
+    offset: usize,
+    size: usize,
+    header: &'static str,
+) -> Result<&'a [u8], ParseError> {
+    get_bytes(bytes, offset, size).map_err(|_| ParseError::TruncatedHeader {
+        header,
+        offset,
+        size,
+        file_size: bytes.len(),
+    })
+}
+
+/// Reads a NUL terminated string, the terminator is optional at the end of the data
+pub fn read_c_string(bytes: &[u8], offset: usize) -> Result<String, ParseError> {
+    let data = bytes.get(offset..).ok_or(ParseError::OutOfRange {
+        offset,
+        size: 1,
+        file_size: bytes.len(),
+    })?;
+    let end = data.iter().position(|&byte| byte == 0).unwrap_or(data.len());
+    Ok(String::from_utf8_lossy(&data[..end]).to_string())
+}
+
+/// Reads an unsigned LEB128 value and moves `offset` past it
+pub fn read_uleb128(bytes: &[u8], offset: &mut usize) -> Result<u64, ParseError> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*offset).ok_or(ParseError::OutOfRange {
+            offset: *offset,
+            size: 1,
+            file_size: bytes.len(),
+        })?;
+        *offset += 1;
+        if shift < 64 {
+            value |= ((byte & 0x7F) as u64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+/// Reads a signed LEB128 value and moves `offset` past it
+pub fn read_sleb128(bytes: &[u8], offset: &mut usize) -> Result<i64, ParseError> {
+    let mut value = 0i64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*offset).ok_or(ParseError::OutOfRange {
+            offset: *offset,
+            size: 1,
+            file_size: bytes.len(),
+        })?;
+        *offset += 1;
+        if shift < 64 {
+            value |= ((byte & 0x7F) as i64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1i64 << shift;
+            }
+            return Ok(value);
+        }
+    }
+}
+
+/// CRC-32 (IEEE 802.3, reflected 0xEDB88320) as used by ZIP and gzip
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes.iter() {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_bytes_rejects_ranges_past_the_end() {
+        let bytes = [1, 2, 3, 4];
+        assert_eq!(get_bytes(&bytes, 1, 3).unwrap(), [2, 3, 4]);
+        assert_eq!(get_bytes(&bytes, 4, 0).unwrap(), []);
+        assert!(matches!(
+            get_bytes(&bytes, 2, 3),
+            Err(ParseError::OutOfRange {
+                offset: 2,
+                size: 3,
+                file_size: 4
+            })
+        ));
+        assert!(get_bytes(&bytes, 5, 0).is_err());
+        assert!(get_bytes(&bytes, usize::MAX, 2).is_err());
+        assert!(get_bytes(&bytes, 2, usize::MAX).is_err());
+        assert!(matches!(
+            get_header(&bytes, 0, 8, "test header"),
+            Err(ParseError::TruncatedHeader {
+                header: "test header",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn integer_fields_follow_the_file_byte_order() {
+        let bytes = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(read_uint(&bytes[..1], Endianness::Big), 1);
+        assert_eq!(read_uint(&bytes[..2], Endianness::Little), 0x0201);
+        assert_eq!(read_uint(&bytes[..2], Endianness::Big), 0x0102);
+        assert_eq!(read_uint(&bytes[..4], Endianness::Little), 0x0403_0201);
+        assert_eq!(read_uint(&bytes[..4], Endianness::Big), 0x0102_0304);
+        assert_eq!(read_uint(&bytes, Endianness::Little), 0x0807_0605_0403_0201);
+        assert_eq!(read_uint(&bytes, Endianness::Big), 0x0102_0304_0506_0708);
+    }
+
+    #[test]
+    #[should_panic]
+    fn integer_fields_have_a_known_width() {
+        read_uint(&[1, 2, 3], Endianness::Big);
+    }
+
+    #[test]
+    fn c_strings_end_at_the_data() {
+        let bytes = b"one\0two";
+        assert_eq!(read_c_string(bytes, 0).unwrap(), "one");
+        assert_eq!(read_c_string(bytes, 4).unwrap(), "two");
+        assert_eq!(read_c_string(bytes, 7).unwrap(), "");
+        assert!(read_c_string(bytes, 8).is_err());
+        assert!(read_c_string(bytes, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn leb128_values_are_bounded() {
+        let mut offset = 0;
+        assert_eq!(read_uleb128(&[0xE5, 0x8E, 0x26], &mut offset).unwrap(), 624485);
+        assert_eq!(offset, 3);
+        offset = 0;
+        assert_eq!(read_sleb128(&[0xC0, 0xBB, 0x78], &mut offset).unwrap(), -123456);
+
+        // bits past the 64th are dropped instead of overflowing
+        let mut long = vec![0xFF; 20];
+        long.push(0x7F);
+        offset = 0;
+        assert_eq!(read_uleb128(&long, &mut offset).unwrap(), u64::MAX);
+        offset = 0;
+        assert_eq!(read_sleb128(&long, &mut offset).unwrap(), -1);
+
+        offset = 0;
+        assert!(matches!(
+            read_uleb128(&[0x80, 0x80], &mut offset),
+            Err(ParseError::OutOfRange { offset: 2, .. })
+        ));
+        offset = 0;
+        assert!(read_sleb128(&[0x80], &mut offset).is_err());
+    }
+}
@@ -0,0 +1,501 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::binary_file::{Architecture, BinaryFile, Bitness, Endianness, FileDetails, Format};
+use crate::error::ParseError;
+use crate::inflate::inflate;
+use crate::jvm_parser::{add_class_to_model, extract_class_file};
+use crate::util::{crc32, get_bytes, get_header, le_to_u16, le_to_u32, le_to_u64};
+use crate::zip_structure::{
+    FileInfoZip, JarManifest, ZipClass, ZipEndOfCentralDirectory, ZipEntry,
+};
+use crate::zip_structure::{
+    CENTRAL_DIRECTORY_HEADER_SIZE, CENTRAL_DIRECTORY_SIGNATURE, COMPRESSION_DEFLATED,
+    COMPRESSION_STORED, END_OF_CENTRAL_DIRECTORY_SIGNATURE, END_OF_CENTRAL_DIRECTORY_SIZE,
+    FLAG_ENCRYPTED, LOCAL_FILE_HEADER_SIGNATURE, LOCAL_FILE_HEADER_SIZE, MANIFEST_NAME,
+    MAX_COMMENT_SIZE, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE,
+    ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE, ZIP64_EXTRA_FIELD, ZIP64_LOCATOR_SIGNATURE,
+    ZIP64_LOCATOR_SIZE,
+};
+
+/// The end of central directory record is found by scanning backwards over the comment
+pub fn extract_end_of_central_directory(
+    bytes: &[u8],
+) -> Result<ZipEndOfCentralDirectory<'_>, ParseError> {
+    let last = bytes
+        .len()
+        .checked_sub(END_OF_CENTRAL_DIRECTORY_SIZE)
+        .ok_or(ParseError::TruncatedHeader {
+            header: "ZIP end of central directory",
+            offset: 0,
+            size: END_OF_CENTRAL_DIRECTORY_SIZE,
+            file_size: bytes.len(),
+        })?;
+    let first = last.saturating_sub(MAX_COMMENT_SIZE);
+    let offset = (first..=last)
+        .rev()
+        .find(|&offset| {
+            let record = &bytes[offset..offset + END_OF_CENTRAL_DIRECTORY_SIZE];
+            &record[0..4] == END_OF_CENTRAL_DIRECTORY_SIGNATURE
+                && offset + END_OF_CENTRAL_DIRECTORY_SIZE + le_to_u16(&record[20..22]) as usize
+                    <= bytes.len()
+        })
+        .ok_or(ParseError::InvalidData {
+            structure: "ZIP end of central directory",
+            offset: last,
+        })?;
+    let record = &bytes[offset..offset + END_OF_CENTRAL_DIRECTORY_SIZE];
+    let comment_offset = offset + END_OF_CENTRAL_DIRECTORY_SIZE;
+    let comment = &bytes[comment_offset..comment_offset + le_to_u16(&record[20..22]) as usize];
+
+    let mut end = ZipEndOfCentralDirectory {
+        disk_number: le_to_u16(&record[4..6]) as u32,
+        entry_count: le_to_u16(&record[10..12]) as u64,
+        central_directory_size: le_to_u32(&record[12..16]) as u64,
+        central_directory_offset: le_to_u32(&record[16..20]) as u64,
+        zip64: false,
+        comment,
+    };
+    // saturated fields are stored in the ZIP64 record, located just before
+    let saturated = end.entry_count == 0xFFFF
+        || end.central_directory_size == 0xFFFF_FFFF
+        || end.central_directory_offset == 0xFFFF_FFFF;
+    if saturated && offset >= ZIP64_LOCATOR_SIZE {
+        let locator = &bytes[offset - ZIP64_LOCATOR_SIZE..offset];
+        if &locator[0..4] == ZIP64_LOCATOR_SIGNATURE {
+            let record_offset = le_to_u64(&locator[8..16]) as usize;
+            let record = get_header(
+                bytes,
+                record_offset,
+                ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE,
+                "ZIP64 end of central directory",
+            )?;
+            if &record[0..4] != ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE {
+                return Err(ParseError::BadMagic {
+                    format: "ZIP64 end of central directory",
+                    found: record[0..4].to_vec(),
+                });
+            }
+            end.disk_number = le_to_u32(&record[16..20]);
+            end.entry_count = le_to_u64(&record[32..40]);
+            end.central_directory_size = le_to_u64(&record[40..48]);
+            end.central_directory_offset = le_to_u64(&record[48..56]);
+            end.zip64 = true;
+        }
+    }
+    Ok(end)
+}
+
+/// Replaces the saturated 32 bits sizes and offset by the values of the ZIP64 extra field,
+/// which only holds the saturated ones, in this order
+fn apply_zip64_extra_field(
+    extra_field: &[u8],
+    uncompressed_size: &mut u64,
+    compressed_size: &mut u64,
+    local_header_offset: &mut u64,
+) -> Result<(), ParseError> {
+    let mut offset = 0;
+    while offset + 4 <= extra_field.len() {
+        let id = le_to_u16(&extra_field[offset..offset + 2]);
+        let size = le_to_u16(&extra_field[offset + 2..offset + 4]) as usize;
+        let data = get_bytes(extra_field, offset + 4, size)?;
+        if id == ZIP64_EXTRA_FIELD {
+            let mut values = data.chunks_exact(8).map(le_to_u64);
+            for field in [uncompressed_size, compressed_size, local_header_offset] {
+                if *field == 0xFFFF_FFFF {
+                    if let Some(value) = values.next() {
+                        *field = value;
+                    }
+                }
+            }
+            return Ok(());
+        }
+        offset += 4 + size;
+    }
+    Ok(())
+}
+
+pub fn extract_zip_entries<'a>(
+    bytes: &'a [u8],
+    end: &ZipEndOfCentralDirectory,
+) -> Result<Vec<ZipEntry<'a>>, ParseError> {
+    let directory = get_bytes(
+        bytes,
+        end.central_directory_offset as usize,
+        end.central_directory_size as usize,
+    )?;
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    for _ in 0..end.entry_count {
+        let header = get_header(
+            directory,
+            offset,
+            CENTRAL_DIRECTORY_HEADER_SIZE,
+            "ZIP central directory header",
+        )?;
+        if &header[0..4] != CENTRAL_DIRECTORY_SIGNATURE {
+            return Err(ParseError::BadMagic {
+                format: "ZIP central directory",
+                found: header[0..4].to_vec(),
+            });
+        }
+        let name_size = le_to_u16(&header[28..30]) as usize;
+        let extra_size = le_to_u16(&header[30..32]) as usize;
+        let comment_size = le_to_u16(&header[32..34]) as usize;
+        let variable = get_bytes(
+            directory,
+            offset + CENTRAL_DIRECTORY_HEADER_SIZE,
+            name_size + extra_size + comment_size,
+        )?;
+        let extra_field = &variable[name_size..name_size + extra_size];
+
+        let mut compressed_size = le_to_u32(&header[20..24]) as u64;
+        let mut uncompressed_size = le_to_u32(&header[24..28]) as u64;
+        let mut local_header_offset = le_to_u32(&header[42..46]) as u64;
+        apply_zip64_extra_field(
+            extra_field,
+            &mut uncompressed_size,
+            &mut compressed_size,
+            &mut local_header_offset,
+        )?;
+
+        // the sizes of the local header may be 0 when a data descriptor follows the data,
+        // only its name and extra field lengths are used
+        let local_header = get_header(
+            bytes,
+            local_header_offset as usize,
+            LOCAL_FILE_HEADER_SIZE,
+            "ZIP local file header",
+        )?;
+        if &local_header[0..4] != LOCAL_FILE_HEADER_SIGNATURE {
+            return Err(ParseError::BadMagic {
+                format: "ZIP local file header",
+                found: local_header[0..4].to_vec(),
+            });
+        }
+        let data_offset = local_header_offset as usize
+            + LOCAL_FILE_HEADER_SIZE
+            + le_to_u16(&local_header[26..28]) as usize
+            + le_to_u16(&local_header[28..30]) as usize;
+
+        entries.push(ZipEntry {
+            name: String::from_utf8_lossy(&variable[..name_size]).to_string(),
+            version_made_by: le_to_u16(&header[4..6]),
+            version_needed: le_to_u16(&header[6..8]),
+            flags: le_to_u16(&header[8..10]),
+            compression_method: le_to_u16(&header[10..12]),
+            modification_time: le_to_u16(&header[12..14]),
+            modification_date: le_to_u16(&header[14..16]),
+            crc32: le_to_u32(&header[16..20]),
+            compressed_size,
+            uncompressed_size,
+            external_attributes: le_to_u32(&header[38..42]),
+            local_header_offset,
+            extra_field,
+            comment: String::from_utf8_lossy(&variable[name_size + extra_size..]).to_string(),
+            compressed_data: get_bytes(bytes, data_offset, compressed_size as usize)?,
+        });
+        offset += CENTRAL_DIRECTORY_HEADER_SIZE + variable.len();
+    }
+    Ok(entries)
+}
+
+/// Uncompressed content of an entry, checked against its CRC-32
+pub fn extract_zip_entry_data<'a>(entry: &ZipEntry<'a>) -> Result<Cow<'a, [u8]>, ParseError> {
+    if entry.flags & FLAG_ENCRYPTED != 0 {
+        return Err(ParseError::UnsupportedFormat(format!(
+            "encrypted ZIP entry {}",
+            entry.name
+        )));
+    }
+    let data = match entry.compression_method {
+        COMPRESSION_STORED => Cow::Borrowed(entry.compressed_data),
+        COMPRESSION_DEFLATED => Cow::Owned(inflate(
+            entry.compressed_data,
+            entry.uncompressed_size as usize,
+        )?),
+        method => {
+            return Err(ParseError::UnsupportedFormat(format!(
+                "ZIP compression method {}",
+                method
+            )))
+        }
+    };
+    if data.len() as u64 != entry.uncompressed_size || crc32(&data) != entry.crc32 {
+        return Err(ParseError::InvalidData {
+            structure: "ZIP entry checksum",
+            offset: entry.local_header_offset as usize,
+        });
+    }
+    Ok(data)
+}
+
+/// Manifest lines are `Name: value`, continued on the next line when it starts with a
+/// space. Sections are separated by blank lines.
+pub fn extract_jar_manifest(data: &[u8]) -> JarManifest {
+    let text = String::from_utf8_lossy(data);
+    let mut sections = Vec::new();
+    let mut section: Vec<(String, String)> = Vec::new();
+    for line in text.lines() {
+        if line.is_empty() {
+            if !section.is_empty() {
+                sections.push(std::mem::take(&mut section));
+            }
+        } else if let Some(continuation) = line.strip_prefix(' ') {
+            if let Some((_, value)) = section.last_mut() {
+                value.push_str(continuation);
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            section.push((name.to_string(), value.trim_start().to_string()));
+        }
+    }
+    if !section.is_empty() {
+        sections.push(section);
+    }
+    let main_attributes = if sections.is_empty() {
+        Vec::new()
+    } else {
+        sections.remove(0)
+    };
+    JarManifest {
+        main_attributes,
+        sections,
+    }
+}
+
+impl JarManifest {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.main_attributes
+            .iter()
+            .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Lists the archive and parses the manifest and every class it contains. A manifest or
+/// classes that fail to decompress or parse are left out of the model but stay listed as
+/// entries.
+pub fn parse_zip(bytes: &[u8]) -> Result<BinaryFile<'_>, ParseError> {
+    let end_of_central_directory = extract_end_of_central_directory(bytes)?;
+    let entries = extract_zip_entries(bytes, &end_of_central_directory)?;
+
+    let manifest = entries
+        .iter()
+        .find(|entry| entry.name == MANIFEST_NAME)
+        .and_then(|entry| extract_zip_entry_data(entry).ok())
+        .map(|data| extract_jar_manifest(&data));
+
+    let mut classes = Vec::new();
+    let mut symbols = Vec::new();
+    let mut imports = Vec::new();
+    let mut exports = Vec::new();
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.name.ends_with(".class"))
+    {
+        let data = match extract_zip_entry_data(entry) {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+        let class = match extract_class_file(&data) {
+            Ok(class) => class,
+            Err(_) => continue,
+        };
+        add_class_to_model(&class, &mut symbols, &mut imports, &mut exports);
+        classes.push(ZipClass {
+            entry_name: entry.name.clone(),
+            class_name: class.name,
+            super_name: class.super_name,
+            method_count: class.methods.len(),
+            field_count: class.fields.len(),
+        });
+    }
+    // references between classes of the archive are not imports
+    let archive_classes: HashSet<&str> = classes
+        .iter()
+        .map(|class| class.class_name.as_str())
+        .collect();
+    let mut seen = HashSet::new();
+    imports.retain(|import| {
+        !archive_classes.contains(import.library.as_str())
+            && seen.insert((import.library.clone(), import.name.clone()))
+    });
+
+    let libraries = manifest
+        .as_ref()
+        .and_then(|manifest| manifest.attribute("Class-Path"))
+        .map(|class_path| class_path.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+
+    Ok(BinaryFile {
+        format: Format::Zip,
+        architecture: if classes.is_empty() {
+            Architecture::Unknown(0)
+        } else {
+            Architecture::Jvm
+        },
+        endianness: Endianness::Little,
+        bitness: Bitness::Bits32,
+        entry_point: 0,
+        sections: Vec::new(),
+        segments: Vec::new(),
+        symbols,
+        libraries,
+        imports,
+        exports,
+        details: FileDetails::Zip(FileInfoZip {
+            end_of_central_directory,
+            entries,
+            manifest,
+            classes,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stored entries given as name, content and recorded CRC-32
+    fn archive(files: &[(&str, &[u8], u32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut directory = Vec::new();
+        for &(name, data, crc) in files {
+            let offset = bytes.len() as u32;
+            let mut fields = Vec::new();
+            fields.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            fields.extend_from_slice(&crc.to_le_bytes());
+            fields.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            fields.extend_from_slice(&[0, 0]);
+
+            bytes.extend_from_slice(LOCAL_FILE_HEADER_SIGNATURE);
+            bytes.extend_from_slice(&fields);
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.extend_from_slice(data);
+
+            directory.extend_from_slice(CENTRAL_DIRECTORY_SIGNATURE);
+            directory.extend_from_slice(&[20, 0]);
+            directory.extend_from_slice(&fields);
+            directory.extend_from_slice(&[0; 10]);
+            directory.extend_from_slice(&offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+        let directory_offset = bytes.len() as u32;
+        bytes.extend_from_slice(&directory);
+        bytes.extend_from_slice(END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&directory_offset.to_le_bytes());
+        bytes.extend_from_slice(&[0, 0]);
+        bytes
+    }
+
+    fn zip_details<'a>(file: &'a BinaryFile<'a>) -> &'a FileInfoZip<'a> {
+        match &file.details {
+            FileDetails::Zip(zip) => zip,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn manifest_is_parsed() {
+        let manifest = b"Manifest-Version: 1.0\r\nClass-Path: a.jar b.jar\r\n";
+        let bytes = archive(&[(MANIFEST_NAME, manifest, crc32(manifest))]);
+        let file = parse_zip(&bytes).unwrap();
+        let zip = zip_details(&file);
+        assert_eq!(
+            zip.manifest.as_ref().unwrap().attribute("manifest-version"),
+            Some("1.0")
+        );
+        assert_eq!(file.libraries, ["a.jar", "b.jar"]);
+    }
+
+    #[test]
+    fn corrupt_manifest_does_not_fail_the_archive() {
+        let manifest = b"Manifest-Version: 1.0\r\n";
+        let bytes = archive(&[
+            (MANIFEST_NAME, manifest, crc32(manifest) ^ 1),
+            ("readme.txt", b"hello", crc32(b"hello")),
+        ]);
+        let file = parse_zip(&bytes).unwrap();
+        let zip = zip_details(&file);
+        assert!(zip.manifest.is_none());
+        assert_eq!(zip.entries.len(), 2);
+        assert!(file.libraries.is_empty());
+    }
+
+    #[test]
+    fn truncated_archives_are_rejected() {
+        let bytes = archive(&[("readme.txt", b"hello", crc32(b"hello"))]);
+        for length in 0..bytes.len() {
+            assert!(parse_zip(&bytes[..length]).is_err());
+        }
+    }
+
+    #[test]
+    fn central_directory_is_bounded() {
+        let bytes = archive(&[("readme.txt", b"hello", crc32(b"hello"))]);
+        let end = bytes.len() - END_OF_CENTRAL_DIRECTORY_SIZE;
+
+        // more entries than the central directory holds
+        let mut hostile = bytes.clone();
+        hostile[end + 10..end + 12].copy_from_slice(&0xFFFEu16.to_le_bytes());
+        assert!(matches!(
+            parse_zip(&hostile),
+            Err(ParseError::TruncatedHeader { .. })
+        ));
+
+        // central directory past the end of the file
+        let mut hostile = bytes.clone();
+        hostile[end + 16..end + 20].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        assert!(matches!(
+            parse_zip(&hostile),
+            Err(ParseError::OutOfRange { .. })
+        ));
+
+        // saturated fields without a ZIP64 locator are taken as they are
+        let mut hostile = bytes;
+        hostile[end + 12..end + 16].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        assert!(parse_zip(&hostile).is_err());
+    }
+
+    #[test]
+    fn entries_are_bounded_by_their_announced_size() {
+        // a stored entry whose data runs past the end of the file
+        let mut bytes = archive(&[("readme.txt", b"hello", crc32(b"hello"))]);
+        let directory = bytes.len() - END_OF_CENTRAL_DIRECTORY_SIZE - 46 - "readme.txt".len();
+        bytes[directory + 20..directory + 24].copy_from_slice(&0x7FFF_FFFFu32.to_le_bytes());
+        assert!(matches!(
+            parse_zip(&bytes),
+            Err(ParseError::OutOfRange { .. })
+        ));
+
+        // deflated data larger than the uncompressed size it announces
+        let entry = ZipEntry {
+            name: "bomb".to_string(),
+            version_made_by: 20,
+            version_needed: 20,
+            flags: 0,
+            compression_method: COMPRESSION_DEFLATED,
+            modification_time: 0,
+            modification_date: 0,
+            crc32: 0,
+            compressed_size: 6,
+            uncompressed_size: 2,
+            external_attributes: 0,
+            local_header_offset: 0,
+            extra_field: &[],
+            comment: String::new(),
+            // a fixed Huffman block of "aaaa"
+            compressed_data: &[0x4B, 0x4C, 0x4C, 0x4C, 0x04, 0x00],
+        };
+        assert!(matches!(
+            extract_zip_entry_data(&entry),
+            Err(ParseError::InvalidSize { .. })
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+pub const LOCAL_FILE_HEADER_SIGNATURE: &[u8] = b"PK\x03\x04";
+pub const CENTRAL_DIRECTORY_SIGNATURE: &[u8] = b"PK\x01\x02";
+pub const END_OF_CENTRAL_DIRECTORY_SIGNATURE: &[u8] = b"PK\x05\x06";
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE: &[u8] = b"PK\x06\x06";
+pub const ZIP64_LOCATOR_SIGNATURE: &[u8] = b"PK\x06\x07";
+
+pub const LOCAL_FILE_HEADER_SIZE: usize = 30;
+pub const CENTRAL_DIRECTORY_HEADER_SIZE: usize = 46;
+pub const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE: usize = 56;
+pub const ZIP64_LOCATOR_SIZE: usize = 20;
+/// The end of central directory record is followed by a comment of at most 65535 bytes
+pub const MAX_COMMENT_SIZE: usize = 0xFFFF;
+
+pub const ZIP64_EXTRA_FIELD: u16 = 0x0001;
+
+pub const COMPRESSION_STORED: u16 = 0;
+pub const COMPRESSION_DEFLATED: u16 = 8;
+
+pub const FLAG_ENCRYPTED: u16 = 0x0001;
+pub const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+pub const FLAG_UTF8: u16 = 0x0800;
+
+pub const MANIFEST_NAME: &str = "META-INF/MANIFEST.MF";
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ZipEndOfCentralDirectory<'a> {
+    pub disk_number: u32,
+    pub entry_count: u64,
+    pub central_directory_size: u64,
+    pub central_directory_offset: u64,
+    /// True when the sizes and offsets come from the ZIP64 record
+    pub zip64: bool,
+    pub comment: &'a [u8],
+}
+
+/// Central directory entry, sizes and offsets already widened by the ZIP64 extra field
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ZipEntry<'a> {
+    pub name: String,
+    pub version_made_by: u16,
+    pub version_needed: u16,
+    pub flags: u16,
+    pub compression_method: u16,
+    pub modification_time: u16,
+    pub modification_date: u16,
+    pub crc32: u32,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub external_attributes: u32,
+    pub local_header_offset: u64,
+    pub extra_field: &'a [u8],
+    pub comment: String,
+    /// Compressed bytes following the local header
+    pub compressed_data: &'a [u8],
+}
+
+/// Attributes of a JAR manifest, the main section first then one section per entry
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct JarManifest {
+    pub main_attributes: Vec<(String, String)>,
+    pub sections: Vec<Vec<(String, String)>>,
+}
+
+/// Class stored in an archive, classes are decompressed again when disassembled
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ZipClass {
+    pub entry_name: String,
+    pub class_name: String,
+    pub super_name: Option<String>,
+    pub method_count: usize,
+    pub field_count: usize,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FileInfoZip<'a> {
+    pub end_of_central_directory: ZipEndOfCentralDirectory<'a>,
+    pub entries: Vec<ZipEntry<'a>>,
+    pub manifest: Option<JarManifest>,
+    pub classes: Vec<ZipClass>,
+}